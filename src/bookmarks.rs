@@ -0,0 +1,277 @@
+//! Things saved for later (`a` on anything), kept between runs in the data directory in named
+//! lists and shown on the bookmarks page (`w`)
+
+use std::fs::read;
+use std::sync::Mutex;
+
+use curl::easy::Easy;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Span, Spans, Text};
+use ratatui::widgets::{ListItem, Paragraph, Wrap};
+use serde::{Deserialize, Serialize};
+use simd_json::{from_slice, to_vec};
+
+use crate::config::DEFAULT_BOOKMARK_LIST;
+use crate::structs::Node;
+use crate::utils::{base_url, data_path, now, time_ago, write_data};
+
+/// File in the data directory the bookmarks are kept in
+const FILE: &str = "bookmarks.json";
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct Bookmark {
+	/// What's entered when it's opened
+	pub node: Node,
+	/// Its title when it was saved
+	pub title: String,
+	/// Its info when it was saved
+	pub info: String,
+	/// When it was saved, in seconds since the unix epoch
+	pub time: u64,
+}
+impl Bookmark {
+	/// Where it is on youtube (or elsewhere, for links), if it's somewhere
+	pub fn url(&self, easy: &mut Easy) -> Option<String> {
+		Some(match &self.node {
+			Node::Video(id) | Node::Short(id, _) => [base_url(), "/watch?v=", id].concat(),
+			Node::Chapter(id, time, _) | Node::Timestamp(id, time) => {
+				format!("{}/watch?v={id}&t={}s", base_url(), time / 1000)
+			}
+			Node::Game(id, _) | Node::Channel(id, _) => [base_url(), "/channel/", id].concat(),
+			Node::Playlist(id) => [base_url(), "/playlist?list=", id].concat(),
+			Node::Search(query, params) => [
+				base_url(),
+				"/results?search_query=",
+				&easy.url_encode(query.as_bytes()),
+				&params
+					.as_ref()
+					.map_or(String::new(), |params| ["&sp=", params].concat()),
+			]
+			.concat(),
+			Node::Hashtag(tag, _) => [base_url(), "/hashtag/", tag].concat(),
+			Node::Url(url) => url.clone(),
+			_ => return None,
+		})
+	}
+}
+
+/// A user-named list of bookmarks
+#[derive(Clone, Deserialize, Serialize)]
+pub struct BookmarkList {
+	pub name: String,
+	/// Oldest first
+	pub bookmarks: Vec<Bookmark>,
+}
+
+/// Loaded from the file the first time it's needed, in the order the lists were made
+static BOOKMARKS: Mutex<Option<Vec<BookmarkList>>> = Mutex::new(None);
+
+/// Runs `f` on the lists, loading them first if they haven't been. Saves them afterwards if `f`
+/// returns true.
+fn with_lists<T>(f: impl FnOnce(&mut Vec<BookmarkList>) -> (T, bool)) -> T {
+	let mut lists = BOOKMARKS.lock().unwrap_or_else(|error| error.into_inner());
+
+	let lists = lists.get_or_insert_with(|| {
+		read(data_path(FILE))
+			.ok()
+			.and_then(|mut file| from_slice(&mut file).ok())
+			.unwrap_or_default()
+	});
+
+	let (result, changed) = f(lists);
+
+	if changed {
+		write_data(
+			FILE,
+			&to_vec(lists).expect("Should be able to serialize bookmarks"),
+		);
+	}
+
+	result
+}
+
+/// Whether a node means anything outside of the page it's on, and will keep meaning it, so it can
+/// be saved. Continuation tokens (comments, chats, transcripts) expire, so what has one can't.
+/// Channels and playlists can't be opened yet, so they can't either.
+pub fn can_save(node: &Node) -> bool {
+	matches!(
+		node,
+		Node::Video(_)
+			| Node::Short(..)
+			| Node::Game(..)
+			| Node::Search(..)
+			| Node::Chapter(..)
+			| Node::Timestamp(..)
+			| Node::Hashtag(..)
+			| Node::Url(_)
+	)
+}
+
+/// Saves a node to a list (`DEFAULT_BOOKMARK_LIST` if it's empty), making the list if it doesn't
+/// exist. Returns false if it was already in the list.
+pub fn add(list: &str, node: Node, title: String, info: String) -> bool {
+	let name = if list.is_empty() {
+		DEFAULT_BOOKMARK_LIST
+	} else {
+		list
+	};
+
+	with_lists(|lists| {
+		let list = match lists.iter().position(|list| list.name == name) {
+			Some(index) => &mut lists[index],
+			None => {
+				lists.push(BookmarkList {
+					name: name.to_owned(),
+					bookmarks: Vec::new(),
+				});
+
+				lists.last_mut().expect("A list was just added")
+			}
+		};
+
+		if list.bookmarks.iter().any(|bookmark| bookmark.node == node) {
+			return (false, false);
+		}
+
+		list.bookmarks.push(Bookmark {
+			node,
+			title,
+			info,
+			time: now(),
+		});
+
+		(true, true)
+	})
+}
+
+/// The names of the lists
+pub fn names() -> Vec<String> {
+	with_lists(|lists| (lists.iter().map(|list| list.name.clone()).collect(), false))
+}
+
+/// A list, or all of them
+pub fn lists(name: Option<&str>) -> Vec<BookmarkList> {
+	with_lists(|lists| {
+		(
+			lists
+				.iter()
+				.filter(|list| name.is_none_or(|name| list.name == name))
+				.cloned()
+				.collect(),
+			false,
+		)
+	})
+}
+
+/// Removes the bookmark at `index` in a list
+pub fn remove(name: &str, index: usize) {
+	with_lists(
+		|lists| match lists.iter_mut().find(|list| list.name == name) {
+			Some(list) if index < list.bookmarks.len() => {
+				list.bookmarks.remove(index);
+
+				((), true)
+			}
+			_ => ((), false),
+		},
+	);
+}
+
+/// Removes a whole list
+pub fn remove_list(name: &str) {
+	with_lists(|lists| {
+		let length = lists.len();
+		lists.retain(|list| list.name != name);
+
+		((), lists.len() != length)
+	});
+}
+
+/// The bookmarks page: the lists, with how many bookmarks they have
+pub fn lists_widgets<'a>() -> (Vec<ListItem<'a>>, Vec<(Paragraph<'a>, Node)>) {
+	with_lists(|lists| {
+		let mut titles = Vec::with_capacity(lists.len());
+		let mut info = Vec::with_capacity(lists.len());
+
+		for list in lists.iter() {
+			titles.push(ListItem::new(Spans(vec![
+				list.name.clone().into(),
+				Span::styled(
+					format!(" ({})", list.bookmarks.len()),
+					Style::default().fg(Color::DarkGray),
+				),
+			])));
+			info.push((
+				Paragraph::new(vec![
+					Spans::from(match list.bookmarks.last() {
+						Some(last) => ["Last added ", &time_ago(last.time).to_lowercase()].concat(),
+						None => "Empty".to_owned(),
+					}),
+					Spans::default(),
+					Spans::from("Enter to open, d to delete the list"),
+				])
+				.wrap(Wrap { trim: false }),
+				Node::BookmarkList(list.name.clone()),
+			));
+		}
+
+		if lists.is_empty() {
+			titles.push(ListItem::new("No bookmarks yet"));
+			info.push((
+				Paragraph::new("a on anything to save it for later").wrap(Wrap { trim: false }),
+				Node::None,
+			));
+		}
+
+		((titles, info), false)
+	})
+}
+
+/// A list's page: its bookmarks as they were when they were saved, oldest first. Each one's node
+/// is the one that was saved, so entering it works like it did on its page.
+pub fn list_widgets<'a>(name: &str) -> (Vec<ListItem<'a>>, Vec<(Paragraph<'a>, Node)>) {
+	with_lists(|lists| {
+		let bookmarks = lists
+			.iter()
+			.find(|list| list.name == name)
+			.map_or(&[][..], |list| &list.bookmarks);
+
+		let mut titles = Vec::with_capacity(bookmarks.len());
+		let mut info = Vec::with_capacity(bookmarks.len());
+
+		for bookmark in bookmarks {
+			let mut title = Text::from(bookmark.title.clone());
+			// Spaced like they are on other pages
+			title.lines.push(Spans::default());
+
+			titles.push(ListItem::new(title));
+
+			let mut text = Text::from(bookmark.info.clone());
+			text.lines.push(Spans::default());
+			text.lines.push(Spans::from(Span::styled(
+				[
+					"Saved ",
+					&time_ago(bookmark.time).to_lowercase(),
+					", d to delete it",
+				]
+				.concat(),
+				Style {
+					add_modifier: Modifier::ITALIC,
+					..Style::default()
+				},
+			)));
+
+			info.push((
+				Paragraph::new(text).wrap(Wrap { trim: false }),
+				bookmark.node.clone(),
+			));
+		}
+
+		if bookmarks.is_empty() {
+			titles.push(ListItem::new("Nothing saved here yet"));
+			info.push((Paragraph::new(""), Node::None));
+		}
+
+		((titles, info), false)
+	})
+}
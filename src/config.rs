@@ -5,6 +5,7 @@ use ratatui::layout::Alignment;
 use ratatui::widgets::BorderType;
 
 use crate::structs::*;
+use crate::utils::locale;
 
 pub enum Selector<T> {
 	Highest,
@@ -98,11 +99,82 @@ pub fn video_player(
 	audio_url: String,
 	// `None` if no subtitle language is set (above) or if there are no matching subtitles
 	subtitle_url: Option<String>,
+	// Where to start playing from, in milliseconds (when playing from a chapter)
+	start: u64,
+	// A file with the video's chapters in ffmetadata format, `None` if it has none
+	chapters_file: Option<std::path::PathBuf>,
+	// Where to write a watch later file (with a `start=<seconds>` line) when the player quits, so
+	// the video can be resumed from there
+	watch_later_directory: std::path::PathBuf,
 ) -> (
 	impl AsRef<std::ffi::OsStr>,
 	impl IntoIterator<Item = impl AsRef<std::ffi::OsStr>>,
 ) {
-	("mpv", [format!("--audio-file={audio_url}"), video_url])
+	let mut args = vec![
+		format!("--audio-file={audio_url}"),
+		format!("--start={}", start as f64 / 1000.0),
+		"--save-position-on-quit".to_owned(),
+		format!(
+			"--watch-later-directory={}",
+			watch_later_directory.display()
+		),
+	];
+
+	if let Some(chapters_file) = chapters_file {
+		args.push(format!("--chapters-file={}", chapters_file.display()));
+	}
+
+	args.push(video_url);
+
+	("mpv", args)
+}
+
+/// The player used for shorts, takes the same arguments as `video_player` above (without the start
+/// and chapters). Shorts are played back to back, each one loops until the player is closed (then
+/// you can pick the next one). The default is an example for mpv, with a window sized for vertical
+/// video.
+#[allow(unused_variables)]
+pub fn shorts_player(
+	video_url: String,
+	audio_url: String,
+	subtitle_url: Option<String>,
+) -> (
+	impl AsRef<std::ffi::OsStr>,
+	impl IntoIterator<Item = impl AsRef<std::ffi::OsStr>>,
+) {
+	(
+		"mpv",
+		[
+			format!("--audio-file={audio_url}"),
+			"--loop-file=inf".to_owned(),
+			"--autofit=40%x90%".to_owned(),
+			video_url,
+		],
+	)
+}
+
+/// The player used alongside chat replays, takes the same arguments as `shorts_player` above plus
+/// the path of a socket. It's run in the background, and should accept mpv's JSON IPC commands on
+/// the socket so the replay can follow it (other players can be used, but the replay will have to
+/// be seeked manually).
+#[allow(unused_variables)]
+pub fn replay_player(
+	video_url: String,
+	audio_url: String,
+	subtitle_url: Option<String>,
+	socket: std::path::PathBuf,
+) -> (
+	impl AsRef<std::ffi::OsStr>,
+	impl IntoIterator<Item = impl AsRef<std::ffi::OsStr>>,
+) {
+	(
+		"mpv",
+		[
+			format!("--audio-file={audio_url}"),
+			format!("--input-ipc-server={}", socket.display()),
+			video_url,
+		],
+	)
 }
 
 /// The command used to play streams, similar to `video_player` above. `hls_manifest_url` is the
@@ -120,6 +192,37 @@ pub fn stream_player(
 	("ffplay", [hls_manifest_url])
 }
 
+/// The command used to open links to other websites (and hashtags, for now) in a browser, similar
+/// to `video_player` above.
+pub fn url_opener(
+	url: String,
+) -> (
+	impl AsRef<std::ffi::OsStr>,
+	impl IntoIterator<Item = impl AsRef<std::ffi::OsStr>>,
+) {
+	("xdg-open", [url])
+}
+
+/// Where the program keeps what it remembers between runs (i.e. search history), can be
+/// overridden with `--data-dir`. `$XDG_DATA_HOME/ytui` or `~/.local/share/ytui` by default.
+pub fn data_directory() -> std::path::PathBuf {
+	std::env::var_os("XDG_DATA_HOME")
+		.map(std::path::PathBuf::from)
+		.or_else(|| {
+			std::env::var_os("HOME").map(|home| std::path::Path::new(&home).join(".local/share"))
+		})
+		.unwrap_or_else(std::env::temp_dir)
+		.join("ytui")
+}
+
+/// How long the decipher functions from youtube's player are allowed to run before giving up. If
+/// the `n` function fails the (throttled) URL is used as is, if the `sig` function fails the video
+/// won't play.
+pub const JS_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// How many bytes V8's heap can grow to while running a decipher function before it's stopped
+pub const JS_MEMORY_LIMIT: usize = 256 * 1024 * 1024;
+
 // ----------------
 // The following settings are for the program's style.
 // ----------------
@@ -132,6 +235,111 @@ pub const TITLE_ALIGNMENT: Alignment = Alignment::Left;
 /// Can be `Plain`, `Thick`, `Double` or `Rounded`.
 pub const BORDER_TYPE: BorderType = BorderType::Plain;
 
+/// Where requests are sent, can be overridden with `--base-url` (i.e. to use a mock server).
+pub const BASE_URL: &str = "https://www.youtube.com";
+
+/// How many recent requests (and their responses) to keep for the request inspector (`i`).
+pub const REQUEST_LOG_LENGTH: usize = 20;
+
+/// Responses bigger than this (in bytes) aren't kept for the request inspector, so they can't be
+/// saved (i.e. the player JS).
+pub const REQUEST_LOG_MAX_RESPONSE: usize = 2 * 1024 * 1024;
+
+/// How often to poll live chat, if youtube doesn't say.
+pub const LIVE_CHAT_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// How many live chat messages to keep, older ones are dropped.
+pub const LIVE_CHAT_SCROLLBACK: usize = 1000;
+
+/// How often a chat replay checks where the player is.
+pub const CHAT_REPLAY_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// How far (in milliseconds) the player can get past the loaded chat replay before it's requested
+/// again from the player's position, instead of loading the messages in between.
+pub const CHAT_REPLAY_SEEK_THRESHOLD: u64 = 60_000;
+
+/// How many requests can be made looking for a comment that matches the filter, when none of the
+/// comments on the page do. Set to 1 to only filter the comments that get loaded anyway.
+pub const COMMENT_FILTER_SEARCH_LIMIT: usize = 10;
+
+/// Put before the names of replies shown under their comments in the comment section's tree mode
+pub const REPLY_INDENT: &str = "    ";
+
+/// How long to wait before each request when exporting comments, so youtube doesn't rate limit us
+pub const EXPORT_REQUEST_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// How many times to try a request again when exporting comments, if youtube rate limits us or
+/// has an error, waiting twice as long as the last time before each one
+pub const EXPORT_RETRIES: u32 = 4;
+
+/// Where search suggestions come from, ignored if `--base-url` is given (then they come from
+/// there).
+pub const SUGGESTIONS_URL: &str = "https://suggestqueries-clients6.youtube.com";
+
+/// Whether to show suggestions from youtube under the search box, as you type. Set to false to
+/// only suggest your own past searches.
+pub const SEARCH_SUGGESTIONS: bool = true;
+
+/// How long typing has to stop for before suggestions are requested.
+pub const SUGGESTION_DELAY: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// How many suggestions are shown under the search box, past searches come first.
+pub const SUGGESTION_COUNT: usize = 10;
+
+/// Where a video was left off isn't kept if it's closer than this (in milliseconds) to its start
+/// or end, it's played from the start next time.
+pub const RESUME_MARGIN: u64 = 10_000;
+
+/// Put before the titles of videos that have been watched (which are dimmed).
+pub const WATCHED_MARKER: &str = "✓ ";
+
+/// How wide the bars showing how much of a video has been watched are.
+pub const PROGRESS_BAR_WIDTH: usize = 20;
+
+/// The list things are bookmarked to (`a`) if no list is given.
+pub const DEFAULT_BOOKMARK_LIST: &str = "Watch later";
+
+/// How wide a bookmark's info is wrapped when it's saved, titles are cut off at this width.
+pub const BOOKMARK_WIDTH: u16 = 80;
+
+/// How many lines of a bookmark's info are saved.
+pub const BOOKMARK_INFO_HEIGHT: u16 = 100;
+
+/// Language youtube's text is in (`hl`), i.e. `Some("ja")` or `Some("en-GB")`. `None` lets youtube
+/// pick, usually English. Can be changed with `--language`, or the switcher (Ctrl+L).
+pub const LANGUAGE: Option<&str> = None;
+
+/// Country youtube's feeds are for (`gl`), i.e. `Some("JP")`. `None` lets youtube pick from your IP
+/// address. Can be changed with `--region`, or the switcher (Ctrl+L).
+pub const REGION: Option<&str> = None;
+
+/// Time zone times are shown in, i.e. `Some("Europe/London")`. Can be changed with `--time-zone`.
+pub const TIME_ZONE: Option<&str> = None;
+
+/// How many minutes ahead of UTC the time zone is, i.e. `Some(60)`. Can be changed with
+/// `--utc-offset`.
+pub const UTC_OFFSET_MINUTES: Option<i16> = None;
+
+/// Offered in the region and language switcher (Ctrl+L), as the region then the language.
+pub const LOCALES: &[&str] = &[
+	"US en",
+	"GB en-GB",
+	"CA fr-CA",
+	"DE de",
+	"FR fr",
+	"ES es",
+	"BR pt-BR",
+	"MX es-419",
+	"IN hi",
+	"JP ja",
+	"KR ko",
+	"RU ru",
+];
+
+/// Whether searches are added to the search history (`y`) to begin with, it can be turned on and
+/// off from there.
+pub const RECORD_SEARCH_HISTORY: bool = true;
+
 // ----------------
 // The following settings are for API request options, changing some of these could cause the
 // server to return errors, which may cause this program to panic. Edit them at your own risk.
@@ -167,9 +375,13 @@ impl Default for MainAppWebInfo {
 
 impl Default for Client {
 	fn default() -> Self {
+		// Set from `LANGUAGE`, `REGION`, `TIME_ZONE` and `UTC_OFFSET_MINUTES` above, unless they've
+		// been changed while running
+		let locale = locale();
+
 		Self {
-			hl: None,
-			gl: None,
+			hl: locale.language,
+			gl: locale.region,
 			remoteHost: None,
 			deviceMake: None,
 			deviceModel: None,
@@ -194,11 +406,11 @@ impl Default for Client {
 			screenHeightPoints: None,
 			screenPixelDensity: None,
 			screenDensityFloat: None,
-			utcOffsetMinutes: None,
+			utcOffsetMinutes: locale.utc_offset_minutes,
 			userInterfaceTheme: None,
 			// Could be `Some(MainAppWebInfo::default())`
 			mainAppWebInfo: None,
-			timeZone: None,
+			timeZone: locale.time_zone,
 		}
 	}
 }
@@ -303,6 +515,57 @@ impl Default for NextRequest {
 	}
 }
 
+// The next two are for shorts
+
+impl Default for ReelItemWatchRequest {
+	fn default() -> Self {
+		Self {
+			context: Context::default(),
+			// This is set by the program
+			playerRequest: PlayerRequest {
+				videoId: String::new(),
+			},
+			// This is what youtube sends, I don't know what it means
+			params: Some("CAUwAg%3D%3D"),
+			// We only need the sequence, the video is requested when it's played
+			disablePlayerResponse: Some(true),
+		}
+	}
+}
+
+impl Default for ReelWatchSequenceRequest {
+	fn default() -> Self {
+		Self {
+			context: Context::default(),
+			// This is set by the program
+			sequenceParams: String::new(),
+		}
+	}
+}
+
+impl Default for LiveChatReplayRequest {
+	fn default() -> Self {
+		Self {
+			context: Context::default(),
+			// These two are set by the program
+			continuation: String::new(),
+			currentPlayerState: CurrentPlayerState {
+				playerOffsetMs: String::new(),
+			},
+		}
+	}
+}
+
+impl Default for ResolveUrlRequest {
+	fn default() -> Self {
+		Self {
+			context: Context::default(),
+			// Set by the program
+			url: String::new(),
+		}
+	}
+}
+
 impl Default for SearchRequest {
 	fn default() -> Self {
 		Self {
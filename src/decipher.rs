@@ -0,0 +1,290 @@
+//! Runs the decipher functions taken from youtube's player JS in V8 on a separate thread, with a
+//! limit on its heap and how long they can run, so a broken player can't hang (or eat all the
+//! memory of) the whole program
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::{fmt, thread};
+
+use curl::easy::Easy;
+use deno_core::{v8, JsRuntime, RuntimeOptions};
+use simd_json::to_string;
+
+use crate::config::{JS_MEMORY_LIMIT, JS_TIMEOUT};
+use crate::utils::{base_url, debug_log, request_get};
+
+/// Why a decipher function call failed
+pub enum DecipherError {
+	/// The function took longer than `JS_TIMEOUT`
+	Timeout,
+	/// V8's heap grew past `JS_MEMORY_LIMIT` while the function was running
+	MemoryLimit,
+	/// The JS threw an exception (or didn't compile), property is the message
+	Js(String),
+	/// The worker thread stopped unexpectedly
+	Crashed,
+}
+impl fmt::Display for DecipherError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			DecipherError::Timeout => write!(f, "timed out after {:?}", JS_TIMEOUT),
+			DecipherError::MemoryLimit => {
+				write!(f, "used more than {} bytes of memory", JS_MEMORY_LIMIT)
+			}
+			DecipherError::Js(message) => write!(f, "JS error: {message}"),
+			DecipherError::Crashed => write!(f, "JS worker thread crashed"),
+		}
+	}
+}
+
+/// A worker thread: where calls are sent, where their results come back, and its isolate (to
+/// terminate whatever it's running if it has to be abandoned)
+type Worker = (
+	Sender<(&'static str, String)>,
+	Receiver<Result<String, DecipherError>>,
+	Option<v8::IsolateHandle>,
+);
+
+/// Terminates whatever the isolate is running if it's still running after `JS_TIMEOUT`. Dropping
+/// the returned sender stops the timer, the flag is set if it went off.
+fn start_timer(isolate: v8::IsolateHandle) -> (Sender<()>, Arc<AtomicBool>) {
+	let (done, timer) = channel::<()>();
+	let timed_out = Arc::new(AtomicBool::new(false));
+	{
+		let timed_out = timed_out.clone();
+
+		thread::spawn(move || {
+			if timer.recv_timeout(JS_TIMEOUT) == Err(RecvTimeoutError::Timeout) {
+				timed_out.store(true, Ordering::Relaxed);
+				isolate.terminate_execution();
+			}
+		});
+	}
+
+	(done, timed_out)
+}
+
+/// Starts a thread that evaluates `source`, then calls functions in it as they're requested.
+/// Evaluating the source or a function that runs for too long or fills the heap is terminated by
+/// V8, after which the thread stops (it'll be started again for the next call).
+fn spawn_worker(source: String) -> Worker {
+	let (request_sender, request_receiver) = channel::<(&'static str, String)>();
+	let (response_sender, response_receiver) = channel();
+	let (isolate_sender, isolate_receiver) = channel();
+
+	thread::spawn(move || {
+		let mut runtime = JsRuntime::new(RuntimeOptions {
+			create_params: Some(v8::CreateParams::default().heap_limits(0, JS_MEMORY_LIMIT)),
+			..RuntimeOptions::default()
+		});
+		let isolate = runtime.v8_isolate().thread_safe_handle();
+		let _ = isolate_sender.send(isolate.clone());
+
+		// Terminate the function when the heap's nearly full, instead of V8 aborting the whole
+		// program. The limit's raised a bit, so there's room to stop.
+		let out_of_memory = Arc::new(AtomicBool::new(false));
+		{
+			let isolate = isolate.clone();
+			let out_of_memory = out_of_memory.clone();
+
+			runtime.add_near_heap_limit_callback(move |current, _| {
+				out_of_memory.store(true, Ordering::Relaxed);
+				isolate.terminate_execution();
+
+				current + current / 4
+			});
+		}
+
+		// What a failed script was stopped by
+		let error = |timed_out: &AtomicBool, error: String| {
+			if out_of_memory.load(Ordering::Relaxed) {
+				DecipherError::MemoryLimit
+			} else if timed_out.load(Ordering::Relaxed) {
+				DecipherError::Timeout
+			} else {
+				DecipherError::Js(error)
+			}
+		};
+
+		// The player's code could hang before the functions are even defined
+		let (done, timed_out) = start_timer(isolate.clone());
+		let loaded = runtime.execute_script("decipher.js", source);
+		drop(done);
+
+		if let Err(loading_error) = loaded {
+			// This will be the result of the first call
+			let _ = response_sender.send(Err(error(&timed_out, loading_error.to_string())));
+			return;
+		}
+
+		for (function, argument) in request_receiver {
+			let (done, timed_out) = start_timer(isolate.clone());
+
+			let result = runtime
+				.execute_script(
+					"call",
+					format!(
+						"{function}({})",
+						to_string(&argument).expect("Should be able to serialize a string")
+					),
+				)
+				.map(|value| {
+					let scope = &mut runtime.handle_scope();
+
+					v8::Local::new(scope, &value).to_rust_string_lossy(scope)
+				})
+				.map_err(|call_error| error(&timed_out, call_error.to_string()));
+			drop(done);
+
+			// A terminated isolate can't be used again
+			let failed = result.is_err();
+
+			// Stop if the result isn't wanted anymore
+			if response_sender.send(result).is_err() || failed {
+				break;
+			}
+		}
+	});
+
+	(
+		request_sender,
+		response_receiver,
+		// `None` if the thread stopped before making the isolate
+		isolate_receiver.recv().ok(),
+	)
+}
+
+/// The `n` and `sig` functions from a version of youtube's player
+pub struct Decipherer {
+	/// Version of the player these functions are from, used in error messages
+	pub player_version: String,
+	/// JS defining the functions, kept to restart the worker if it fails
+	source: String,
+	/// `None` if the worker hasn't been started yet or was abandoned
+	worker: Option<Worker>,
+}
+impl Decipherer {
+	/// Creates a new `Decipherer`. `source` should define an `f` (`n`) and an `s` (`sig`) function.
+	pub fn new(player_version: String, source: String) -> Self {
+		Self {
+			player_version,
+			source,
+			worker: None,
+		}
+	}
+
+	/// Downloads the player used by the given watch page and takes the decipher functions out of it
+	pub fn from_watch_page(easy: &mut Easy, watch_page: &str) -> Self {
+		// Find the current name of the player JS
+		let start = watch_page
+			.find("c=\"/")
+			.expect("Video page should have the /s/player/.../base.js")
+			+ 3;
+		let player_path = &watch_page[start
+			..start
+				+ watch_page[start..]
+					.find('"')
+					.expect("Video page should have the /s/player/.../base.js")];
+
+		// Download the player
+		let mut player = unsafe {
+			String::from_utf8_unchecked(request_get(easy, &[base_url(), player_path].concat()))
+		};
+
+		// Find the current n decryption function in the player js
+		let n_start = player
+			.find("n(a){var b=a.sp")
+			.expect("Should be an `n` decryption function")
+			- 9;
+
+		// Use a determined function name ('f')
+		player.replace_range(n_start..n_start + 1, "f");
+
+		// Find the signature decipher function
+		let sig_start = player
+			.find("a=a.split(\"\"")
+			.expect("Should be a `sig` decryption function")
+			- 14;
+
+		// Use a determined function name ('s')
+		player.replace_range(sig_start..sig_start + 1, "s");
+
+		Self::new(
+			// The version is the directory after `/s/player/`
+			player_path
+				.split('/')
+				.nth(3)
+				.unwrap_or("unknown")
+				.to_owned(),
+			[
+				// `n` function
+				&player[n_start
+					..n_start
+						+ player[n_start..]
+							.find("\ng")
+							.expect("`n` function should end with this")],
+				// I don't think this changes. (between 'VF=' and '};g.W')
+				"VF={RV:function(a,b){var \
+				 c=a[0];a[0]=a[b%a.length];a[b%a.length]=c},p4:function(a,b){a.splice(0,b)},wa:\
+				 function(a){a.reverse()}};",
+				// `sig` function
+				&player[sig_start
+					..sig_start
+						+ player[sig_start..]
+							.find(";\n")
+							.expect("`sig` function should end with this")],
+			]
+			.concat(),
+		)
+	}
+
+	/// Calls the given function in the worker thread, starting it if necessary. Failures are
+	/// logged, with the player's version.
+	fn call(&mut self, function: &'static str, argument: String) -> Result<String, DecipherError> {
+		let (sender, receiver, isolate) = self
+			.worker
+			.get_or_insert_with(|| spawn_worker(self.source.clone()));
+
+		let result = if sender.send((function, argument)).is_err() {
+			Err(DecipherError::Crashed)
+		} else {
+			// V8 terminates the function after `JS_TIMEOUT`, this is in case that doesn't work
+			// (the source and the function each get `JS_TIMEOUT`)
+			match receiver.recv_timeout(JS_TIMEOUT * 3) {
+				Ok(result) => result,
+				Err(RecvTimeoutError::Disconnected) => Err(DecipherError::Crashed),
+				Err(RecvTimeoutError::Timeout) => {
+					// Try again to stop it, so the thread ends instead of running forever
+					if let Some(isolate) = isolate {
+						isolate.terminate_execution();
+					}
+
+					Err(DecipherError::Timeout)
+				}
+			}
+		};
+
+		// The worker stops after a failure, it'll be restarted on the next call
+		if let Err(error) = &result {
+			debug_log(&format!(
+				"Decipher function `{function}` from player {} failed: {error}",
+				self.player_version
+			));
+
+			self.worker = None;
+		}
+
+		result
+	}
+
+	/// Solves an `n` challenge
+	pub fn n(&mut self, n: &str) -> Result<String, DecipherError> {
+		self.call("f", n.to_owned())
+	}
+
+	/// Runs the `sig` function on the given string
+	pub fn signature(&mut self, signature: String) -> Result<String, DecipherError> {
+		self.call("s", signature)
+	}
+}
@@ -0,0 +1,324 @@
+//! Exporting a whole comment section, with replies, as JSON or CSV (`ytui comments` and `x` in a
+//! comment section), and bookmarks (`x` on the bookmarks page)
+
+use std::borrow::Cow;
+use std::io::Write;
+use std::thread::sleep;
+
+use curl::easy::Easy;
+use serde::Serialize;
+use simd_json::{from_slice, to_string};
+
+use crate::bookmarks::BookmarkList;
+use crate::config::{EXPORT_REQUEST_INTERVAL, EXPORT_RETRIES};
+use crate::structs::{
+	BrowseRequest, Comment, CommentsResponse, ContinuationItem, ContinuationResponse, Exported,
+	ExportedComment, Node,
+};
+use crate::utils::{api_url, captured, request_post};
+
+/// Formats comments and bookmarks can be exported in
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+	/// One object per line, so an interrupted export can be appended to
+	Json,
+	Csv,
+}
+impl Format {
+	/// From its name, or a file's extension
+	pub fn new(name: &str) -> Option<Format> {
+		match name.rsplit('.').next()?.to_lowercase().as_str() {
+			"json" | "jsonl" => Some(Format::Json),
+			"csv" => Some(Format::Csv),
+			_ => None,
+		}
+	}
+}
+
+/// The first row of a CSV export, in the same order as `ExportedComment`'s fields
+const CSV_HEADER: &str = "id,parent,author,channel_id,text,likes,replies,pinned,hearted,published";
+
+/// Quotes a CSV field if it has any commas, quotes or newlines
+fn csv_field(field: &str) -> Cow<'_, str> {
+	if field.contains([',', '"', '\n', '\r']) {
+		["\"", &field.replace('"', "\"\""), "\""].concat().into()
+	} else {
+		field.into()
+	}
+}
+
+fn write_comment(
+	output: &mut impl Write,
+	format: Format,
+	comment: &ExportedComment,
+) -> Result<(), String> {
+	match format {
+		Format::Json => writeln!(
+			output,
+			"{}",
+			to_string(comment).expect("Should be able to serialize comment")
+		),
+		Format::Csv => writeln!(
+			output,
+			"{}",
+			[
+				&comment.id,
+				comment.parent.as_deref().unwrap_or_default(),
+				&comment.author,
+				&comment.channel_id,
+				&comment.text,
+				&comment.likes,
+				&comment.replies.to_string(),
+				&comment.pinned.to_string(),
+				&comment.hearted.to_string(),
+				&comment.published,
+			]
+			.map(csv_field)
+			.join(",")
+		),
+	}
+	.map_err(written)
+}
+
+/// The first row of a CSV export of bookmarks, in the same order as `ExportedBookmark`'s fields
+/// (without the node)
+const BOOKMARK_CSV_HEADER: &str = "list,title,info,url,saved";
+
+/// A bookmark as it's exported
+#[derive(Serialize)]
+struct ExportedBookmark<'a> {
+	list: &'a str,
+	title: &'a str,
+	info: &'a str,
+	/// Where it is on youtube, empty if it isn't anywhere
+	url: String,
+	/// In seconds since the unix epoch
+	saved: u64,
+	/// So it can be imported again
+	node: &'a Node,
+}
+
+/// Exports bookmarks to `output`, oldest first in each list, returning how many there were
+pub fn export_bookmarks(
+	easy: &mut Easy,
+	lists: &[BookmarkList],
+	format: Format,
+	output: &mut impl Write,
+) -> Result<usize, String> {
+	let mut count = 0;
+
+	if format == Format::Csv {
+		writeln!(output, "{BOOKMARK_CSV_HEADER}").map_err(written)?;
+	}
+
+	for list in lists {
+		for bookmark in &list.bookmarks {
+			let bookmark = ExportedBookmark {
+				list: &list.name,
+				title: &bookmark.title,
+				info: &bookmark.info,
+				url: bookmark.url(easy).unwrap_or_default(),
+				saved: bookmark.time,
+				node: &bookmark.node,
+			};
+
+			match format {
+				Format::Json => writeln!(
+					output,
+					"{}",
+					to_string(&bookmark).expect("Should be able to serialize bookmark")
+				),
+				Format::Csv => writeln!(
+					output,
+					"{}",
+					[
+						bookmark.list,
+						bookmark.title,
+						bookmark.info,
+						&bookmark.url,
+						&bookmark.saved.to_string(),
+					]
+					.map(csv_field)
+					.join(",")
+				),
+			}
+			.map_err(written)?;
+
+			count += 1;
+		}
+	}
+
+	output.flush().map_err(written)?;
+
+	Ok(count)
+}
+
+/// The error for when the export can't be written
+fn written(error: std::io::Error) -> String {
+	format!("Couldn't write the export: {error}")
+}
+
+/// Requests comments or replies, after waiting `EXPORT_REQUEST_INTERVAL` so youtube doesn't rate
+/// limit us. If it does anyway (or it can't be reached, or has an error), it's tried again up to
+/// `EXPORT_RETRIES` times, waiting twice as long each time.
+fn request(easy: &mut Easy, name: &str, endpoint: &str, token: &str) -> Result<Vec<u8>, String> {
+	let mut wait = EXPORT_REQUEST_INTERVAL;
+	let mut retries = 0;
+
+	loop {
+		sleep(wait);
+
+		let response = request_post(
+			easy,
+			&api_url(endpoint),
+			&BrowseRequest {
+				continuation: Some(token.to_owned()),
+				..BrowseRequest::default()
+			},
+		);
+
+		match easy.response_code().unwrap_or(0) {
+			200 => return Ok(captured(name, "json", response)),
+			429 | 500.. | 0 if retries < EXPORT_RETRIES => {
+				retries += 1;
+				wait *= 2;
+			}
+			0 => return Err("Couldn't connect to youtube".to_owned()),
+			status => return Err(format!("Youtube responded with status {status}")),
+		}
+	}
+}
+
+/// Exports a comment's replies, returning how many there were
+fn export_replies(
+	easy: &mut Easy,
+	endpoint: &str,
+	parent: &str,
+	token: String,
+	format: Format,
+	output: &mut impl Write,
+) -> Result<usize, String> {
+	let mut count = 0;
+	let mut next = Some(token);
+
+	while let Some(token) = next.take() {
+		for reply in from_slice::<ContinuationResponse<Comment>>(&mut request(
+			easy, "replies", endpoint, &token,
+		)?)
+		.map_err(|error| format!("Couldn't understand the replies: {error}"))?
+		.into_items()
+		{
+			match reply.export(parent) {
+				Exported::Comment(reply, _) => {
+					write_comment(output, format, &reply)?;
+					count += 1;
+				}
+				Exported::Continuation(token) => next = Some(token),
+				Exported::None => (),
+			}
+		}
+	}
+
+	Ok(count)
+}
+
+/// Exports a page of comments, and their replies if `all`, returning how many there were and the
+/// token for the next page
+fn export_page(
+	easy: &mut Easy,
+	endpoint: &str,
+	token: &str,
+	first: bool,
+	all: bool,
+	format: Format,
+	output: &mut impl Write,
+) -> Result<(usize, Option<String>), String> {
+	let items = if first {
+		from_slice::<CommentsResponse>(&mut request(easy, "comments", endpoint, token)?)
+			.map_err(|error| format!("Couldn't understand the comments: {error}"))?
+			.into_items()
+	} else {
+		from_slice::<ContinuationResponse<ContinuationItem>>(&mut request(
+			easy,
+			"comments_continuation",
+			endpoint,
+			token,
+		)?)
+		.map_err(|error| format!("Couldn't understand the comments: {error}"))?
+		.into_items()
+	};
+
+	let mut count = 0;
+	let mut next = None;
+
+	for item in items {
+		match item.export() {
+			Exported::Comment(comment, replies) => {
+				write_comment(output, format, &comment)?;
+				count += 1;
+
+				if let Some(replies) = replies.filter(|_| all) {
+					count += export_replies(easy, endpoint, &comment.id, replies, format, output)?;
+				}
+			}
+			Exported::Continuation(token) => next = Some(token),
+			Exported::None => (),
+		}
+	}
+
+	output.flush().map_err(written)?;
+
+	Ok((count, next))
+}
+
+/// Exports comments to `output`, returning how many there were. `token` is either a comment
+/// section's first token, or one from an interrupted export (`resumed`, so the CSV header isn't
+/// written again). `endpoint` is the same as the comment section's.
+///
+/// With `all`, every page and every comment's replies are exported, otherwise only the first page
+/// of comments. After each page `progress` is given how many comments have been exported so far
+/// and the token to resume from. If a page fails, the error has the token to resume from, the
+/// comments from that page are exported again. It doesn't if it's the comment section's first page,
+/// which can't be resumed from (it's a different response), the export can just be started again.
+#[allow(clippy::too_many_arguments)]
+pub fn export_comments(
+	easy: &mut Easy,
+	endpoint: &str,
+	token: String,
+	resumed: bool,
+	all: bool,
+	format: Format,
+	output: &mut impl Write,
+	progress: &mut impl FnMut(usize, Option<&str>),
+) -> Result<usize, String> {
+	if format == Format::Csv && !resumed {
+		writeln!(output, "{CSV_HEADER}").map_err(written)?;
+	}
+
+	let mut count = 0;
+	let mut next = Some(token);
+	// Only the first page is a comments response, later ones are continuations
+	let mut first = !resumed;
+
+	while let Some(token) = next.take() {
+		let (page_count, page_next) =
+			export_page(easy, endpoint, &token, first, all, format, output).map_err(|error| {
+				if first {
+					error
+				} else {
+					format!("{error}, resume from {token}")
+				}
+			})?;
+		first = false;
+		count += page_count;
+		next = page_next;
+
+		if !all {
+			break;
+		}
+
+		progress(count, next.as_deref());
+	}
+
+	Ok(count)
+}
@@ -0,0 +1,282 @@
+//! Leaving things out of pages by what they are, configured in a blocklist file in the data
+//! directory. Renderers describe the videos they add, then `apply` drops the ones the blocklist
+//! (or `u`, for watched videos) says to, so it works the same on every page. It isn't applied to
+//! pages of what's kept locally (bookmarks, local playlists, search history and the inspector).
+//!
+//! Each line of the file is a rule, `#` at the start of a line or after a space starts a comment:
+//!
+//! ```text
+//! channel UCxxxxxxxxxxxxxxxxxxxxxx   videos from a channel, by its ID (`!` on a video adds one)
+//! name Some Channel                  videos from a channel, by its name (ignoring case)
+//! title ^live:|full vod$             videos with titles matching a pattern (ignoring case)
+//! min-duration 1:00                  videos shorter than this
+//! max-duration 2:00:00               videos longer than this
+//! live                               livestreams
+//! upcoming                           streams and premieres that haven't started
+//! shorts                             shorts
+//! ```
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::{metadata, read_to_string};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use ratatui::widgets::{ListItem, Paragraph};
+use regex::{Regex, RegexBuilder};
+
+use crate::structs::Node;
+use crate::utils::{data_path, debug_log, parse_timestamp, write_data};
+use crate::watched;
+
+/// File in the data directory the blocklist is kept in
+const FILE: &str = "blocklist";
+
+/// What's known about a video, to check it against the blocklist
+#[derive(Default)]
+pub struct Details {
+	pub channel_id: Option<String>,
+	pub channel: String,
+	pub title: String,
+	/// In milliseconds, not known for streams and some shelves
+	pub duration: Option<u64>,
+	pub live: bool,
+	pub upcoming: bool,
+	pub short: bool,
+}
+
+/// The blocklist as it was last read, with the file's modification time then (`None` if there
+/// wasn't a file)
+static LOADED: Mutex<Option<(Option<SystemTime>, Blocklist)>> = Mutex::new(None);
+
+thread_local! {
+	/// The videos on the page that's open, by ID
+	static DESCRIBED: RefCell<HashMap<String, Details>> = RefCell::new(HashMap::new());
+}
+
+/// Should be called before a page is loaded (not continued), so its videos are the only ones
+/// described
+pub fn clear() {
+	DESCRIBED.with_borrow_mut(HashMap::clear);
+}
+
+/// Called by renderers for each video they add
+pub fn describe(id: &str, details: Details) {
+	DESCRIBED.with_borrow_mut(|described| described.insert(id.to_owned(), details));
+}
+
+/// Parses a title pattern, a regular expression (see the `regex` crate's syntax) that ignores
+/// case. Matching takes time linear in the length of the title.
+pub fn title_pattern(pattern: &str) -> Result<Regex, String> {
+	RegexBuilder::new(pattern)
+		.case_insensitive(true)
+		.build()
+		.map_err(|error| error.to_string())
+}
+
+/// The rules in the blocklist file
+#[derive(Default)]
+pub struct Blocklist {
+	channels: Vec<String>,
+	/// Lowercase
+	names: Vec<String>,
+	titles: Vec<Regex>,
+	/// In milliseconds
+	min_duration: Option<u64>,
+	/// In milliseconds
+	max_duration: Option<u64>,
+	live: bool,
+	upcoming: bool,
+	shorts: bool,
+}
+impl Blocklist {
+	/// Reads the rules from the file's contents. Lines that can't be understood are logged and
+	/// skipped.
+	pub fn parse(file: &str) -> Blocklist {
+		let mut blocklist = Blocklist::default();
+
+		for line in file.lines() {
+			let rule = if line.trim_start().starts_with('#') {
+				""
+			} else {
+				line.split_once(" #").map_or(line, |(rule, _)| rule).trim()
+			};
+			let (kind, value) = rule.split_once(char::is_whitespace).unwrap_or((rule, ""));
+			let value = value.trim();
+
+			let understood = match (kind, value) {
+				("", _) => true,
+				("channel", id) if !id.is_empty() => {
+					blocklist.channels.push(id.to_owned());
+					true
+				}
+				("name", name) if !name.is_empty() => {
+					blocklist.names.push(name.to_lowercase());
+					true
+				}
+				("title", pattern) if !pattern.is_empty() => match title_pattern(pattern) {
+					Ok(pattern) => {
+						blocklist.titles.push(pattern);
+						true
+					}
+					Err(error) => {
+						debug_log(&format!("Blocklist: {error} in `{pattern}`"));
+						false
+					}
+				},
+				("min-duration", duration) => {
+					blocklist.min_duration = parse_timestamp(duration);
+					blocklist.min_duration.is_some()
+				}
+				("max-duration", duration) => {
+					blocklist.max_duration = parse_timestamp(duration);
+					blocklist.max_duration.is_some()
+				}
+				("live", "") => {
+					blocklist.live = true;
+					true
+				}
+				("upcoming", "") => {
+					blocklist.upcoming = true;
+					true
+				}
+				("shorts", "") => {
+					blocklist.shorts = true;
+					true
+				}
+				_ => false,
+			};
+
+			if !understood {
+				debug_log(&format!("Blocklist: couldn't understand `{line}`"));
+			}
+		}
+
+		blocklist
+	}
+
+	/// Reads the file, empty if there isn't one
+	pub fn load() -> Blocklist {
+		Blocklist::parse(&read_to_string(data_path(FILE)).unwrap_or_default())
+	}
+
+	fn blocks(&self, details: &Details) -> bool {
+		details
+			.channel_id
+			.as_ref()
+			.is_some_and(|id| self.channels.contains(id))
+			|| self.names.contains(&details.channel.to_lowercase())
+			|| self
+				.titles
+				.iter()
+				.any(|pattern| pattern.is_match(&details.title))
+			|| details.duration.is_some_and(|duration| {
+				self.min_duration.is_some_and(|min| duration < min)
+					|| self.max_duration.is_some_and(|max| duration > max)
+			}) || (self.live && details.live)
+			|| (self.upcoming && details.upcoming)
+			|| (self.shorts && details.short)
+	}
+
+	/// Leaves out whatever this blocks from a page (`page` is its `identity()`), and watched videos
+	/// if they're hidden on it. Something's added if everything was left out, since something has
+	/// to be selected.
+	pub fn apply<'a>(
+		&self,
+		page: &str,
+		list: &mut Vec<ListItem<'a>>,
+		info: &mut Vec<(Paragraph<'a>, Node)>,
+	) {
+		let keep = DESCRIBED.with_borrow(|described| {
+			info.iter()
+				.map(|(_, node)| match node {
+					Node::Channel(id, _) => !self.channels.contains(id),
+					Node::Video(id) | Node::Short(id, _) => described
+						.get(id)
+						.is_none_or(|details| !self.blocks(details) && !watched::hidden(page, id)),
+					_ => true,
+				})
+				.collect::<Vec<bool>>()
+		});
+
+		let mut index = 0;
+		list.retain(|_| {
+			index += 1;
+			keep[index - 1]
+		});
+
+		let mut index = 0;
+		info.retain(|_| {
+			index += 1;
+			keep[index - 1]
+		});
+
+		if info.is_empty() {
+			list.push(ListItem::new("Nothing here"));
+			info.push((
+				Paragraph::new(
+					"Everything's blocked or hidden. If watched videos are hidden here, u shows \
+					 them again.",
+				),
+				Node::None,
+			));
+		}
+	}
+}
+
+/// Leaves out whatever the blocklist file blocks from a page, see `Blocklist::apply`. The file's
+/// only read again when it's been changed since it was last read.
+pub fn apply<'a>(page: &str, list: &mut Vec<ListItem<'a>>, info: &mut Vec<(Paragraph<'a>, Node)>) {
+	let modified = metadata(data_path(FILE))
+		.and_then(|metadata| metadata.modified())
+		.ok();
+	let mut loaded = LOADED.lock().unwrap_or_else(|error| error.into_inner());
+
+	if loaded
+		.as_ref()
+		.is_none_or(|(loaded_modified, _)| *loaded_modified != modified)
+	{
+		*loaded = Some((modified, Blocklist::load()));
+	}
+
+	if let Some((_, blocklist)) = loaded.as_ref() {
+		blocklist.apply(page, list, info);
+	}
+}
+
+/// The ID and name of the channel something's from, if it's known
+pub fn channel(node: &Node) -> Option<(String, String)> {
+	match node {
+		Node::Channel(id, _) => Some((id.clone(), String::new())),
+		Node::Video(id) | Node::Short(id, _) => DESCRIBED.with_borrow(|described| {
+			let details = described.get(id)?;
+
+			Some((details.channel_id.clone()?, details.channel.clone()))
+		}),
+		_ => None,
+	}
+}
+
+/// The title of a video on the page that's open, if it's known
+pub fn title(id: &str) -> Option<String> {
+	DESCRIBED.with_borrow(|described| described.get(id).map(|details| details.title.clone()))
+}
+
+/// Adds a channel to the blocklist file, with its name as a comment
+pub fn block_channel(id: &str, name: &str) {
+	let mut file = read_to_string(data_path(FILE)).unwrap_or_default();
+
+	if !file.is_empty() && !file.ends_with('\n') {
+		file.push('\n');
+	}
+	file += &format!("channel {id}");
+	if !name.is_empty() {
+		file += &format!(" # {name}");
+	}
+	file.push('\n');
+
+	write_data(FILE, file.as_bytes());
+
+	// In case it's changed within the resolution of its modification time
+	*LOADED.lock().unwrap_or_else(|error| error.into_inner()) = None;
+}
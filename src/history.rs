@@ -0,0 +1,173 @@
+//! Searches made in the program, kept between runs in the data directory and shown on the search
+//! history page (`y`)
+
+use std::fs::read;
+use std::sync::Mutex;
+
+use ratatui::style::{Color, Style};
+use ratatui::text::{Span, Spans};
+use ratatui::widgets::{ListItem, Paragraph, Wrap};
+use serde::{Deserialize, Serialize};
+use simd_json::{from_slice, to_vec};
+
+use crate::config::RECORD_SEARCH_HISTORY;
+use crate::structs::Node;
+use crate::utils::{data_path, now, time_ago, write_data};
+
+/// File in the data directory the history is kept in
+const FILE: &str = "search_history.json";
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct Search {
+	pub query: String,
+	/// Filters, as sent to youtube
+	pub params: Option<String>,
+	/// When it was last searched for, in seconds since the unix epoch
+	pub time: u64,
+}
+
+#[derive(Deserialize, Serialize)]
+struct History {
+	/// Whether new searches are added, turned on and off on the history page
+	recording: bool,
+	/// Oldest first
+	searches: Vec<Search>,
+}
+
+/// Loaded from the file the first time it's needed
+static HISTORY: Mutex<Option<History>> = Mutex::new(None);
+
+/// Runs `f` on the history, loading it first if it hasn't been. Saves it afterwards if `f`
+/// returns true.
+fn with_history<T>(f: impl FnOnce(&mut History) -> (T, bool)) -> T {
+	let mut history = HISTORY.lock().unwrap_or_else(|error| error.into_inner());
+
+	let history = history.get_or_insert_with(|| {
+		read(data_path(FILE))
+			.ok()
+			.and_then(|mut file| from_slice(&mut file).ok())
+			.unwrap_or(History {
+				recording: RECORD_SEARCH_HISTORY,
+				searches: Vec::new(),
+			})
+	});
+
+	let (result, changed) = f(history);
+
+	if changed {
+		write_data(
+			FILE,
+			&to_vec(history).expect("Should be able to serialize search history"),
+		);
+	}
+
+	result
+}
+
+/// Adds a search to the history, unless recording is off. Searching for the same thing again
+/// moves it to the top.
+pub fn record(query: &str, params: Option<&str>) {
+	with_history(|history| {
+		if !history.recording {
+			return ((), false);
+		}
+
+		history
+			.searches
+			.retain(|search| search.query != query || search.params.as_deref() != params);
+		history.searches.push(Search {
+			query: query.to_owned(),
+			params: params.map(str::to_owned),
+			time: now(),
+		});
+
+		((), true)
+	});
+}
+
+/// The queries searched for, oldest first
+pub fn queries() -> Vec<String> {
+	with_history(|history| {
+		(
+			history
+				.searches
+				.iter()
+				.map(|search| search.query.clone())
+				.collect(),
+			false,
+		)
+	})
+}
+
+/// The search at `index` (oldest first)
+pub fn get(index: usize) -> Option<Search> {
+	with_history(|history| (history.searches.get(index).cloned(), false))
+}
+
+/// Removes the search at `index` (oldest first)
+pub fn remove(index: usize) {
+	with_history(|history| {
+		let exists = index < history.searches.len();
+
+		if exists {
+			history.searches.remove(index);
+		}
+
+		((), exists)
+	});
+}
+
+/// Turns recording on or off, returning whether it's now on
+pub fn toggle_recording() -> bool {
+	with_history(|history| {
+		history.recording = !history.recording;
+
+		(history.recording, true)
+	})
+}
+
+/// The search history page: whether it's recording, then the searches, newest first
+pub fn into_widgets<'a>() -> (Vec<ListItem<'a>>, Vec<(Paragraph<'a>, Node)>) {
+	with_history(|history| {
+		let mut titles = Vec::with_capacity(history.searches.len() + 1);
+		let mut info = Vec::with_capacity(history.searches.len() + 1);
+
+		titles.push(ListItem::new(Spans(vec![
+			"Recording searches: ".into(),
+			if history.recording {
+				Span::styled("on", Style::default().fg(Color::Green))
+			} else {
+				Span::styled("off", Style::default().fg(Color::Red))
+			},
+		])));
+		info.push((
+			Paragraph::new(
+				"p to turn recording on or off, searches that were already recorded are kept",
+			)
+			.wrap(Wrap { trim: false }),
+			Node::None,
+		));
+
+		for (index, search) in history.searches.iter().enumerate().rev() {
+			titles.push(ListItem::new(Spans(if search.params.is_some() {
+				vec![
+					search.query.clone().into(),
+					Span::styled(" (filtered)", Style::default().fg(Color::DarkGray)),
+				]
+			} else {
+				vec![search.query.clone().into()]
+			})));
+			info.push((
+				Paragraph::new(vec![
+					Spans::from(["Searched ", &time_ago(search.time).to_lowercase()].concat()),
+					Spans::default(),
+					Spans::from("Enter to search again, d to delete"),
+				])
+				.wrap(Wrap { trim: false }),
+				Node::PastSearch(index),
+			));
+		}
+
+		((titles, info), false)
+	})
+}
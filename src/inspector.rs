@@ -0,0 +1,218 @@
+//! Keeps track of recent requests, so they can be looked at in the request inspector (`i`) and
+//! their responses saved for bug reports
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::fs::{create_dir_all, write};
+use std::path::PathBuf;
+use std::process;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use ratatui::style::{Color, Style};
+use ratatui::text::{Span, Spans};
+use ratatui::widgets::{ListItem, Paragraph, Wrap};
+
+use crate::config::{REQUEST_LOG_LENGTH, REQUEST_LOG_MAX_RESPONSE};
+use crate::structs::Node;
+use crate::utils::{base_url, data_path, debug_log, LOG_FILE};
+
+/// A request that was sent
+pub struct Record {
+	/// Increases with every request, used to find this record again
+	pub id: usize,
+	pub url: String,
+	/// JSON sent, `None` for GET requests
+	pub body: Option<String>,
+	/// HTTP status, 0 if the request failed
+	pub status: u32,
+	/// Set if curl failed
+	pub error: Option<String>,
+	pub latency: Duration,
+	/// Size of the response in bytes
+	pub size: usize,
+	/// Empty if it's bigger than `REQUEST_LOG_MAX_RESPONSE`
+	pub response: Vec<u8>,
+	/// Where the response was saved, if it has been
+	pub saved_to: Option<PathBuf>,
+}
+impl Record {
+	/// The part of the URL after the base URL, i.e. `/youtubei/v1/browse`
+	fn endpoint(&self) -> &str {
+		self.url.strip_prefix(base_url()).unwrap_or(&self.url)
+	}
+}
+
+/// The last `REQUEST_LOG_LENGTH` requests, newest at the back
+static REQUESTS: Mutex<VecDeque<Record>> = Mutex::new(VecDeque::new());
+
+thread_local! {
+	/// ID of the last request sent from this thread
+	static LAST: Cell<Option<usize>> = const { Cell::new(None) };
+	/// ID of the request the last page loaded on this thread was made from, if it was
+	static PAGE: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// ID of the last request sent from this thread, to pass to `page_loaded`
+pub fn last_id() -> Option<usize> {
+	LAST.get()
+}
+
+/// Should be called after a page has loaded, with `last_id()` from before it started. The last
+/// request it sent is the one it was made from, it didn't send any if it's local.
+pub fn page_loaded(before: Option<usize>) {
+	PAGE.set(Some(last_id()).filter(|last| *last != before).flatten());
+}
+
+/// ID of the request the last page loaded was made from, see `page_loaded`
+pub fn page_request() -> Option<usize> {
+	PAGE.get()
+}
+
+/// Saves a request to the log, also writing it to the `--log` file if there is one
+pub fn record(mut record: Record) {
+	let mut requests = REQUESTS.lock().unwrap_or_else(|error| error.into_inner());
+
+	record.id = requests.back().map_or(0, |last| last.id + 1);
+	LAST.set(Some(record.id));
+
+	if LOG_FILE.get().is_some() {
+		debug_log(&format!(
+			"{} {} -> {} ({}ms, {} bytes){}{}",
+			if record.body.is_some() { "POST" } else { "GET" },
+			record.url,
+			record.status,
+			record.latency.as_millis(),
+			record.size,
+			record
+				.error
+				.as_ref()
+				.map_or(String::new(), |error| [" error: ", error].concat()),
+			record
+				.body
+				.as_ref()
+				.map_or(String::new(), |body| ["\n", body].concat()),
+		));
+	}
+
+	// Too big to keep around, i.e. the player JS
+	if record.response.len() > REQUEST_LOG_MAX_RESPONSE {
+		record.response = Vec::new();
+	}
+
+	if requests.len() == REQUEST_LOG_LENGTH {
+		requests.pop_front();
+	}
+	requests.push_back(record);
+}
+
+/// Writes the response of the request with the given ID to a file in the data directory, so it
+/// can be attached to bug reports. IDs start again each run, so the file has the process's ID too.
+pub fn save(id: usize) {
+	let mut requests = REQUESTS.lock().unwrap_or_else(|error| error.into_inner());
+
+	if let Some(record) = requests
+		.iter_mut()
+		.find(|record| record.id == id && record.response.len() == record.size)
+	{
+		let path = data_path(&format!(
+			"response-{}-{id}.{}",
+			process::id(),
+			// Everything but the home page is JSON
+			if record.body.is_some() {
+				"json"
+			} else {
+				"html"
+			}
+		));
+
+		if let Some(directory) = path.parent() {
+			let _ = create_dir_all(directory);
+		}
+		if write(&path, &record.response).is_ok() {
+			record.saved_to = Some(path);
+		}
+	}
+}
+
+/// Lists the recent requests, newest first. The first item is the response the previous page was
+/// made from (`request`, from `page_request`), if it was and it's still kept.
+pub fn into_widgets<'a>(
+	previous_page: String,
+	request: Option<usize>,
+) -> (Vec<ListItem<'a>>, Vec<(Paragraph<'a>, Node)>) {
+	let requests = REQUESTS.lock().unwrap_or_else(|error| error.into_inner());
+
+	let mut titles = Vec::with_capacity(requests.len() + 1);
+	let mut info = Vec::with_capacity(requests.len() + 1);
+
+	if let Some(page) = request.and_then(|id| requests.iter().find(|record| record.id == id)) {
+		titles.push(ListItem::new(Spans::from(vec![
+			"Save response of ".into(),
+			Span::styled(previous_page, Style::default().fg(Color::Yellow)),
+		])));
+		info.push((
+			Paragraph::new(
+				page.saved_to
+					.as_ref()
+					.map_or("Not saved yet".to_owned(), |path| {
+						["Saved to ", &path.display().to_string()].concat()
+					}),
+			)
+			.wrap(Wrap { trim: false }),
+			Node::Request(page.id),
+		));
+	}
+
+	for record in requests.iter().rev() {
+		titles.push(ListItem::new(Span::styled(
+			format!("{} {}", record.status, record.endpoint()),
+			Style::default().fg(if record.status == 200 {
+				Color::Green
+			} else {
+				Color::Red
+			}),
+		)));
+
+		let mut lines: Vec<Spans> = vec![
+			["URL: ", &record.url].concat().into(),
+			format!("Status: {}", record.status).into(),
+			format!("Latency: {}ms", record.latency.as_millis()).into(),
+			format!("Size: {} bytes", record.size).into(),
+		];
+
+		if let Some(error) = &record.error {
+			lines.push(["Error: ", error].concat().into());
+		}
+
+		lines.push(
+			match &record.saved_to {
+				Some(path) => ["Saved to ", &path.display().to_string()].concat(),
+				None if record.response.len() < record.size => {
+					"Too big to keep, so it can't be saved".to_owned()
+				}
+				None => "Enter to save the response".to_owned(),
+			}
+			.into(),
+		);
+
+		if let Some(body) = &record.body {
+			lines.push("".into());
+			lines.push("Request body:".into());
+			lines.push(body.clone().into());
+		}
+
+		info.push((
+			Paragraph::new(lines).wrap(Wrap { trim: false }),
+			Node::Request(record.id),
+		));
+	}
+
+	// The list can't be empty
+	if titles.is_empty() {
+		titles.push(ListItem::new("No requests yet"));
+		info.push((Paragraph::new(""), Node::None));
+	}
+
+	(titles, info)
+}
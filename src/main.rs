@@ -2,30 +2,53 @@
 #![feature(option_zip)]
 #![feature(exclusive_range_pattern)]
 
-use std::io::{stdin, stdout, Read};
+use std::env::args;
+use std::fs::{write, File};
+use std::io::{stdin, stdout, BufWriter, Read, Stdout, Write};
 use std::panic::{set_hook, take_hook};
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
 
-use crossterm::event::{read, Event, KeyCode, KeyEvent};
+use crossterm::event::{poll, read, Event, KeyCode, KeyEvent, KeyModifiers};
 use crossterm::execute;
 use crossterm::terminal::{
 	disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
 };
-use curl::easy;
 use curl::easy::Easy;
-use js_sandbox::Script;
 use ratatui::backend::CrosstermBackend;
 use ratatui::layout::{Alignment, Rect};
-use ratatui::style::{Modifier, Style};
-use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Span, Spans};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
 use ratatui::Terminal;
+use serde::de::DeserializeOwned;
 use simd_json::{from_slice, Error, ErrorType};
 
+mod bookmarks;
 mod config;
 use config::*;
+mod decipher;
+use decipher::Decipherer;
+mod export;
+use export::*;
+mod filter;
+mod history;
+mod inspector;
+mod player;
+mod playlists;
+use playlists::Entry;
+mod resume;
+mod search_filter;
+use search_filter::*;
 mod structs;
 use structs::*;
+mod suggestions;
+use suggestions::Suggester;
+#[cfg(test)]
+mod tests;
 mod utils;
 use utils::*;
+mod watched;
 
 /// Current page + information on previous pages
 pub enum Page {
@@ -44,9 +67,41 @@ pub enum Page {
 		params: Option<String>,
 		previous: (Box<Page>, usize),
 	},
+	/// Trending videos, for the region requests are sent with
+	Trending {
+		/// The open tab's (`None` for the first, now)
+		params: Option<String>,
+		continuation: Option<String>,
+		previous: (Box<Page>, usize),
+	},
+	/// Explore, youtube's other destinations
+	Explore {
+		/// The open tab's (`None` for the first)
+		params: Option<String>,
+		continuation: Option<String>,
+		previous: (Box<Page>, usize),
+	},
 	/// Search results
 	Search {
 		query: String,
+		/// Either from youtube or encoded from `filters`
+		params: Option<String>,
+		/// The filters picked in the filter panel (or on the command line)
+		filters: SearchFilters,
+		continuation: Option<String>,
+		previous: (Box<Page>, usize),
+	},
+	/// The filters for a search, the search they were opened from is the previous page
+	SearchFilters {
+		query: String,
+		filters: SearchFilters,
+		previous: (Box<Page>, usize),
+	},
+	/// Videos and shorts with a hashtag
+	Hashtag {
+		/// Without the `#`
+		tag: String,
+		/// Resolved from the hashtag's URL if they're not known
 		params: Option<String>,
 		continuation: Option<String>,
 		previous: (Box<Page>, usize),
@@ -67,18 +122,162 @@ pub enum Page {
 		first_continuation: String,
 		/// Token for subsequent comments
 		continuation: Option<String>,
+		/// Endpoint the tokens are sent to, `next` for videos and `browse` for community posts
+		endpoint: &'static str,
+		/// The orders the comments can be sorted in (names and tokens for their first comments),
+		/// found when the page is requested
+		sorts: Vec<(String, String)>,
+		/// Index of the current sort
+		sort: usize,
+		/// Only comments with this in their author or text are shown, lowercase
+		filter: Option<String>,
+		/// Whether replies are shown under their comments instead of on their own page
+		tree: bool,
+		/// In tree mode, the threads whose replies are shown: the token for their replies, how
+		/// many items were added under the comment, and the token for more replies if there are
+		/// any
+		threads: Vec<(String, usize, Option<String>)>,
 		previous: (Box<Page>, usize),
 	},
 	/// A comment's replies
 	Comment {
 		first_continuation: String,
 		continuation: Option<String>,
+		/// The same as the comment section's
+		endpoint: &'static str,
+		/// The same as the comment section's
+		filter: Option<String>,
+		previous: (Box<Page>, usize),
+	},
+	/// A channel's community posts
+	Community {
+		browse_id: String,
+		continuation: Option<String>,
+		previous: (Box<Page>, usize),
+	},
+	/// Shorts, played back to back
+	Shorts {
+		/// The first short
+		video_id: String,
+		/// The first short's title, its ID is shown if it's not known
+		title: Option<String>,
+		/// Params for the sequence after the first short, found when the page is requested if
+		/// they're not known
+		sequence_params: Option<String>,
+		continuation: Option<String>,
+		previous: (Box<Page>, usize),
+	},
+	/// A stream's live chat, polled while it's open
+	LiveChat {
+		/// Token for the recent messages, from the video's page
+		first_continuation: String,
+		/// Token to poll with next, `None` once the stream has ended
+		continuation: Option<String>,
+		/// How long to wait before polling
+		timeout: Duration,
+		previous: (Box<Page>, usize),
+	},
+	/// A finished stream's chat replay, follows the player if it's started from here
+	ChatReplay {
+		video_id: String,
+		/// Token for the start of the replay, from the video's page
+		first_continuation: String,
+		/// Token used to request the replay from `offset`, found in the first response
+		seek_continuation: Option<String>,
+		/// Where the replay was requested from, in milliseconds
+		offset: u64,
+		/// Token for the messages after the loaded ones
+		continuation: Option<String>,
+		/// The player, if it was started from here. The replay follows it while it's running.
+		player: Option<Child>,
+		previous: (Box<Page>, usize),
+	},
+	/// The links in a description or comment
+	Links {
+		links: Vec<(String, Node)>,
+		previous: (Box<Page>, usize),
+	},
+	/// Past searches
+	SearchHistory {
+		previous: (Box<Page>, usize),
+	},
+	/// Lists of bookmarks
+	Bookmarks {
+		previous: (Box<Page>, usize),
+	},
+	/// The bookmarks in a list
+	BookmarkList {
+		name: String,
+		previous: (Box<Page>, usize),
+	},
+	/// Local playlists
+	Playlists {
+		previous: (Box<Page>, usize),
+	},
+	/// The videos in a local playlist
+	LocalPlaylist {
+		name: String,
+		previous: (Box<Page>, usize),
+	},
+	/// Recent requests
+	Inspector {
+		/// ID of the request the previous page was made from, if it was
+		request: Option<usize>,
 		previous: (Box<Page>, usize),
 	},
 }
 impl Page {
-	fn request<'a>(&mut self, easy: &mut Easy) -> (Vec<ListItem<'a>>, Vec<(Paragraph<'a>, Node)>) {
+	/// Whether the blocklist (and hiding watched videos) applies to this page. Pages of what's
+	/// kept locally aren't filtered, their indices are what `d` deletes by.
+	fn filtered(&self) -> bool {
+		!matches!(
+			self,
+			Page::SearchFilters { .. }
+				| Page::SearchHistory { .. }
+				| Page::Bookmarks { .. }
+				| Page::BookmarkList { .. }
+				| Page::Playlists { .. }
+				| Page::LocalPlaylist { .. }
+				| Page::Inspector { .. }
+		)
+	}
+
+	/// What this page is, from what it was requested with (not its title, which many pages
+	/// share), so watched videos can be hidden on it and not others
+	fn identity(&self) -> String {
 		match self {
+			Page::Category { continuation, .. } => format!("category {continuation:?}"),
+			Page::Game {
+				browse_id, params, ..
+			} => format!("game {browse_id} {params:?}"),
+			Page::Trending { params, .. } => format!("trending {params:?}"),
+			Page::Explore { params, .. } => format!("explore {params:?}"),
+			Page::Search { query, params, .. } => format!("search {query} {params:?}"),
+			Page::Hashtag { tag, .. } => format!("hashtag {tag}"),
+			Page::Next { video_id, .. } => format!("next {video_id}"),
+			Page::Transcript { params, .. } => format!("transcript {params}"),
+			Page::CommentSection {
+				first_continuation, ..
+			}
+			| Page::Comment {
+				first_continuation, ..
+			}
+			| Page::LiveChat {
+				first_continuation, ..
+			} => format!("continuation {first_continuation}"),
+			Page::Community { browse_id, .. } => format!("community {browse_id}"),
+			Page::Shorts { video_id, .. } => format!("shorts {video_id}"),
+			Page::ChatReplay { video_id, .. } => format!("replay {video_id}"),
+			// Only one of each of these
+			_ => self.to_string(),
+		}
+	}
+
+	fn request<'a>(&mut self, easy: &mut Easy) -> (Vec<ListItem<'a>>, Vec<(Paragraph<'a>, Node)>) {
+		filter::clear();
+		let last_request = inspector::last_id();
+
+		let (mut list, mut info) = match self {
 			Page::Home {
 				ref mut continuation,
 				..
@@ -88,7 +287,11 @@ impl Page {
 				// be something like this even with a HTML parser, since it's embedded in js).
 				let (list, info, new_continuation) = extract_json::<RichGridRenderer>(
 					&mut unsafe {
-						String::from_utf8_unchecked(request_get(easy, "https://www.youtube.com"))
+						String::from_utf8_unchecked(captured(
+							"home",
+							"html",
+							request_get(easy, &home_url()),
+						))
 					},
 					"{\"cont",
 					"1}}",
@@ -110,10 +313,10 @@ impl Page {
 				let mut list = Vec::new();
 				let mut info_vec = Vec::new();
 
-				from_slice::<ContinuationResponse<RichGridRendererContent>>(&mut request_post(
-					easy,
-					"https://www.youtube.com/youtubei/v1/browse",
-					&request,
+				from_slice::<ContinuationResponse<RichGridRendererContent>>(&mut captured(
+					"browse_continuation",
+					"json",
+					request_post(easy, &api_url("browse"), &request),
 				))
 				.expect("Category JSON should be valid")
 				.into_widgets(&mut list, &mut info_vec);
@@ -126,17 +329,29 @@ impl Page {
 				params,
 				..
 			} => {
-				let request = BrowseRequest {
-					browseId: Some(browse_id.clone()),
-					params: params.clone(),
-					..BrowseRequest::new(easy).expect("Youtube should set `__Secure-YEC` cookie")
-				};
+				let (list, info, new_continuation) = browse_general(easy, browse_id, params);
 
-				let (list, info, new_continuation) = from_slice::<GeneralResponse>(
-					&mut request_post(easy, "https://www.youtube.com/youtubei/v1/browse", &request),
-				)
-				.expect("Game JSON should be valid")
-				.into_widgets();
+				*continuation = new_continuation;
+
+				(list, info)
+			}
+			Page::Trending {
+				params,
+				ref mut continuation,
+				..
+			} => {
+				let (list, info, new_continuation) = browse_general(easy, "FEtrending", params);
+
+				*continuation = new_continuation;
+
+				(list, info)
+			}
+			Page::Explore {
+				params,
+				ref mut continuation,
+				..
+			} => {
+				let (list, info, new_continuation) = browse_general(easy, "FEexplore", params);
 
 				*continuation = new_continuation;
 
@@ -148,18 +363,63 @@ impl Page {
 				ref mut continuation,
 				..
 			} => {
-				let (list, info, new_continuation) =
-					from_slice::<SearchResponse>(&mut request_post(
+				let (list, info, new_continuation) = from_slice::<SearchResponse>(&mut captured(
+					"search",
+					"json",
+					request_post(
 						easy,
-						"https://www.youtube.com/youtubei/v1/search",
+						&api_url("search"),
 						&SearchRequest {
 							query: query.clone(),
 							params: params.clone(),
 							..SearchRequest::default()
 						},
+					),
+				))
+				.expect("Next JSON should be valid")
+				.into_widgets();
+
+				*continuation = new_continuation;
+
+				(list, info)
+			}
+			Page::Hashtag {
+				tag,
+				params,
+				ref mut continuation,
+				..
+			} => {
+				// Typed in the search box, find the params from the hashtag's URL
+				if params.is_none() {
+					*params = from_slice::<ResolveUrlResponse>(&mut captured(
+						"resolve_url",
+						"json",
+						request_post(
+							easy,
+							&api_url("navigation/resolve_url"),
+							&ResolveUrlRequest {
+								url: ["https://www.youtube.com/hashtag/", tag].concat(),
+								..ResolveUrlRequest::default()
+							},
+						),
 					))
-					.expect("Next JSON should be valid")
-					.into_widgets();
+					.expect("Resolve URL JSON should be valid")
+					.params();
+				}
+
+				let request = BrowseRequest {
+					browseId: Some("FEhashtag".to_owned()),
+					params: params.clone(),
+					..BrowseRequest::new(easy).expect("Youtube should set `__Secure-YEC` cookie")
+				};
+
+				let (list, info, new_continuation) = from_slice::<HashtagResponse>(&mut captured(
+					"hashtag",
+					"json",
+					request_post(easy, &api_url("browse"), &request),
+				))
+				.expect("Hashtag JSON should be valid")
+				.into_widgets();
 
 				*continuation = new_continuation;
 
@@ -170,13 +430,17 @@ impl Page {
 				ref mut continuation,
 				..
 			} => {
-				let (list, info, new_continuation) = from_slice::<NextResponse>(&mut request_post(
-					easy,
-					"https://www.youtube.com/youtubei/v1/next",
-					&NextRequest {
-						videoId: video_id.clone(),
-						..NextRequest::default()
-					},
+				let (list, info, new_continuation) = from_slice::<NextResponse>(&mut captured(
+					"next",
+					"json",
+					request_post(
+						easy,
+						&api_url("next"),
+						&NextRequest {
+							videoId: video_id.clone(),
+							..NextRequest::default()
+						},
+					),
 				))
 				.expect("Next JSON should be valid")
 				.into_widgets();
@@ -185,59 +449,278 @@ impl Page {
 
 				(list, info)
 			}
-			Page::Transcript { params, .. } => from_slice::<TranscriptResponse>(&mut request_post(
-				easy,
-				"https://www.youtube.com/youtubei/v1/get_transcript",
-				&BrowseRequest {
-					params: Some(params.clone()),
-					..BrowseRequest::default()
-				},
+			Page::Transcript { params, .. } => from_slice::<TranscriptResponse>(&mut captured(
+				"transcript",
+				"json",
+				request_post(
+					easy,
+					&api_url("get_transcript"),
+					&BrowseRequest {
+						params: Some(params.clone()),
+						..BrowseRequest::default()
+					},
+				),
 			))
 			.expect("Transcript response should be valid")
 			.into_widgets(),
 			Page::CommentSection {
 				first_continuation,
 				ref mut continuation,
+				endpoint,
+				sorts,
+				sort,
+				filter,
+				threads,
 				..
 			} => {
 				let mut list = Vec::new();
 				let mut info_vec = Vec::new();
 
-				*continuation = from_slice::<CommentsResponse>(&mut request_post(
-					easy,
-					"https://www.youtube.com/youtubei/v1/next",
-					&BrowseRequest {
-						continuation: Some(first_continuation.clone()),
-						..BrowseRequest::default()
-					},
+				// All threads start collapsed
+				threads.clear();
+
+				let mut response = from_slice::<CommentsResponse>(&mut captured(
+					"comments",
+					"json",
+					request_post(
+						easy,
+						&api_url(endpoint),
+						&BrowseRequest {
+							continuation: Some(first_continuation.clone()),
+							..BrowseRequest::default()
+						},
+					),
 				))
-				.expect("Comments JSON should be valid")
-				.into_widgets(&mut list, &mut info_vec);
+				.expect("Comments JSON should be valid");
+
+				(*sorts, *sort) = response.sorts();
+
+				if let Some(filter) = filter {
+					response.retain(filter);
+				}
+
+				*continuation = response.into_widgets(&mut list, &mut info_vec);
+
+				// None of them matched, keep looking
+				if list.is_empty() {
+					continue_comments::<ContinuationItem>(
+						easy,
+						"comments_continuation",
+						endpoint,
+						continuation,
+						filter,
+						&mut list,
+						&mut info_vec,
+					);
+				}
+
+				// The list can't be empty
+				if list.is_empty() {
+					list.push(ListItem::new(if filter.is_some() {
+						"No matching comments"
+					} else {
+						"No comments"
+					}));
+					info_vec.push((Paragraph::new(""), Node::None));
+				}
 
 				(list, info_vec)
 			}
 			Page::Comment {
 				first_continuation,
 				ref mut continuation,
+				endpoint,
+				filter,
 				..
 			} => {
 				let mut list = Vec::new();
 				let mut info = Vec::new();
 
-				*continuation = from_slice::<ContinuationResponse<Comment>>(&mut request_post(
+				*continuation = Some(first_continuation.clone());
+
+				continue_comments::<Comment>(
 					easy,
-					"https://www.youtube.com/youtubei/v1/next",
-					&BrowseRequest {
-						continuation: Some(first_continuation.clone()),
-						..BrowseRequest::default()
-					},
-				))
-				.expect("Next JSON should be valid")
-				.into_widgets(&mut list, &mut info);
+					"replies",
+					endpoint,
+					continuation,
+					filter,
+					&mut list,
+					&mut info,
+				);
+
+				// The list can't be empty
+				if list.is_empty() {
+					list.push(ListItem::new("No matching replies"));
+					info.push((Paragraph::new(""), Node::None));
+				}
+
+				(list, info)
+			}
+			Page::Community {
+				browse_id,
+				ref mut continuation,
+				..
+			} => {
+				let (list, info, new_continuation) =
+					from_slice::<CommunityResponse>(&mut captured(
+						"community",
+						"json",
+						request_post(
+							easy,
+							&api_url("browse"),
+							&BrowseRequest {
+								browseId: Some(browse_id.clone()),
+								params: Some(COMMUNITY_TAB_PARAMS.to_owned()),
+								..BrowseRequest::default()
+							},
+						),
+					))
+					.expect("Community JSON should be valid")
+					.into_widgets();
+
+				*continuation = new_continuation;
+
+				(list, info)
+			}
+			Page::Shorts {
+				video_id,
+				title,
+				sequence_params,
+				ref mut continuation,
+				..
+			} => {
+				let mut list = Vec::new();
+				let mut info = Vec::new();
+
+				short_into_widgets(video_id.clone(), title.clone(), &mut list, &mut info);
+
+				// Shorts from search results don't come with the sequence
+				if sequence_params.is_none() {
+					*sequence_params = from_slice::<ReelItemWatchResponse>(&mut captured(
+						"reel_item_watch",
+						"json",
+						request_post(
+							easy,
+							&api_url("reel/reel_item_watch"),
+							&ReelItemWatchRequest {
+								playerRequest: PlayerRequest {
+									videoId: video_id.clone(),
+								},
+								..ReelItemWatchRequest::default()
+							},
+						),
+					))
+					.expect("Short JSON should be valid")
+					.sequence_params();
+				}
+
+				if let Some(sequence_params) = sequence_params {
+					*continuation = from_slice::<ReelWatchSequenceResponse>(&mut captured(
+						"reel_watch_sequence",
+						"json",
+						request_post(
+							easy,
+							&api_url("reel/reel_watch_sequence"),
+							&ReelWatchSequenceRequest {
+								sequenceParams: sequence_params.clone(),
+								..ReelWatchSequenceRequest::default()
+							},
+						),
+					))
+					.expect("Shorts sequence JSON should be valid")
+					.into_widgets(&mut list, &mut info);
+				}
+
+				(list, info)
+			}
+			Page::LiveChat {
+				first_continuation,
+				ref mut continuation,
+				timeout,
+				..
+			} => {
+				let mut list = Vec::new();
+				let mut info = Vec::new();
+
+				(*continuation, *timeout) =
+					poll_live_chat(easy, first_continuation.clone(), &mut list, &mut info);
+
+				// The list can't be empty
+				if list.is_empty() {
+					list.push(ListItem::new("No messages yet"));
+					info.push((Paragraph::new(""), Node::None));
+				}
+
+				(list, info)
+			}
+			Page::ChatReplay {
+				first_continuation,
+				seek_continuation,
+				offset,
+				continuation,
+				..
+			} => {
+				let mut list = Vec::new();
+				let mut info = Vec::new();
+
+				let (next_continuation, next_seek_continuation) =
+					from_slice::<LiveChatReplayResponse>(&mut captured(
+						"live_chat_replay",
+						"json",
+						request_post(
+							easy,
+							&api_url("live_chat/get_live_chat_replay"),
+							&LiveChatReplayRequest {
+								// The first token only works from the start
+								continuation: seek_continuation
+									.clone()
+									.filter(|_| *offset != 0)
+									.unwrap_or_else(|| first_continuation.clone()),
+								currentPlayerState: CurrentPlayerState {
+									playerOffsetMs: offset.to_string(),
+								},
+								..LiveChatReplayRequest::default()
+							},
+						),
+					))
+					.expect("Chat replay JSON should be valid")
+					.into_widgets(&mut list, &mut info);
+
+				*continuation = next_continuation;
+				if next_seek_continuation.is_some() {
+					*seek_continuation = next_seek_continuation;
+				}
+
+				// The list can't be empty
+				if list.is_empty() {
+					list.push(ListItem::new("No messages here"));
+					info.push((Paragraph::new(""), Node::None));
+				}
 
 				(list, info)
 			}
+			Page::Links { links, .. } => links_into_widgets(links.clone()),
+			Page::SearchFilters { query, filters, .. } => filters.widgets(query),
+			Page::SearchHistory { .. } => history::into_widgets(),
+			Page::Bookmarks { .. } => bookmarks::lists_widgets(),
+			Page::BookmarkList { name, .. } => bookmarks::list_widgets(name),
+			Page::Playlists { .. } => playlists::playlists_widgets(),
+			Page::LocalPlaylist { name, .. } => playlists::playlist_widgets(name),
+			Page::Inspector { request, previous } => {
+				inspector::into_widgets(previous.0.to_string(), *request)
+			}
+		};
+
+		if self.filtered() {
+			filter::apply(&self.identity(), &mut list, &mut info);
+		}
+
+		// So the inspector knows which request this page was made from
+		if !matches!(self, Page::Inspector { .. }) {
+			inspector::page_loaded(last_request);
 		}
+
+		(list, info)
 	}
 
 	/// Continue this page, adds items to the passed `Vec`s
@@ -257,43 +740,61 @@ impl Page {
 					..BrowseRequest::new(easy).expect("Youtube should set `__Secure-YEC` cookie")
 				};
 
-				*continuation = from_slice::<ContinuationResponse<RichGridRendererContent>>(
-					&mut request_post(easy, "https://www.youtube.com/youtubei/v1/browse", &request),
-				)
-				.expect("Continuation JSON should be valid")
-				.into_widgets(list, info_vec);
+				*continuation =
+					from_slice::<ContinuationResponse<RichGridRendererContent>>(&mut captured(
+						"browse_continuation",
+						"json",
+						request_post(easy, &api_url("browse"), &request),
+					))
+					.expect("Continuation JSON should be valid")
+					.into_widgets(list, info_vec);
 			}
 			Page::Game {
 				continuation: continuation @ Some(_),
 				..
+			}
+			| Page::Trending {
+				continuation: continuation @ Some(_),
+				..
+			}
+			| Page::Explore {
+				continuation: continuation @ Some(_),
+				..
 			} => {
 				let request = BrowseRequest {
 					continuation: continuation.take(),
 					..BrowseRequest::new(easy).expect("Youtube should set `__Secure-YEC` cookie")
 				};
 
-				*continuation = from_slice::<ContinuationResponse<RichGridRendererContent>>(
-					&mut request_post(easy, "https://www.youtube.com/youtubei/v1/browse", &request),
-				)
-				.expect("Continuation JSON should be valid")
-				.into_widgets(list, info_vec);
+				*continuation =
+					from_slice::<ContinuationResponse<RichGridRendererContent>>(&mut captured(
+						"browse_continuation",
+						"json",
+						request_post(easy, &api_url("browse"), &request),
+					))
+					.expect("Continuation JSON should be valid")
+					.into_widgets(list, info_vec);
 			}
 			Page::Search {
 				continuation: continuation @ Some(_),
 				..
 			} => {
-				*continuation = from_slice::<SearchContinuationResponse>(&mut request_post(
-					easy,
-					"https://www.youtube.com/youtubei/v1/search",
-					&BrowseRequest {
-						continuation: continuation.take(),
-						..BrowseRequest::default()
-					},
+				*continuation = from_slice::<SearchContinuationResponse>(&mut captured(
+					"search_continuation",
+					"json",
+					request_post(
+						easy,
+						&api_url("search"),
+						&BrowseRequest {
+							continuation: continuation.take(),
+							..BrowseRequest::default()
+						},
+					),
 				))
 				.expect("Continuation JSON should be valid")
 				.into_widgets(list, info_vec);
 			}
-			Page::Next {
+			Page::Hashtag {
 				continuation: continuation @ Some(_),
 				..
 			} => {
@@ -302,46 +803,142 @@ impl Page {
 					..BrowseRequest::new(easy).expect("Youtube should set `__Secure-YEC` cookie")
 				};
 
-				*continuation = from_slice::<ContinuationResponse<SecondaryResultsResult>>(
-					&mut request_post(easy, "https://www.youtube.com/youtubei/v1/next", &request),
-				)
+				*continuation = from_slice::<ContinuationResponse<HashtagItem>>(&mut captured(
+					"hashtag_continuation",
+					"json",
+					request_post(easy, &api_url("browse"), &request),
+				))
 				.expect("Continuation JSON should be valid")
 				.into_widgets(list, info_vec);
 			}
-			Page::CommentSection {
+			Page::Next {
 				continuation: continuation @ Some(_),
 				..
 			} => {
+				let request = BrowseRequest {
+					continuation: continuation.take(),
+					..BrowseRequest::new(easy).expect("Youtube should set `__Secure-YEC` cookie")
+				};
+
 				*continuation =
-					from_slice::<ContinuationResponse<ContinuationItem>>(&mut request_post(
-						easy,
-						"https://www.youtube.com/youtubei/v1/next",
-						&BrowseRequest {
-							continuation: continuation.take(),
-							..BrowseRequest::default()
-						},
+					from_slice::<ContinuationResponse<SecondaryResultsResult>>(&mut captured(
+						"next_continuation",
+						"json",
+						request_post(easy, &api_url("next"), &request),
 					))
-					.expect("Comments JSON should be valid")
+					.expect("Continuation JSON should be valid")
 					.into_widgets(list, info_vec);
 			}
+			Page::CommentSection {
+				continuation: continuation @ Some(_),
+				endpoint,
+				filter,
+				..
+			} => continue_comments::<ContinuationItem>(
+				easy,
+				"comments_continuation",
+				endpoint,
+				continuation,
+				filter,
+				list,
+				info_vec,
+			),
 			Page::Comment {
+				continuation: continuation @ Some(_),
+				endpoint,
+				filter,
+				..
+			} => continue_comments::<Comment>(
+				easy,
+				"replies",
+				endpoint,
+				continuation,
+				filter,
+				list,
+				info_vec,
+			),
+			Page::Community {
 				continuation: continuation @ Some(_),
 				..
 			} => {
-				*continuation = from_slice::<ContinuationResponse<Comment>>(&mut request_post(
-					easy,
-					"https://www.youtube.com/youtubei/v1/next",
-					&BrowseRequest {
-						continuation: continuation.take(),
-						..BrowseRequest::default()
-					},
+				*continuation = from_slice::<ContinuationResponse<CommunityItem>>(&mut captured(
+					"community_continuation",
+					"json",
+					request_post(
+						easy,
+						&api_url("browse"),
+						&BrowseRequest {
+							continuation: continuation.take(),
+							..BrowseRequest::default()
+						},
+					),
 				))
-				.expect("Next JSON should be valid")
+				.expect("Community JSON should be valid")
+				.into_widgets(list, info_vec);
+			}
+			Page::Shorts {
+				continuation: continuation @ Some(_),
+				..
+			} => {
+				*continuation = from_slice::<ReelWatchSequenceResponse>(&mut captured(
+					"reel_watch_sequence",
+					"json",
+					request_post(
+						easy,
+						&api_url("reel/reel_watch_sequence"),
+						&ReelWatchSequenceRequest {
+							// Continuation tokens are sent in the same place
+							sequenceParams: continuation.take().expect("Matched `Some` above"),
+							..ReelWatchSequenceRequest::default()
+						},
+					),
+				))
+				.expect("Shorts sequence JSON should be valid")
+				.into_widgets(list, info_vec);
+			}
+			Page::LiveChat {
+				continuation: continuation @ Some(_),
+				timeout,
+				..
+			} => {
+				(*continuation, *timeout) = poll_live_chat(
+					easy,
+					continuation.take().expect("Matched `Some` above"),
+					list,
+					info_vec,
+				);
+			}
+			Page::ChatReplay {
+				continuation: continuation @ Some(_),
+				offset,
+				..
+			} => {
+				*continuation = from_slice::<LiveChatReplayResponse>(&mut captured(
+					"live_chat_replay",
+					"json",
+					request_post(
+						easy,
+						&api_url("live_chat/get_live_chat_replay"),
+						&LiveChatReplayRequest {
+							continuation: continuation.take().expect("Matched `Some` above"),
+							currentPlayerState: CurrentPlayerState {
+								playerOffsetMs: offset.to_string(),
+							},
+							..LiveChatReplayRequest::default()
+						},
+					),
+				))
+				.expect("Chat replay JSON should be valid")
 				.into_widgets(list, info_vec)
+				.0;
 			}
 			// No continuation token or can't be continued
 			_ => (),
 		}
+
+		if self.filtered() {
+			filter::apply(&self.identity(), list, info_vec);
+		}
 	}
 }
 impl ToString for Page {
@@ -351,26 +948,821 @@ impl ToString for Page {
 			Page::Home { .. } => "Home",
 			Page::Category { .. } => "A category",
 			Page::Game { .. } => "A game",
-			Page::Search { query, .. } => query,
+			Page::Trending { .. } => "Trending",
+			Page::Explore { .. } => "Explore",
+			Page::Search { query, filters, .. } => {
+				return match filters.summary().join(", ") {
+					summary if summary.is_empty() => query.clone(),
+					summary => [query, " (", &summary, ")"].concat(),
+				};
+			}
+			Page::SearchFilters { query, .. } => return ["Filters for \"", query, "\""].concat(),
+			Page::Hashtag { tag, .. } => return ["#", tag].concat(),
+			Page::BookmarkList { name, .. } | Page::LocalPlaylist { name, .. } => {
+				return name.clone()
+			}
 			Page::Next { .. } => "Recommendations",
 			Page::Transcript { .. } => "Transcript",
-			Page::CommentSection { .. } => "Comments",
+			Page::CommentSection {
+				sorts,
+				sort,
+				filter,
+				tree,
+				..
+			} => {
+				let mut title = "Comments".to_owned();
+
+				if let Some((name, _)) = sorts.get(*sort) {
+					title = [&title, " (", name, ")"].concat();
+				}
+
+				if *tree {
+					title += " as a tree";
+				}
+
+				return match filter {
+					Some(filter) => [&title, " matching \"", filter, "\""].concat(),
+					None => title,
+				};
+			}
+			Page::Comment {
+				filter: Some(filter),
+				..
+			} => return ["Replies matching \"", filter, "\""].concat(),
 			Page::Comment { .. } => "A comment",
+			Page::Community { .. } => "Community",
+			Page::Shorts { .. } => "Shorts",
+			Page::LiveChat {
+				continuation: None, ..
+			} => "Live chat (ended)",
+			Page::LiveChat { .. } => "Live chat",
+			Page::ChatReplay {
+				player: Some(_), ..
+			} => "Chat replay (following the player)",
+			Page::ChatReplay { .. } => "Chat replay",
+			Page::Links { .. } => "Links",
+			Page::SearchHistory { .. } => "Search history",
+			Page::Bookmarks { .. } => "Bookmarks",
+			Page::Playlists { .. } => "Playlists",
+			Page::Inspector { .. } => "Requests",
 		}
 		.to_owned()
 	}
 }
 
+/// Requests a page made of shelves (`GeneralResponse`), i.e. a game's or trending, returning its
+/// items and continuation token
+fn browse_general<'a>(
+	easy: &mut Easy,
+	browse_id: &str,
+	params: &Option<String>,
+) -> (
+	Vec<ListItem<'a>>,
+	Vec<(Paragraph<'a>, Node)>,
+	Option<String>,
+) {
+	let request = BrowseRequest {
+		browseId: Some(browse_id.to_owned()),
+		params: params.clone(),
+		..BrowseRequest::new(easy).expect("Youtube should set `__Secure-YEC` cookie")
+	};
+
+	from_slice::<GeneralResponse>(&mut captured(
+		"general",
+		"json",
+		request_post(easy, &api_url("browse"), &request),
+	))
+	.expect("Browse JSON should be valid")
+	.into_widgets()
+}
+
+/// Gets new live chat messages, returning the token and timeout to poll with next (`None` once the
+/// stream has ended)
+fn poll_live_chat<'a>(
+	easy: &mut Easy,
+	continuation: String,
+	list: &mut Vec<ListItem<'a>>,
+	info_vec: &mut Vec<(Paragraph<'a>, Node)>,
+) -> (Option<String>, Duration) {
+	from_slice::<LiveChatResponse>(&mut captured(
+		"live_chat",
+		"json",
+		request_post(
+			easy,
+			&api_url("live_chat/get_live_chat"),
+			&BrowseRequest {
+				continuation: Some(continuation),
+				..BrowseRequest::default()
+			},
+		),
+	))
+	.expect("Live chat JSON should be valid")
+	.into_widgets(list, info_vec)
+	.map_or(
+		(None, LIVE_CHAT_POLL_INTERVAL),
+		|(continuation, timeout)| (Some(continuation), timeout),
+	)
+}
+
+/// Gets the info needed to play a video, setting up `decipherer` from the video's watch page if it
+/// hasn't been yet. Should be called outside the TUI, returns `None` (after telling the user) if
+/// the video can't be played.
+fn video_response(
+	easy: &mut Easy,
+	decipherer: &mut Option<Decipherer>,
+	video_id: &str,
+) -> Option<VideoResponse> {
+	let video_response = if decipherer.is_some() {
+		// If JS has already been initialized, we can directly get the video data
+		match from_slice::<VideoResponse>(&mut request_post(
+			easy,
+			&api_url("player"),
+			&BrowseRequest {
+				videoId: Some(video_id.to_owned()),
+				..BrowseRequest::default()
+			},
+		)) {
+			Ok(video_response) => Some(video_response),
+			// Common error for unavailable videos, don't panic
+			Err(error)
+				if error
+					== Error::generic(ErrorType::Serde(
+						"missing field `streamingData`".to_owned(),
+					)) =>
+			{
+				None
+			}
+			// Panic on any other errors
+			Err(error) => panic!("Failed to parse video response: {error}"),
+		}
+	} else {
+		// We need to initialize js context first, download the whole page
+		let mut response = unsafe {
+			String::from_utf8_unchecked(request_get(
+				easy,
+				&[base_url(), "/watch/", video_id].concat(),
+			))
+		};
+
+		// Download the player and take the decipher functions out of it
+		*decipherer = Some(Decipherer::from_watch_page(easy, &response));
+
+		// Extract the JSON data
+		extract_json::<VideoResponse>(&mut response, "{\"re", "};", 1)
+	};
+
+	if video_response.is_none() {
+		print!("Couldn't parse response, it may be age restricted (press enter to continue) ");
+		let _ = stdout().flush();
+
+		// Wait for enter
+		let _ = stdin().read(&mut [0]);
+	}
+
+	video_response
+}
+
+/// Plays videos one after another, asking before each one after the first. Should be called
+/// outside the TUI.
+fn play_all(easy: &mut Easy, decipherer: &mut Option<Decipherer>, videos: &[Entry]) {
+	let mut index = 0;
+
+	while let Some(video) = videos.get(index) {
+		println!("{}/{}: {}", index + 1, videos.len(), video.title);
+
+		// Unavailable videos are skipped, after telling the user
+		if let Some(video_response) = video_response(easy, decipherer, &video.id) {
+			video_response.play(
+				decipherer.as_mut().expect("Should have been set up"),
+				false,
+				0,
+				&[],
+			);
+		}
+
+		if index + 1 == videos.len() {
+			break;
+		}
+
+		print!("Next: enter, previous: p, stop: q ");
+		let _ = stdout().flush();
+
+		let mut input = String::new();
+		let _ = stdin().read_line(&mut input);
+
+		match input.trim() {
+			"q" | "Q" => break,
+			"p" | "P" => index = index.saturating_sub(1),
+			_ => index += 1,
+		}
+	}
+}
+
+/// The chapters of a video listed on the page (start in milliseconds and title), to pass to the
+/// player
+fn chapters(info_vec: &[(Paragraph, Node)], video_id: &str) -> Vec<(u64, String)> {
+	info_vec
+		.iter()
+		.filter_map(|(_, node)| match node {
+			Node::Chapter(chapter_video_id, start, title) if chapter_video_id == video_id => {
+				Some((*start, title.clone()))
+			}
+			_ => None,
+		})
+		.collect()
+}
+
+/// Continues a comment section or a comment's replies, adding the comments that match `filter`.
+/// With a filter, more are requested until one matches (up to `COMMENT_FILTER_SEARCH_LIMIT`
+/// requests).
+fn continue_comments<'a, T: DeserializeOwned + IntoWidgets + Filter>(
+	easy: &mut Easy,
+	name: &str,
+	endpoint: &str,
+	continuation: &mut Option<String>,
+	filter: &Option<String>,
+	list: &mut Vec<ListItem<'a>>,
+	info_vec: &mut Vec<(Paragraph<'a>, Node)>,
+) {
+	let length = info_vec.len();
+
+	for _ in 0..COMMENT_FILTER_SEARCH_LIMIT.max(1) {
+		let Some(token) = continuation.take() else {
+			break;
+		};
+
+		let mut response = from_slice::<ContinuationResponse<T>>(&mut captured(
+			name,
+			"json",
+			request_post(
+				easy,
+				&api_url(endpoint),
+				&BrowseRequest {
+					continuation: Some(token),
+					..BrowseRequest::default()
+				},
+			),
+		))
+		.expect("Comments JSON should be valid");
+
+		if let Some(filter) = filter {
+			response.retain(filter);
+		}
+
+		*continuation = response.into_widgets(list, info_vec);
+
+		if filter.is_none() || info_vec.len() != length {
+			break;
+		}
+	}
+}
+
+/// Shows or hides a thread's replies under its comment, in a comment section's tree mode. `token`
+/// is from the item at `index`, either a comment (for its replies) or the end of a thread's shown
+/// replies (for more of them). Only replies that match the page's `filter` are shown.
+#[allow(clippy::too_many_arguments)]
+fn toggle_replies<'a>(
+	easy: &mut Easy,
+	endpoint: &str,
+	threads: &mut Vec<(String, usize, Option<String>)>,
+	filter: &Option<String>,
+	token: String,
+	index: usize,
+	list: &mut Vec<ListItem<'a>>,
+	info_vec: &mut Vec<(Paragraph<'a>, Node)>,
+) {
+	// Replies go after the comment and its links
+	let start = index + 1 + matches!(info_vec.get(index + 1), Some((_, Node::Links(_)))) as usize;
+
+	// Collapse
+	if let Some(position) = threads.iter().position(|(replies, ..)| *replies == token) {
+		let (_, length, _) = threads.remove(position);
+
+		list.drain(start..start + length);
+		info_vec.drain(start..start + length);
+
+		return;
+	}
+
+	let mut replies_list = Vec::new();
+	let mut replies_info = Vec::new();
+	let mut more = Some(token.clone());
+
+	continue_comments::<Reply>(
+		easy,
+		"replies",
+		endpoint,
+		&mut more,
+		filter,
+		&mut replies_list,
+		&mut replies_info,
+	);
+
+	if more.is_some() {
+		replies_list.push(ListItem::new(Spans(vec![
+			REPLY_INDENT.into(),
+			Span::styled("More replies", Style::default().fg(Color::DarkGray)),
+		])));
+		replies_info.push((
+			Paragraph::new("Enter to load more replies"),
+			Node::Comment(more.clone().expect("There should be more replies")),
+		));
+	}
+
+	let length = replies_list.len();
+
+	if let Some(thread) = threads
+		.iter_mut()
+		.find(|(_, _, thread_more)| thread_more.as_ref() == Some(&token))
+	{
+		// Replace the item for more replies, which is counted, so this can't underflow even if none
+		// came back
+		thread.1 = thread.1 + length - 1;
+		thread.2 = more;
+
+		list.splice(index..=index, replies_list);
+		info_vec.splice(index..=index, replies_info);
+	} else {
+		// Expand
+		threads.push((token, length, more));
+
+		list.splice(start..start, replies_list);
+		info_vec.splice(start..start, replies_info);
+	}
+}
+
+/// Opens a URL with `url_opener` from the config, in the background
+fn open_url(url: &str) {
+	let (program, args) = url_opener(url.to_owned());
+
+	// Keep its output out of the TUI
+	let _ = Command::new(program)
+		.args(args)
+		.stdin(Stdio::null())
+		.stdout(Stdio::null())
+		.stderr(Stdio::null())
+		.spawn();
+}
+
+/// Index of the last chat replay message sent before `position` (in milliseconds)
+fn replay_index(info_vec: &[(Paragraph, Node)], position: u64) -> usize {
+	info_vec
+		.iter()
+		.rposition(|(_, node)| matches!(node, Node::ReplayMessage(offset) if *offset <= position))
+		.unwrap_or(0)
+}
+
+/// The start of the word before `cursor` in `text`, for moving back and deleting words
+fn word_start(text: &[char], cursor: usize) -> usize {
+	let end = text[..cursor]
+		.iter()
+		.rposition(|c| !c.is_whitespace())
+		.map_or(0, |i| i + 1);
+
+	text[..end]
+		.iter()
+		.rposition(|c| c.is_whitespace())
+		.map_or(0, |i| i + 1)
+}
+
+/// The end of the word after `cursor` in `text`
+fn word_end(text: &[char], cursor: usize) -> usize {
+	let start = text[cursor..]
+		.iter()
+		.position(|c| !c.is_whitespace())
+		.map_or(text.len(), |i| cursor + i);
+
+	text[start..]
+		.iter()
+		.position(|c| c.is_whitespace())
+		.map_or(text.len(), |i| start + i)
+}
+
+/// Shows a text box in the middle of the screen until enter is pressed, returning what was typed,
+/// or `None` if escape was pressed. With `history` (past entries, oldest first), suggestions are
+/// shown under the box: the past entries that contain the text, then youtube's search suggestions
+/// if `remote_suggestions` is set (and `SEARCH_SUGGESTIONS` is on). Up and down pick one, tab puts
+/// it in the box to keep typing.
+fn prompt(
+	terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+	title: &str,
+	history: Option<&[String]>,
+	remote_suggestions: bool,
+) -> Option<String> {
+	// Show cursor
+	let _ = terminal.show_cursor();
+
+	let mut text = Vec::new();
+	// Index in `text` the cursor is before
+	let mut cursor = 0;
+
+	let suggester = (remote_suggestions && SEARCH_SUGGESTIONS).then(Suggester::new);
+	// Youtube's suggestions for the text
+	let mut remote = Vec::new();
+	let mut suggestions = Vec::new();
+	let mut suggestion_state = ListState::default();
+
+	loop {
+		let query = text.iter().collect::<String>();
+
+		// Past searches that contain the text (newest first), then youtube's
+		suggestions.clear();
+		if let Some(history) = history {
+			let lowercase = query.to_lowercase();
+
+			for search in history.iter().rev() {
+				if search.to_lowercase().contains(&lowercase) && !suggestions.contains(search) {
+					suggestions.push(search.clone());
+				}
+			}
+		}
+		let past = suggestions.len();
+		for suggestion in &remote {
+			if !suggestions.contains(suggestion) {
+				suggestions.push(suggestion.clone());
+			}
+		}
+		suggestions.truncate(SUGGESTION_COUNT);
+		let past = past.min(suggestions.len());
+
+		// Make sure the picked suggestion still exists
+		suggestion_state.select(
+			suggestion_state
+				.selected()
+				.filter(|selected| *selected < suggestions.len()),
+		);
+
+		let _ = terminal.draw(|frame| {
+			// Width of the input box
+			let width = (text.len() as u16 + 3).clamp(8, frame.size().width);
+			let area = Rect {
+				x: (frame.size().width - width) / 2,
+				y: (frame.size().height / 2).saturating_sub(1),
+				width,
+				height: 3,
+			};
+
+			frame.render_widget(
+				Paragraph::new(query.clone()).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(title)
+						.title_alignment(TITLE_ALIGNMENT)
+						.border_type(BORDER_TYPE),
+				),
+				area,
+			);
+			frame.set_cursor(area.x + 1 + cursor as u16, area.y + 1);
+
+			if suggestions.is_empty() {
+				return;
+			}
+
+			// Dropdown under the box, as wide as the longest suggestion
+			let width = (suggestions
+				.iter()
+				.map(|suggestion| suggestion.chars().count())
+				.max()
+				.unwrap_or(0) as u16
+				+ 2)
+			.max(area.width)
+			.min(frame.size().width - area.x);
+			let dropdown = Rect {
+				x: area.x,
+				y: area.y + area.height,
+				width,
+				height: (suggestions.len() as u16 + 2)
+					.min(frame.size().height.saturating_sub(area.y + area.height)),
+			};
+
+			frame.render_widget(Clear, dropdown);
+			frame.render_stateful_widget(
+				List::new(
+					suggestions
+						.iter()
+						.enumerate()
+						.map(|(i, suggestion)| {
+							// Past searches are a different colour
+							ListItem::new(suggestion.clone()).style(if i < past {
+								Style::default().fg(Color::Magenta)
+							} else {
+								Style::default()
+							})
+						})
+						.collect::<Vec<ListItem>>(),
+				)
+				.block(
+					Block::default()
+						.borders(Borders::ALL)
+						.border_type(BORDER_TYPE),
+				)
+				.highlight_style(Style {
+					add_modifier: Modifier::REVERSED,
+					..Style::default()
+				}),
+				dropdown,
+				&mut suggestion_state,
+			);
+		});
+
+		// Check for suggestions every so often while waiting for input
+		if let Some(suggester) = &suggester {
+			if !poll(SUGGESTION_DELAY / 5).expect("IO error") {
+				if let Some((for_query, suggestions)) = suggester.receive() {
+					if for_query == query {
+						remote = suggestions;
+					}
+				}
+
+				continue;
+			}
+		}
+
+		let Event::Key(KeyEvent {
+			code, modifiers, ..
+		}) = read().expect("Should be able to read input")
+		else {
+			continue;
+		};
+
+		match code {
+			KeyCode::Esc => {
+				let _ = terminal.hide_cursor();
+
+				return None;
+			}
+			KeyCode::Enter => break,
+			// Delete the word before the cursor
+			KeyCode::Char('w') if modifiers.contains(KeyModifiers::CONTROL) => {
+				let start = word_start(&text, cursor);
+
+				text.drain(start..cursor);
+				cursor = start;
+			}
+			KeyCode::Backspace
+				if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+			{
+				let start = word_start(&text, cursor);
+
+				text.drain(start..cursor);
+				cursor = start;
+			}
+			KeyCode::Char(c) => {
+				text.insert(cursor, c);
+				cursor += 1;
+			}
+			KeyCode::Backspace if cursor > 0 => {
+				cursor -= 1;
+				text.remove(cursor);
+			}
+			KeyCode::Delete if cursor < text.len() => {
+				text.remove(cursor);
+			}
+			KeyCode::Left if modifiers.contains(KeyModifiers::CONTROL) => {
+				cursor = word_start(&text, cursor);
+			}
+			KeyCode::Right if modifiers.contains(KeyModifiers::CONTROL) => {
+				cursor = word_end(&text, cursor);
+			}
+			KeyCode::Left => cursor = cursor.saturating_sub(1),
+			KeyCode::Right => cursor = (cursor + 1).min(text.len()),
+			KeyCode::Home => cursor = 0,
+			KeyCode::End => cursor = text.len(),
+			KeyCode::Down if !suggestions.is_empty() => suggestion_state.select(Some(
+				suggestion_state
+					.selected()
+					.map_or(0, |selected| (selected + 1) % suggestions.len()),
+			)),
+			KeyCode::Up if !suggestions.is_empty() => suggestion_state.select(Some(
+				suggestion_state
+					.selected()
+					.map_or(suggestions.len() - 1, |selected| {
+						(selected + suggestions.len() - 1) % suggestions.len()
+					}),
+			)),
+			// Put the picked suggestion in the box
+			KeyCode::Tab => {
+				if let Some(selected) = suggestion_state.selected() {
+					text = suggestions[selected].chars().collect();
+					cursor = text.len();
+				}
+			}
+			_ => (),
+		}
+
+		// The text changed, so the suggestions did too
+		if text.iter().copied().ne(query.chars()) {
+			suggestion_state.select(None);
+			remote.clear();
+
+			if let Some(suggester) = &suggester {
+				if !text.is_empty() {
+					suggester.request(&text.iter().collect::<String>());
+				}
+			}
+		}
+	}
+
+	// Hide the cursor again
+	let _ = terminal.hide_cursor();
+
+	// The picked suggestion, or what was typed
+	Some(match suggestion_state.selected() {
+		Some(selected) => suggestions.swap_remove(selected),
+		None => text.into_iter().collect(),
+	})
+}
+
+/// Shows a message in a box in the middle of the screen. With `wait` (i.e. for errors) it's shown
+/// until a key is pressed, otherwise (i.e. for progress) until the screen is next drawn.
+fn message(terminal: &mut Terminal<CrosstermBackend<Stdout>>, title: &str, text: &str, wait: bool) {
+	let _ = terminal.draw(|frame| {
+		let width = (text
+			.lines()
+			.chain([title])
+			.map(|line| line.chars().count())
+			.max()
+			.unwrap_or(0) as u16
+			+ 2)
+		.clamp(8, frame.size().width);
+		let height = (text.lines().count() as u16 + 2).min(frame.size().height);
+		let area = Rect {
+			x: (frame.size().width - width) / 2,
+			y: (frame.size().height - height) / 2,
+			width,
+			height,
+		};
+
+		frame.render_widget(Clear, area);
+		frame.render_widget(
+			Paragraph::new(text.to_owned()).block(
+				Block::default()
+					.borders(Borders::ALL)
+					.title(title)
+					.title_alignment(TITLE_ALIGNMENT)
+					.border_type(BORDER_TYPE),
+			),
+			area,
+		);
+	});
+
+	while wait && !matches!(read().expect("IO error"), Event::Key(_)) {}
+}
+
 fn main() {
-	let mut easy = Easy::new();
+	let mut args = args().skip(1).peekable();
+
+	// `ytui comments <video id>` exports the video's comments instead of starting the TUI
+	let mut export_video_id = None;
+	let mut export_all = false;
+	let mut export_format = Format::Json;
+	let mut export_resume = None;
+
+	// `ytui search <query>` starts with a search, optionally with filters
+	let mut search_query = None;
+	let mut search_filters = SearchFilters::default();
+
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"search" => {
+				search_query = Some(args.next().expect("`search` should be given a query"));
+			}
+			// `--upload-date`, `--type`, `--duration`, `--features` or `--sort`
+			argument if FilterGroup::from_argument(argument).is_some() => {
+				let group = FilterGroup::from_argument(argument).expect("Checked above");
+				let names = args
+					.next()
+					.unwrap_or_else(|| panic!("`{argument}` should be given a filter"));
+
+				if let Err(name) = search_filters.set(group, &names) {
+					eprintln!(
+						"Unknown filter `{name}` for `{argument}`, it should be one of: {}",
+						group
+							.options()
+							.iter()
+							.map(|option| option.name)
+							.collect::<Vec<&str>>()
+							.join(", ")
+					);
+					return;
+				}
+			}
+			"comments" => {
+				export_video_id = Some(args.next().expect("`comments` should be given a video ID"));
+			}
+			// Every page of comments and their replies, not just the first page
+			"--all" => export_all = true,
+			"--format" => {
+				export_format = Format::new(&args.next().unwrap_or_default())
+					.expect("`--format` should be `json` or `csv`");
+			}
+			// Continue an interrupted export from the token it printed
+			"--resume" => {
+				export_resume = Some(args.next().expect("`--resume` should be given a token"));
+			}
+			// `ytui capture [directory]` saves every response it parses, to be used as test
+			// fixtures
+			"capture" => {
+				let _ = CAPTURE_DIRECTORY.set(
+					args.next_if(|arg| !arg.starts_with("--"))
+						.unwrap_or_else(|| "tests/fixtures".to_owned())
+						.into(),
+				);
+			}
+			// Use a different server, i.e. a mock one
+			"--base-url" => {
+				let _ =
+					BASE_URL_OVERRIDE.set(args.next().expect("`--base-url` should be given a URL"));
+			}
+			// Log every request (and anything youtube sends that we don't understand) to a file
+			"--log" => {
+				let _ = LOG_FILE.set(args.next().expect("`--log` should be given a file").into());
+			}
+			// Keep search history (and such) somewhere else
+			"--data-dir" => {
+				let _ = DATA_DIRECTORY_OVERRIDE.set(
+					args.next()
+						.expect("`--data-dir` should be given a directory")
+						.into(),
+				);
+			}
+			// Youtube's language (`hl`) and region (`gl`), i.e. `--language ja --region JP`
+			"--language" => {
+				let language = args
+					.next()
+					.expect("`--language` should be given a language");
+				set_locale(|locale| locale.language = Some(language));
+			}
+			"--region" => {
+				let region = args.next().expect("`--region` should be given a region");
+				set_locale(|locale| locale.region = Some(region.to_uppercase()));
+			}
+			"--time-zone" => {
+				let time_zone = args
+					.next()
+					.expect("`--time-zone` should be given a time zone");
+				set_locale(|locale| locale.time_zone = Some(time_zone));
+			}
+			// Minutes ahead of UTC
+			"--utc-offset" => {
+				let offset = args
+					.next()
+					.and_then(|offset| offset.parse().ok())
+					.expect("`--utc-offset` should be given a number of minutes");
+				set_locale(|locale| locale.utc_offset_minutes = Some(offset));
+			}
+			_ => {
+				eprintln!("Unknown argument `{arg}`");
+				return;
+			}
+		}
+	}
+
+	let mut easy = new_easy();
+
+	if let Some(video_id) = export_video_id {
+		// Sets the visitor cookie, like the home page does
+		request_get(&mut easy, &home_url());
 
-	// Enable cookie engine
-	let _ = easy.cookie_file("");
+		let token = export_resume.clone().or_else(|| {
+			// The token for the comments is on the video's page
+			Page::Next {
+				video_id,
+				continuation: None,
+				previous: (Box::new(Page::Home { continuation: None }), 0),
+			}
+			.request(&mut easy)
+			.1
+			.into_iter()
+			.find_map(|(_, node)| match node {
+				Node::CommentSection(token) => Some(token),
+				_ => None,
+			})
+		});
+
+		match token {
+			Some(token) => {
+				if let Err(error) = export_comments(
+					&mut easy,
+					"next",
+					token,
+					export_resume.is_some(),
+					export_all,
+					export_format,
+					&mut stdout(),
+					&mut |count, token| match token {
+						Some(token) => eprintln!("Exported {count} comments, resume from {token}"),
+						None => eprintln!("Exported {count} comments"),
+					},
+				) {
+					eprintln!("{error}");
+				}
+			}
+			None => eprintln!("This video has no comments"),
+		}
 
-	let mut easy_list = easy::List::new();
-	// Youtube needs the header for post requests
-	let _ = easy_list.append("CONTENT-TYPE:");
-	let _ = easy.http_headers(easy_list);
+		return;
+	}
 
 	let hook = take_hook();
 	// Run cleanup code on panic
@@ -382,12 +1774,26 @@ fn main() {
 
 	let mut page = Page::Home { continuation: None };
 
-	// Cached JS script (it's used to decrypt stuff to avoid throttling)
-	let mut js_script = None;
+	// Cached decipher functions from the player JS (used to decrypt stuff to avoid throttling)
+	let mut decipherer = None;
 
 	// Fetch data
 	let (mut list, mut info_vec) = page.request(&mut easy);
 
+	if let Some(query) = search_query {
+		history::record(&query, search_filters.params().as_deref());
+
+		page = Page::Search {
+			query,
+			params: search_filters.params(),
+			filters: search_filters,
+			continuation: None,
+			previous: (Box::new(page), 0),
+		};
+
+		(list, info_vec) = page.request(&mut easy);
+	}
+
 	let mut ratatui_list = List::new(list.clone()).highlight_style(Style {
 		add_modifier: Modifier::REVERSED,
 		..Style::default()
@@ -410,8 +1816,64 @@ fn main() {
 	let mut list_state = ListState::default();
 	list_state.select(Some(0));
 
+	// Index of the replay message selected by following the player, following stops if the user
+	// selects something else
+	let mut followed = None;
+
 	loop {
 		if redraw {
+			let mut help = vec![
+				"back: b".into(),
+				"search: s".into(),
+				"refresh: r".into(),
+				"next: n".into(),
+				"community: c".into(),
+				"filter comments or search: f".into(),
+				"export comments, bookmarks or playlist: x".into(),
+				"comment tree: e".into(),
+				"sort comments: o".into(),
+				"search history: y".into(),
+				"bookmark: a".into(),
+				"bookmarks: w".into(),
+				"add to playlist: v".into(),
+				"playlists: m".into(),
+				"hide watched: u".into(),
+				"block channel: !".into(),
+				"home: h".into(),
+				"trending: ctrl+t".into(),
+				"explore: ctrl+e".into(),
+				"language and region: ctrl+l".into(),
+				"requests: i".into(),
+				"quit: q".into(),
+			];
+
+			// Keys that only do something on this page
+			match page {
+				Page::ChatReplay { .. } => {
+					help.splice(0..0, ["play with chat: p".into(), "seek chat: t".into()]);
+				}
+				Page::SearchHistory { .. } => {
+					help.splice(0..0, ["delete: d".into(), "record searches: p".into()]);
+				}
+				Page::Bookmarks { .. } | Page::BookmarkList { .. } => {
+					help.insert(0, "delete: d".into());
+				}
+				Page::Playlists { .. } => {
+					help.splice(0..0, ["delete: d".into(), "import: g".into()]);
+				}
+				Page::LocalPlaylist { .. } => {
+					help.splice(
+						0..0,
+						[
+							"delete: d".into(),
+							"play shuffled: z".into(),
+							"move: ctrl+up/down".into(),
+						],
+					);
+				}
+				_ => (),
+			}
+
 			let _ = terminal.draw(|frame| {
 				// Left panel border
 				frame.render_widget(
@@ -460,21 +1922,15 @@ fn main() {
 					},
 				);
 
-				// Bottom-right panel text
+				// Bottom-right panel text, as much of it as fits
+				let height = (help.len() as u16 + 1).min(frame.size().height.saturating_sub(2));
 				frame.render_widget(
-					Paragraph::new(vec![
-						"back: b".into(),
-						"search: s".into(),
-						"refresh: r".into(),
-						"next: n".into(),
-						"quit: q".into(),
-					])
-					.alignment(Alignment::Right),
+					Paragraph::new(help.clone()).alignment(Alignment::Right),
 					Rect {
 						x: frame.size().width / 2 + 2,
-						y: frame.size().height - 7,
-						width: (frame.size().width - 7) / 2,
-						height: 7,
+						y: frame.size().height - height,
+						width: frame.size().width.saturating_sub(7) / 2,
+						height,
 					},
 				);
 			});
@@ -482,40 +1938,221 @@ fn main() {
 
 		redraw = true;
 
+		// Poll live chat while waiting for input
+		if let Page::LiveChat {
+			continuation: Some(_),
+			timeout,
+			..
+		} = page
+		{
+			if !poll(timeout).expect("IO error") {
+				let selected = list_state.selected().expect("Something should be selected");
+				// Only follow new messages if the newest one was selected, so scrolling back
+				// isn't interrupted
+				let at_bottom = selected + 1 == info_vec.len();
+
+				page.r#continue(&mut easy, &mut list, &mut info_vec);
+
+				// Drop the oldest messages
+				let excess = info_vec.len().saturating_sub(LIVE_CHAT_SCROLLBACK);
+				list.drain(..excess);
+				info_vec.drain(..excess);
+
+				list_state.select(Some(if at_bottom {
+					info_vec.len() - 1
+				} else {
+					selected.saturating_sub(excess)
+				}));
+
+				ratatui_list = List::new(list.clone()).highlight_style(Style {
+					add_modifier: Modifier::REVERSED,
+					..Style::default()
+				});
+
+				continue;
+			}
+		}
+
+		// Follow the player while a chat replay is playing alongside it (its position isn't known
+		// until it's started)
+		if let Page::ChatReplay {
+			player: Some(ref mut player),
+			offset,
+			ref continuation,
+			..
+		} = page
+		{
+			if !poll(CHAT_REPLAY_POLL_INTERVAL).expect("IO error") {
+				if !matches!(player.try_wait(), Ok(None)) {
+					// The player was closed
+					if let Page::ChatReplay { player, .. } = &mut page {
+						*player = None;
+					}
+				} else if let Some(position) = player::position() {
+					// Time of the last loaded message
+					let loaded = info_vec
+						.iter()
+						.rev()
+						.find_map(|(_, node)| match node {
+							Node::ReplayMessage(offset) => Some(*offset),
+							_ => None,
+						})
+						.unwrap_or(offset);
+
+					// The player was seeked outside the loaded messages
+					if position < offset || position > loaded + CHAT_REPLAY_SEEK_THRESHOLD {
+						if let Page::ChatReplay { offset, .. } = &mut page {
+							*offset = position;
+						}
+
+						(list, info_vec) = page.request(&mut easy);
+						followed = None;
+					} else if position > loaded && continuation.is_some() {
+						page.r#continue(&mut easy, &mut list, &mut info_vec);
+					}
+
+					if followed.is_none() || followed == list_state.selected() {
+						let index = replay_index(&info_vec, position);
+
+						list_state.select(Some(index));
+						followed = Some(index);
+					}
+				}
+
+				ratatui_list = List::new(list.clone()).highlight_style(Style {
+					add_modifier: Modifier::REVERSED,
+					..Style::default()
+				});
+
+				continue;
+			}
+		}
+
 		// Read input
 		match read().expect("IO error") {
-			Event::Key(KeyEvent { code, .. }) => match code {
+			Event::Key(KeyEvent {
+				code, modifiers, ..
+			}) => match code {
 				// Quit
 				KeyCode::Char('Q' | 'q') => break,
-				// Move down
-				KeyCode::Down | KeyCode::Char('J' | 'j') => {
-					list_state.select(list_state.selected().map(|s| {
-						// Load next page if we're at the bottom
-						if s + 1 == info_vec.len() {
-							page.r#continue(&mut easy, &mut list, &mut info_vec);
+				// Trending and explore
+				KeyCode::Char('T' | 't' | 'E' | 'e')
+					if modifiers.contains(KeyModifiers::CONTROL) =>
+				{
+					let trending = matches!(code, KeyCode::Char('T' | 't'));
+
+					match page {
+						Page::Trending { .. } if trending => redraw = false,
+						Page::Explore { .. } if !trending => redraw = false,
+						_ => {
+							let previous = (
+								Box::new(page),
+								list_state.selected().expect("Something should be selected"),
+							);
+
+							page = if trending {
+								Page::Trending {
+									params: None,
+									continuation: None,
+									previous,
+								}
+							} else {
+								Page::Explore {
+									params: None,
+									continuation: None,
+									previous,
+								}
+							};
 
+							// Move cursor to the top
+							list_state.select(Some(0));
+
+							(list, info_vec) = page.request(&mut easy);
 							ratatui_list = List::new(list.clone()).highlight_style(Style {
 								add_modifier: Modifier::REVERSED,
 								..Style::default()
 							});
 						}
-
-						info_vec.len().min(s + 2) - 1
-					}))
-				}
-				// Move up
-				KeyCode::Up | KeyCode::Char('K' | 'k') => {
-					list_state.select(list_state.selected().map(|s| s.saturating_sub(1)))
+					}
 				}
-				KeyCode::PageDown => list_state.select(list_state.selected().map(|s| {
-					let new_position = s
-						+ (terminal
-							.size()
-							.expect("Should be able to get terminal height")
-							.height / 2) as usize;
+				// Switch region and language, then load the page again in them
+				KeyCode::Char('L' | 'l') if modifiers.contains(KeyModifiers::CONTROL) => {
+					// Suggestions are shown last first
+					let locales = LOCALES
+						.iter()
+						.rev()
+						.map(|locale| locale.to_string())
+						.collect::<Vec<String>>();
+
+					if let Some(text) = prompt(
+						&mut terminal,
+						&format!("Region and language (now {})", locale()),
+						Some(&locales),
+						false,
+					) {
+						set_locale(|locale| locale.switch(&text));
 
-					// Load next page if we've gone past the end
-					if info_vec.len() <= new_position + 1 {
+						(list, info_vec) = page.request(&mut easy);
+						ratatui_list = List::new(list.clone()).highlight_style(Style {
+							add_modifier: Modifier::REVERSED,
+							..Style::default()
+						});
+
+						// Make sure the cursor isn't past the end of the data
+						list_state.select(list_state.selected().map(|s| s.min(info_vec.len() - 1)));
+					}
+				}
+				// Move a video in a local playlist
+				KeyCode::Down | KeyCode::Up if modifiers.contains(KeyModifiers::CONTROL) => {
+					let selected = list_state.selected().expect("Something should be selected");
+
+					if let (Page::LocalPlaylist { name, .. }, Node::PlaylistEntry(index)) =
+						(&page, &info_vec[selected].1)
+					{
+						if let Some(new_index) = playlists::shift(name, *index, code == KeyCode::Up)
+						{
+							(list, info_vec) = page.request(&mut easy);
+							ratatui_list = List::new(list.clone()).highlight_style(Style {
+								add_modifier: Modifier::REVERSED,
+								..Style::default()
+							});
+
+							// Keep it selected, the first item is the playlist's link
+							list_state.select(Some(new_index + 1));
+						}
+					} else {
+						redraw = false;
+					}
+				}
+				// Move down
+				KeyCode::Down | KeyCode::Char('J' | 'j') => {
+					list_state.select(list_state.selected().map(|s| {
+						// Load next page if we're at the bottom
+						if s + 1 == info_vec.len() {
+							page.r#continue(&mut easy, &mut list, &mut info_vec);
+
+							ratatui_list = List::new(list.clone()).highlight_style(Style {
+								add_modifier: Modifier::REVERSED,
+								..Style::default()
+							});
+						}
+
+						info_vec.len().min(s + 2) - 1
+					}))
+				}
+				// Move up
+				KeyCode::Up | KeyCode::Char('K' | 'k') => {
+					list_state.select(list_state.selected().map(|s| s.saturating_sub(1)))
+				}
+				KeyCode::PageDown => list_state.select(list_state.selected().map(|s| {
+					let new_position = s
+						+ (terminal
+							.size()
+							.expect("Should be able to get terminal height")
+							.height / 2) as usize;
+
+					// Load next page if we've gone past the end
+					if info_vec.len() <= new_position + 1 {
 						page.r#continue(&mut easy, &mut list, &mut info_vec);
 
 						ratatui_list = List::new(list.clone()).highlight_style(Style {
@@ -553,134 +2190,135 @@ fn main() {
 							true
 						}
 						Node::Video(video_id) => {
-							// Video
-							if let Some(ref mut script) = js_script {
-								// If JS has already been initialized, we can directly get the video
-								// data and play it
-								match from_slice::<VideoResponse>(&mut request_post(
-									&mut easy,
-									"https://www.youtube.com/youtubei/v1/player",
-									&BrowseRequest {
-										videoId: Some(video_id.clone()),
-										..BrowseRequest::default()
-									},
-								)) {
-									Ok(parsed_response) => parsed_response.play(script),
-									// Common error for unavailable videos, don't panic
-									Err(error)
-										if error
-											== Error::generic(ErrorType::Serde(
-												"missing field `streamingData`".to_owned(),
-											)) =>
-									{
-										// Temporarily move into normal terminal
-										let _ = disable_raw_mode();
-										let _ = execute!(stdout(), LeaveAlternateScreen);
-
-										print!(
-											"Couldn't parse response, it may be age restricted \
-											 (press enter to continue) "
-										);
-
-										// Wait for enter
-										let _ = stdin().read(&mut [0]);
-
-										let _ = enable_raw_mode();
-										let _ = execute!(stdout(), EnterAlternateScreen);
+							leave_tui();
+
+							if let Some(video_response) =
+								video_response(&mut easy, &mut decipherer, video_id)
+							{
+								video_response.play(
+									decipherer.as_mut().expect("Should have been set up"),
+									false,
+									0,
+									&chapters(&info_vec, video_id),
+								);
+							}
+
+							enter_tui();
+
+							// Don't reload page
+							false
+						}
+						Node::Chapter(video_id, start, _) | Node::Timestamp(video_id, start) => {
+							leave_tui();
+
+							if let Some(video_response) =
+								video_response(&mut easy, &mut decipherer, video_id)
+							{
+								video_response.play(
+									decipherer.as_mut().expect("Should have been set up"),
+									false,
+									*start,
+									&chapters(&info_vec, video_id),
+								);
+							}
+
+							enter_tui();
+
+							// Don't reload page
+							false
+						}
+						Node::Short(video_id, sequence_params) => {
+							if let Page::Shorts { .. } = page {
+								leave_tui();
+
+								let mut index =
+									list_state.selected().expect("Something should be selected");
+
+								// Play shorts back to back until the user stops
+								while let Node::Short(video_id, _) = &info_vec[index].1 {
+									if !video_response(&mut easy, &mut decipherer, video_id)
+										.is_some_and(|video_response| {
+											video_response.play(
+												decipherer
+													.as_mut()
+													.expect("Should have been set up"),
+												true,
+												0,
+												&[],
+											)
+										}) {
+										break;
+									}
+
+									print!("Next: enter, previous: p, back to the list: q ");
+									let _ = stdout().flush();
+
+									let mut input = String::new();
+									let _ = stdin().read_line(&mut input);
+
+									match input.trim() {
+										"q" | "Q" => break,
+										"p" | "P" => index = index.saturating_sub(1),
+										_ => {
+											// Load more if this was the last one
+											if index + 1 == info_vec.len() {
+												page.r#continue(
+													&mut easy,
+													&mut list,
+													&mut info_vec,
+												);
+
+												ratatui_list = List::new(list.clone())
+													.highlight_style(Style {
+														add_modifier: Modifier::REVERSED,
+														..Style::default()
+													});
+											}
+
+											if index + 1 == info_vec.len() {
+												// No more shorts
+												break;
+											}
+
+											index += 1;
+										}
 									}
-									// Panic on any other errors
-									Err(error) => panic!("Failed to parse video response: {error}"),
 								}
-							} else {
-								// We need to initialize js context first, download the whole page
-								let mut response = unsafe {
-									String::from_utf8_unchecked(request_get(
-										&mut easy,
-										&["https://www.youtube.com/watch/", video_id].concat(),
-									))
-								};
 
-								// Find the current name of the player JS
-								let start = response
-									.find("c=\"/")
-									.expect("Video page should have the /s/player/.../base.js")
-									+ 3;
+								// Select the last one played
+								list_state.select(Some(index));
 
-								// Download the player
-								let mut player = unsafe {
-									String::from_utf8_unchecked(request_get(
-										&mut easy,
-										&[
-											"https://www.youtube.com",
-											&response[start
-												..start
-													+ response[start..].find('"').expect(
-														"Video page should have the \
-														 /s/player/.../base.js",
-													)],
-										]
-										.concat(),
-									))
-								};
+								enter_tui();
 
-								// Find the current n decryption function in the player js and
-								// define it in our context
-								let n_start = player
-									.find("n(a){var b=a.sp")
-									.expect("Should be an `n` decryption function")
-									- 9;
-
-								// Use a determined function name ('f')
-								player.replace_range(n_start..n_start + 1, "f");
-
-								// Find the signature decipher function
-								let sig_start = player
-									.find("a=a.split(\"\"")
-									.expect("Should be a `sig` decryption function")
-									- 14;
-
-								// Use a determined function name ('s')
-								player.replace_range(sig_start..sig_start + 1, "s");
-
-								// Create new script and define `n` function in it
-								let mut script = Script::from_string(
-									&[
-										// `n` function
-										&player[n_start
-											..n_start
-												+ player[n_start..]
-													.find("\ng")
-													.expect("`n` function should end with this")],
-										// I don't think this changes. (between 'VF=' and '};g.W')
-										"VF={RV:function(a,b){var \
-										 c=a[0];a[0]=a[b%a.length];a[b%a.length]=c},p4:function(a,\
-										 b){a.splice(0,b)},wa:function(a){a.reverse()}};",
-										// `sig` function
-										&player[sig_start
-											..sig_start
-												+ player[sig_start..]
-													.find(";\n")
-													.expect("`sig` function should end with this")],
-									]
-									.concat(),
-								)
-								.expect("`n` function should be valid");
-
-								// Extract the JSON data
-								if let Some(parsed_response) =
-									extract_json::<VideoResponse>(&mut response, "{\"re", "};", 1)
-								{
-									parsed_response.play(&mut script);
-								} else {
-									println!("Couldn't parse response, it may be age restricted");
-								}
+								false
+							} else {
+								page = Page::Shorts {
+									video_id: video_id.clone(),
+									title: filter::title(video_id),
+									sequence_params: sequence_params.clone(),
+									continuation: None,
+									previous: (
+										Box::new(page),
+										list_state
+											.selected()
+											.expect("Something should be selected"),
+									),
+								};
 
-								js_script = Some(script);
+								true
 							}
-
-							// Don't reload page
-							false
 						}
+						// Open the tab in place of the one that's open
+						Node::Tab(tab_params) => match &mut page {
+							Page::Game { params, .. }
+							| Page::Trending { params, .. }
+							| Page::Explore { params, .. } => {
+								*params = tab_params.clone();
+
+								true
+							}
+							_ => false,
+						},
 						Node::Game(browse_id, params) => {
 							page = Page::Game {
 								browse_id: browse_id.clone(),
@@ -695,18 +2333,53 @@ fn main() {
 							true
 						}
 						Node::Search(query, params) => {
-							page = Page::Search {
-								query: query.clone(),
-								params: params.clone(),
-								continuation: None,
-								previous: (
-									Box::new(page),
-									list_state.selected().expect("Something should be selected"),
-								),
+							history::record(query, params.as_deref());
+
+							page = match page {
+								// Searching with the filters replaces the search they were
+								// opened from
+								Page::SearchFilters {
+									filters, previous, ..
+								} => Page::Search {
+									query: query.clone(),
+									params: params.clone(),
+									filters,
+									continuation: None,
+									previous: match *previous.0 {
+										Page::Search { previous, .. } => previous,
+										page => (Box::new(page), previous.1),
+									},
+								},
+								page => Page::Search {
+									query: query.clone(),
+									params: params.clone(),
+									filters: SearchFilters::from_params(params.as_deref()),
+									continuation: None,
+									previous: (
+										Box::new(page),
+										list_state
+											.selected()
+											.expect("Something should be selected"),
+									),
+								},
 							};
 
 							true
 						}
+						Node::SearchFilter(group, index) => {
+							if let Page::SearchFilters { filters, .. } = &mut page {
+								filters.toggle(*group, *index);
+							}
+
+							// Show the change without moving the cursor
+							(list, info_vec) = page.request(&mut easy);
+							ratatui_list = List::new(list.clone()).highlight_style(Style {
+								add_modifier: Modifier::REVERSED,
+								..Style::default()
+							});
+
+							false
+						}
 						Node::Channel(browse_id, params) => todo!(),
 						Node::Playlist(playlist_id) => todo!(),
 						Node::Transcript(params) => {
@@ -724,6 +2397,12 @@ fn main() {
 							page = Page::CommentSection {
 								first_continuation: first_continuation.clone(),
 								continuation: None,
+								endpoint: "next",
+								sorts: Vec::new(),
+								sort: 0,
+								filter: None,
+								tree: false,
+								threads: Vec::new(),
 								previous: (
 									Box::new(page),
 									list_state.selected().expect("Something should be selected"),
@@ -732,10 +2411,120 @@ fn main() {
 
 							true
 						}
+						Node::Comment(token)
+							if matches!(page, Page::CommentSection { tree: true, .. }) =>
+						{
+							let token = token.clone();
+							let index =
+								list_state.selected().expect("Something should be selected");
+
+							if let Page::CommentSection {
+								endpoint,
+								threads,
+								filter,
+								..
+							} = &mut page
+							{
+								toggle_replies(
+									&mut easy,
+									endpoint,
+									threads,
+									filter,
+									token,
+									index,
+									&mut list,
+									&mut info_vec,
+								);
+							}
+
+							ratatui_list = List::new(list.clone()).highlight_style(Style {
+								add_modifier: Modifier::REVERSED,
+								..Style::default()
+							});
+
+							false
+						}
 						Node::Comment(first_continuation) => {
 							page = Page::Comment {
 								first_continuation: first_continuation.clone(),
 								continuation: None,
+								endpoint: match page {
+									Page::CommentSection { endpoint, .. }
+									| Page::Comment { endpoint, .. } => endpoint,
+									_ => "next",
+								},
+								filter: None,
+								previous: (
+									Box::new(page),
+									list_state.selected().expect("Something should be selected"),
+								),
+							};
+
+							true
+						}
+						Node::Post(params) => {
+							// The post's own page has the token for its comments
+							if let Some(first_continuation) =
+								from_slice::<CommunityResponse>(&mut captured(
+									"community",
+									"json",
+									request_post(
+										&mut easy,
+										&api_url("browse"),
+										&BrowseRequest {
+											browseId: Some("FEpost_detail".to_owned()),
+											params: Some(params.clone()),
+											..BrowseRequest::default()
+										},
+									),
+								))
+								.expect("Post JSON should be valid")
+								.into_widgets()
+								.2
+							{
+								page = Page::CommentSection {
+									first_continuation,
+									continuation: None,
+									endpoint: "browse",
+									sorts: Vec::new(),
+									sort: 0,
+									filter: None,
+									tree: false,
+									threads: Vec::new(),
+									previous: (
+										Box::new(page),
+										list_state
+											.selected()
+											.expect("Something should be selected"),
+									),
+								};
+
+								true
+							} else {
+								false
+							}
+						}
+						Node::LiveChat(continuation) => {
+							page = Page::LiveChat {
+								first_continuation: continuation.clone(),
+								continuation: None,
+								timeout: LIVE_CHAT_POLL_INTERVAL,
+								previous: (
+									Box::new(page),
+									list_state.selected().expect("Something should be selected"),
+								),
+							};
+
+							true
+						}
+						Node::ChatReplay(video_id, continuation) => {
+							page = Page::ChatReplay {
+								video_id: video_id.clone(),
+								first_continuation: continuation.clone(),
+								seek_continuation: None,
+								offset: 0,
+								continuation: None,
+								player: None,
 								previous: (
 									Box::new(page),
 									list_state.selected().expect("Something should be selected"),
@@ -744,6 +2533,116 @@ fn main() {
 
 							true
 						}
+						Node::ReplayMessage(offset) => {
+							if let Page::ChatReplay {
+								player: Some(_), ..
+							} = page
+							{
+								player::seek(*offset);
+
+								// Follow the player from this message
+								followed = None;
+							}
+
+							false
+						}
+						Node::Hashtag(tag, params) => {
+							page = Page::Hashtag {
+								tag: tag.clone(),
+								params: params.clone(),
+								continuation: None,
+								previous: (
+									Box::new(page),
+									list_state.selected().expect("Something should be selected"),
+								),
+							};
+
+							true
+						}
+						Node::Url(url) => {
+							open_url(url);
+
+							false
+						}
+						Node::Links(links) => {
+							page = Page::Links {
+								links: links.clone(),
+								previous: (
+									Box::new(page),
+									list_state.selected().expect("Something should be selected"),
+								),
+							};
+
+							true
+						}
+						Node::PastSearch(index) => {
+							if let Some(search) = history::get(*index) {
+								history::record(&search.query, search.params.as_deref());
+
+								page = Page::Search {
+									query: search.query,
+									filters: SearchFilters::from_params(search.params.as_deref()),
+									params: search.params,
+									continuation: None,
+									previous: (
+										Box::new(page),
+										list_state
+											.selected()
+											.expect("Something should be selected"),
+									),
+								};
+
+								true
+							} else {
+								false
+							}
+						}
+						Node::BookmarkList(name) => {
+							page = Page::BookmarkList {
+								name: name.clone(),
+								previous: (
+									Box::new(page),
+									list_state.selected().expect("Something should be selected"),
+								),
+							};
+
+							true
+						}
+						Node::LocalPlaylist(name) => {
+							page = Page::LocalPlaylist {
+								name: name.clone(),
+								previous: (
+									Box::new(page),
+									list_state.selected().expect("Something should be selected"),
+								),
+							};
+
+							true
+						}
+						Node::PlaylistEntry(index) => {
+							if let Some(playlist) = match &page {
+								Page::LocalPlaylist { name, .. } => playlists::get(name),
+								_ => None,
+							} {
+								leave_tui();
+								play_all(&mut easy, &mut decipherer, &playlist.entries[*index..]);
+								enter_tui();
+							}
+
+							false
+						}
+						Node::Request(id) => {
+							inspector::save(*id);
+
+							// Reload the page to show where it was saved
+							(list, info_vec) = page.request(&mut easy);
+							ratatui_list = List::new(list.clone()).highlight_style(Style {
+								add_modifier: Modifier::REVERSED,
+								..Style::default()
+							});
+
+							false
+						}
 						// This can't be selected, do nothing
 						Node::None => false,
 					} {
@@ -752,6 +2651,11 @@ fn main() {
 						list_state.select(Some(0));
 
 						(list, info_vec) = page.request(&mut easy);
+
+						// Chat starts at the newest message
+						if let Page::LiveChat { .. } = page {
+							list_state.select(Some(info_vec.len() - 1));
+						}
 						ratatui_list = List::new(list.clone()).highlight_style(Style {
 							add_modifier: Modifier::REVERSED,
 							..Style::default()
@@ -767,11 +2671,26 @@ fn main() {
 						Page::Home { .. } => list_state.select(Some(0)),
 						Page::Category { previous, .. }
 						| Page::Game { previous, .. }
+						| Page::Trending { previous, .. }
+						| Page::Explore { previous, .. }
 						| Page::Search { previous, .. }
+						| Page::SearchFilters { previous, .. }
+						| Page::Hashtag { previous, .. }
 						| Page::Next { previous, .. }
 						| Page::Transcript { previous, .. }
 						| Page::CommentSection { previous, .. }
-						| Page::Comment { previous, .. } => {
+						| Page::Comment { previous, .. }
+						| Page::Community { previous, .. }
+						| Page::Shorts { previous, .. }
+						| Page::LiveChat { previous, .. }
+						| Page::ChatReplay { previous, .. }
+						| Page::Links { previous, .. }
+						| Page::SearchHistory { previous, .. }
+						| Page::Bookmarks { previous, .. }
+						| Page::BookmarkList { previous, .. }
+						| Page::Playlists { previous, .. }
+						| Page::LocalPlaylist { previous, .. }
+						| Page::Inspector { previous, .. } => {
 							page = *previous.0;
 							list_state.select(Some(previous.1.min(info_vec.len() - 1)));
 
@@ -797,53 +2716,320 @@ fn main() {
 				}
 				// Search
 				KeyCode::Char('S' | 's' | '/') => {
-					// Show cursor
-					let _ = terminal.show_cursor();
-
-					let mut query = String::new();
-
-					loop {
-						let _ = terminal.draw(|frame| {
-							// Width of the input box
-							let width = (query.len() as u16 + 3).clamp(8, frame.size().width);
-
-							frame.render_widget(
-								Paragraph::new(query.clone()).block(
-									Block::default()
-										.borders(Borders::ALL)
-										.title("Search")
-										.title_alignment(TITLE_ALIGNMENT)
-										.border_type(BORDER_TYPE),
-								),
-								Rect {
-									x: (frame.size().width - width) / 2,
-									y: frame.size().height / 2 - 1,
-									width,
-									height: 3,
-								},
-							)
+					let query = prompt(&mut terminal, "Search", Some(&history::queries()), true)
+						.unwrap_or_default();
+
+					// Don't do anything if the user typed nothing (or cancelled)
+					if !query.is_empty() {
+						let previous = (
+							Box::new(page),
+							list_state.selected().expect("Something should be selected"),
+						);
+
+						page = match query.strip_prefix('#') {
+							// A single `#tag` goes to the hashtag's page
+							Some(tag) if !tag.is_empty() && !tag.contains(char::is_whitespace) => {
+								Page::Hashtag {
+									tag: tag.to_owned(),
+									params: None,
+									continuation: None,
+									previous,
+								}
+							}
+							_ => {
+								history::record(&query, None);
+
+								Page::Search {
+									query,
+									params: None,
+									filters: SearchFilters::default(),
+									continuation: None,
+									previous,
+								}
+							}
+						};
+
+						// Move cursor to the top
+						list_state.select(Some(0));
+
+						(list, info_vec) = page.request(&mut easy);
+
+						ratatui_list = List::new(list.clone()).highlight_style(Style {
+							add_modifier: Modifier::REVERSED,
+							..Style::default()
 						});
+					}
+				}
+				// Play alongside the chat replay
+				KeyCode::Char('P' | 'p') => {
+					if let Page::ChatReplay {
+						video_id, player, ..
+					} = &mut page
+					{
+						leave_tui();
+
+						if let Some(video_response) =
+							video_response(&mut easy, &mut decipherer, video_id)
+						{
+							*player = video_response.play_in_background(
+								decipherer.as_mut().expect("Should have been set up"),
+							);
+							followed = None;
+						}
+
+						enter_tui();
+
+						let _ = terminal.clear();
+					} else if let Page::SearchHistory { .. } = page {
+						history::toggle_recording();
 
-						if let Event::Key(KeyEvent { code, .. }) =
-							read().expect("Should be able to read input")
+						(list, info_vec) = page.request(&mut easy);
+						ratatui_list = List::new(list.clone()).highlight_style(Style {
+							add_modifier: Modifier::REVERSED,
+							..Style::default()
+						});
+					} else {
+						redraw = false;
+					}
+				}
+				// Seek the chat replay (and the player, if it's playing)
+				KeyCode::Char('T' | 't') => {
+					if let Page::ChatReplay { .. } = page {
+						if let Some(position) = prompt(&mut terminal, "Seek to", None, false)
+							.as_deref()
+							.and_then(parse_timestamp)
 						{
-							match code {
-								KeyCode::Char(c) => query.push(c),
-								KeyCode::Backspace => {
-									query.pop();
+							if let Page::ChatReplay { offset, player, .. } = &mut page {
+								*offset = position;
+
+								if player.is_some() {
+									player::seek(position);
 								}
-								KeyCode::Enter => break,
-								_ => (),
+							}
+
+							(list, info_vec) = page.request(&mut easy);
+							ratatui_list = List::new(list.clone()).highlight_style(Style {
+								add_modifier: Modifier::REVERSED,
+								..Style::default()
+							});
+
+							// Select the last message before the position
+							list_state.select(Some(replay_index(&info_vec, position)));
+							followed = None;
+						}
+					} else {
+						redraw = false;
+					}
+				}
+				// Bookmark the selected item
+				KeyCode::Char('A' | 'a') => {
+					let selected = list_state.selected().expect("Something should be selected");
+
+					if bookmarks::can_save(&info_vec[selected].1) {
+						if let Some(name) = prompt(
+							&mut terminal,
+							&["Bookmark to (empty for ", DEFAULT_BOOKMARK_LIST, ")"].concat(),
+							Some(&bookmarks::names()),
+							false,
+						) {
+							// What it looks like now, it can't be requested again
+							bookmarks::add(
+								&name,
+								info_vec[selected].1.clone(),
+								widget_text(
+									List::new(vec![list[selected].clone()]),
+									list[selected].height() as u16,
+								),
+								widget_text(info_vec[selected].0.clone(), BOOKMARK_INFO_HEIGHT),
+							);
+
+							// Show it if it was added to a list that's open
+							if let Page::Bookmarks { .. } | Page::BookmarkList { .. } = page {
+								(list, info_vec) = page.request(&mut easy);
+								ratatui_list = List::new(list.clone()).highlight_style(Style {
+									add_modifier: Modifier::REVERSED,
+									..Style::default()
+								});
 							}
 						}
+					} else {
+						redraw = false;
 					}
+				}
+				// Add the selected video to a local playlist
+				KeyCode::Char('V' | 'v') => {
+					let selected = list_state.selected().expect("Something should be selected");
+
+					if let Node::Video(id) | Node::Short(id, _) = &info_vec[selected].1 {
+						let name = prompt(
+							&mut terminal,
+							"Add to playlist",
+							Some(&playlists::names()),
+							false,
+						)
+						.unwrap_or_default();
+
+						if !name.is_empty() {
+							// The first line of the title is the video's
+							let title = widget_text(
+								List::new(vec![list[selected].clone()]),
+								list[selected].height() as u16,
+							);
+
+							playlists::add(
+								&name,
+								vec![Entry {
+									id: id.clone(),
+									title: title.lines().next().unwrap_or(id).to_owned(),
+								}],
+							);
+						}
+					} else {
+						redraw = false;
+					}
+				}
+				// Local playlists
+				KeyCode::Char('M' | 'm') => {
+					if let Page::Playlists { .. } = page {
+						redraw = false;
+					} else {
+						page = Page::Playlists {
+							previous: (
+								Box::new(page),
+								list_state.selected().expect("Something should be selected"),
+							),
+						};
 
-					// Don't do anything if the user typed nothing
-					if !query.is_empty() {
-						page = Page::Search {
-							query,
-							params: None,
-							continuation: None,
+						// Move cursor to the top
+						list_state.select(Some(0));
+
+						(list, info_vec) = page.request(&mut easy);
+						ratatui_list = List::new(list.clone()).highlight_style(Style {
+							add_modifier: Modifier::REVERSED,
+							..Style::default()
+						});
+					}
+				}
+				// Import a local playlist
+				KeyCode::Char('G' | 'g') => {
+					if let Page::Playlists { .. } = page {
+						let source = prompt(
+							&mut terminal,
+							"Import from (.m3u file or watch_videos URL)",
+							None,
+							false,
+						)
+						.unwrap_or_default();
+
+						if let Some(entries) = playlists::import(&source) {
+							let name = prompt(
+								&mut terminal,
+								"Playlist name",
+								Some(&playlists::names()),
+								false,
+							)
+							.unwrap_or_default();
+
+							if !name.is_empty() {
+								playlists::add(&name, entries);
+
+								(list, info_vec) = page.request(&mut easy);
+								ratatui_list = List::new(list.clone()).highlight_style(Style {
+									add_modifier: Modifier::REVERSED,
+									..Style::default()
+								});
+							}
+						}
+					} else {
+						redraw = false;
+					}
+				}
+				// Play a local playlist shuffled
+				KeyCode::Char('Z' | 'z') => {
+					if let Some(mut playlist) = match &page {
+						Page::LocalPlaylist { name, .. } => playlists::get(name),
+						_ => None,
+					} {
+						shuffle(&mut playlist.entries);
+
+						leave_tui();
+						play_all(&mut easy, &mut decipherer, &playlist.entries);
+						enter_tui();
+					} else {
+						redraw = false;
+					}
+				}
+				// Hide watched videos on this page, or show them again
+				KeyCode::Char('U' | 'u') if page.filtered() => {
+					watched::toggle_hidden(page.identity());
+
+					(list, info_vec) = page.request(&mut easy);
+					ratatui_list = List::new(list.clone()).highlight_style(Style {
+						add_modifier: Modifier::REVERSED,
+						..Style::default()
+					});
+
+					// Make sure the cursor isn't past the end of the data
+					list_state.select(
+						list_state
+							.selected()
+							.map(|s| s.min(info_vec.len().saturating_sub(1))),
+					);
+				}
+				// Block the selected video's channel (or the selected channel)
+				KeyCode::Char('!') => {
+					if let Some((id, name)) = filter::channel(
+						&info_vec[list_state.selected().expect("Something should be selected")].1,
+					) {
+						filter::block_channel(&id, &name);
+
+						if page.filtered() {
+							// Everything's still described, so there's no need to load it again
+							filter::apply(&page.to_string(), &mut list, &mut info_vec);
+							ratatui_list = List::new(list.clone()).highlight_style(Style {
+								add_modifier: Modifier::REVERSED,
+								..Style::default()
+							});
+
+							// Make sure the cursor isn't past the end of the data
+							list_state.select(
+								list_state
+									.selected()
+									.map(|s| s.min(info_vec.len().saturating_sub(1))),
+							);
+						}
+					} else {
+						redraw = false;
+					}
+				}
+				// Bookmarks
+				KeyCode::Char('W' | 'w') => {
+					if let Page::Bookmarks { .. } = page {
+						redraw = false;
+					} else {
+						page = Page::Bookmarks {
+							previous: (
+								Box::new(page),
+								list_state.selected().expect("Something should be selected"),
+							),
+						};
+
+						// Move cursor to the top
+						list_state.select(Some(0));
+
+						(list, info_vec) = page.request(&mut easy);
+						ratatui_list = List::new(list.clone()).highlight_style(Style {
+							add_modifier: Modifier::REVERSED,
+							..Style::default()
+						});
+					}
+				}
+				// Search history
+				KeyCode::Char('Y' | 'y') => {
+					if let Page::SearchHistory { .. } = page {
+						redraw = false;
+					} else {
+						page = Page::SearchHistory {
 							previous: (
 								Box::new(page),
 								list_state.selected().expect("Something should be selected"),
@@ -854,15 +3040,103 @@ fn main() {
 						list_state.select(Some(0));
 
 						(list, info_vec) = page.request(&mut easy);
+						ratatui_list = List::new(list.clone()).highlight_style(Style {
+							add_modifier: Modifier::REVERSED,
+							..Style::default()
+						});
+					}
+				}
+				// Delete a past search, a bookmark, a video in a playlist or a list of them
+				KeyCode::Char('D' | 'd') => {
+					let selected = list_state.selected().expect("Something should be selected");
+
+					if match (&page, &info_vec[selected].1) {
+						(_, Node::PastSearch(index)) => {
+							history::remove(*index);
+
+							true
+						}
+						// The placeholder in an empty list
+						(Page::BookmarkList { .. }, Node::None) => false,
+						(Page::BookmarkList { name, .. }, _) => {
+							bookmarks::remove(name, selected);
+
+							true
+						}
+						(Page::LocalPlaylist { name, .. }, Node::PlaylistEntry(index)) => {
+							playlists::remove(name, *index);
+
+							true
+						}
+						(_, Node::LocalPlaylist(name)) => {
+							if prompt(
+								&mut terminal,
+								&["Delete the playlist \"", name, "\"? (y to confirm)"].concat(),
+								None,
+								false,
+							)
+							.is_some_and(|answer| answer.eq_ignore_ascii_case("y"))
+							{
+								playlists::remove_playlist(name);
+							}
+
+							true
+						}
+						(_, Node::BookmarkList(name)) => {
+							// It could have a lot in it, so make sure
+							if prompt(
+								&mut terminal,
+								&["Delete \"", name, "\" and everything in it? (y to confirm)"]
+									.concat(),
+								None,
+								false,
+							)
+							.is_some_and(|answer| answer.eq_ignore_ascii_case("y"))
+							{
+								bookmarks::remove_list(name);
+							}
 
+							true
+						}
+						_ => false,
+					} {
+						(list, info_vec) = page.request(&mut easy);
 						ratatui_list = List::new(list.clone()).highlight_style(Style {
 							add_modifier: Modifier::REVERSED,
 							..Style::default()
 						});
+
+						// Make sure the cursor isn't past the end of the data
+						list_state.select(list_state.selected().map(|s| s.min(info_vec.len() - 1)));
+					} else {
+						redraw = false;
+					}
+				}
+				// Request inspector
+				KeyCode::Char('I' | 'i') => {
+					if let Page::Inspector { previous, .. } = page {
+						// Close it, like going back
+						page = *previous.0;
+						list_state.select(Some(previous.1));
+					} else {
+						page = Page::Inspector {
+							request: inspector::page_request(),
+							previous: (
+								Box::new(page),
+								list_state.selected().expect("Something should be selected"),
+							),
+						};
+						list_state.select(Some(0));
 					}
 
-					// Hide the cursor again
-					let _ = terminal.hide_cursor();
+					(list, info_vec) = page.request(&mut easy);
+					ratatui_list = List::new(list.clone()).highlight_style(Style {
+						add_modifier: Modifier::REVERSED,
+						..Style::default()
+					});
+
+					// Make sure the cursor isn't past the end of the data
+					list_state.select(list_state.selected().map(|s| s.min(info_vec.len() - 1)));
 				}
 				// Refresh
 				KeyCode::Char('R' | 'r') => {
@@ -876,6 +3150,221 @@ fn main() {
 					// Make sure the cursor isn't past the end of the data
 					list_state.select(list_state.selected().map(|s| s.min(info_vec.len() - 1)));
 				}
+				// Change the order of the comments
+				KeyCode::Char('O' | 'o') => {
+					if let Page::CommentSection {
+						first_continuation,
+						continuation,
+						sorts,
+						sort,
+						..
+					} = &mut page
+					{
+						if sorts.len() > 1 {
+							*sort = (*sort + 1) % sorts.len();
+							*first_continuation = sorts[*sort].1.clone();
+							*continuation = None;
+
+							// Move cursor to the top
+							list_state.select(Some(0));
+
+							(list, info_vec) = page.request(&mut easy);
+							ratatui_list = List::new(list.clone()).highlight_style(Style {
+								add_modifier: Modifier::REVERSED,
+								..Style::default()
+							});
+						}
+					}
+				}
+				// Show replies under their comments, or on their own page
+				KeyCode::Char('E' | 'e') => {
+					if let Page::CommentSection { tree, .. } = &mut page {
+						*tree = !*tree;
+
+						(list, info_vec) = page.request(&mut easy);
+						ratatui_list = List::new(list.clone()).highlight_style(Style {
+							add_modifier: Modifier::REVERSED,
+							..Style::default()
+						});
+
+						// Make sure the cursor isn't past the end of the data
+						list_state.select(list_state.selected().map(|s| s.min(info_vec.len() - 1)));
+					}
+				}
+				// Export the comments and their replies, bookmarks or a playlist to a file
+				KeyCode::Char('X' | 'x') => {
+					if let Page::CommentSection {
+						first_continuation,
+						endpoint,
+						..
+					} = &page
+					{
+						let path = prompt(&mut terminal, "Export to (.json or .csv)", None, false)
+							.unwrap_or_default();
+
+						if let Some(format) = Format::new(&path) {
+							let result = File::create(&path)
+								.map_err(|error| format!("{path}: {error}"))
+								.and_then(|file| {
+									message(
+										&mut terminal,
+										"Exporting",
+										"Exported 0 comments",
+										false,
+									);
+
+									export_comments(
+										&mut easy,
+										endpoint,
+										first_continuation.clone(),
+										false,
+										true,
+										format,
+										&mut BufWriter::new(file),
+										&mut |count, _| {
+											message(
+												&mut terminal,
+												"Exporting",
+												&format!("Exported {count} comments"),
+												false,
+											);
+										},
+									)
+								});
+
+							match result {
+								Ok(count) => message(
+									&mut terminal,
+									"Exported",
+									&format!("Exported {count} comments to {path}"),
+									true,
+								),
+								Err(error) => {
+									message(&mut terminal, "Couldn't export", &error, true);
+								}
+							}
+						}
+					} else if let Page::LocalPlaylist { name, .. } = &page {
+						let path = prompt(&mut terminal, "Export to (.m3u)", None, false)
+							.unwrap_or_default();
+
+						if let (Some(playlist), true) = (
+							playlists::get(name),
+							path.ends_with(".m3u") || path.ends_with(".m3u8"),
+						) {
+							if let Err(error) = write(&path, playlist.m3u()) {
+								message(
+									&mut terminal,
+									"Couldn't export",
+									&format!("{path}: {error}"),
+									true,
+								);
+							}
+						}
+					} else if let Page::Bookmarks { .. } | Page::BookmarkList { .. } = &page {
+						let path = prompt(&mut terminal, "Export to (.json or .csv)", None, false)
+							.unwrap_or_default();
+
+						if let Some(format) = Format::new(&path) {
+							// All of them, or just the open list
+							let name = match &page {
+								Page::BookmarkList { name, .. } => Some(name.as_str()),
+								_ => None,
+							};
+
+							let result = File::create(&path)
+								.map_err(|error| format!("{path}: {error}"))
+								.and_then(|file| {
+									export_bookmarks(
+										&mut easy,
+										&bookmarks::lists(name),
+										format,
+										&mut BufWriter::new(file),
+									)
+								});
+
+							match result {
+								Ok(count) => message(
+									&mut terminal,
+									"Exported",
+									&format!("Exported {count} bookmarks to {path}"),
+									true,
+								),
+								Err(error) => {
+									message(&mut terminal, "Couldn't export", &error, true);
+								}
+							}
+						}
+					}
+				}
+				// Filter the comments, or open the search filter panel
+				KeyCode::Char('F' | 'f') => {
+					if let Page::Search { query, filters, .. } = &page {
+						page = Page::SearchFilters {
+							query: query.clone(),
+							filters: filters.clone(),
+							previous: (
+								Box::new(page),
+								list_state.selected().expect("Something should be selected"),
+							),
+						};
+
+						// Move cursor to the top
+						list_state.select(Some(0));
+
+						(list, info_vec) = page.request(&mut easy);
+						ratatui_list = List::new(list.clone()).highlight_style(Style {
+							add_modifier: Modifier::REVERSED,
+							..Style::default()
+						});
+					} else if let Page::CommentSection { .. } | Page::Comment { .. } = page {
+						// Escape keeps the current filter
+						if let Some(text) =
+							prompt(&mut terminal, "Filter (empty to clear)", None, false)
+						{
+							let text = text.to_lowercase();
+
+							if let Page::CommentSection { filter, .. }
+							| Page::Comment { filter, .. } = &mut page
+							{
+								*filter = (!text.is_empty()).then_some(text);
+							}
+
+							// Move cursor to the top
+							list_state.select(Some(0));
+
+							(list, info_vec) = page.request(&mut easy);
+							ratatui_list = List::new(list.clone()).highlight_style(Style {
+								add_modifier: Modifier::REVERSED,
+								..Style::default()
+							});
+						}
+					}
+				}
+				// Community posts
+				KeyCode::Char('C' | 'c') => {
+					if let Node::Channel(browse_id, _) =
+						&info_vec[list_state.selected().expect("Something should be selected")].1
+					{
+						page = Page::Community {
+							browse_id: browse_id.clone(),
+							continuation: None,
+							previous: (
+								Box::new(page),
+								list_state.selected().expect("Something should be selected"),
+							),
+						};
+
+						// Move cursor to the top
+						list_state.select(Some(0));
+
+						(list, info_vec) = page.request(&mut easy);
+						ratatui_list = List::new(list.clone()).highlight_style(Style {
+							add_modifier: Modifier::REVERSED,
+							..Style::default()
+						});
+					}
+				}
 				KeyCode::Char('N' | 'n') => {
 					if let Node::Video(video_id) =
 						&info_vec[list_state.selected().expect("Something should be selected")].1
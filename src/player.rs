@@ -0,0 +1,65 @@
+//! Talks to a player running in the background over mpv's JSON IPC, so chat replays can follow it
+
+use std::env::temp_dir;
+#[cfg(unix)]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::process;
+#[cfg(unix)]
+use std::time::Duration;
+
+use serde::Deserialize;
+#[cfg(unix)]
+use simd_json::from_slice;
+
+/// Where the player listens for commands, with this process's ID so more than one can run
+pub fn socket() -> PathBuf {
+	temp_dir().join(format!("ytui-player-{}.sock", process::id()))
+}
+
+/// A reply to a command, events are sent on the same socket but don't have `error`
+#[derive(Deserialize)]
+struct Reply {
+	data: Option<f64>,
+	error: String,
+}
+
+/// Sends a command to the player, returning the `data` of the reply. `None` if the player isn't
+/// running (or the command failed).
+#[cfg(unix)]
+fn command(command: &str) -> Option<f64> {
+	let mut stream = UnixStream::connect(socket()).ok()?;
+	let _ = stream.set_read_timeout(Some(Duration::from_secs(1)));
+
+	stream
+		.write_all([r#"{"command":"#, command, "}\n"].concat().as_bytes())
+		.ok()?;
+
+	BufReader::new(stream)
+		.split(b'\n')
+		.map_while(Result::ok)
+		.find_map(|mut line| from_slice::<Reply>(&mut line).ok())
+		.filter(|reply| reply.error == "success")
+		.map(|reply| reply.data.unwrap_or_default())
+}
+
+/// mpv's IPC is a named pipe on windows, which isn't supported, so replays can't follow the player
+#[cfg(not(unix))]
+fn command(_command: &str) -> Option<f64> {
+	None
+}
+
+/// How far into the video the player is, in milliseconds. `None` if it isn't running.
+pub fn position() -> Option<u64> {
+	command(r#"["get_property","time-pos"]"#).map(|seconds| (seconds * 1000.) as u64)
+}
+
+/// Moves the player to the given time, in milliseconds
+pub fn seek(milliseconds: u64) {
+	command(&format!(
+		r#"["set_property","time-pos",{}]"#,
+		milliseconds as f64 / 1000.
+	));
+}
@@ -0,0 +1,341 @@
+//! Playlists kept locally (`m`), made from videos (`v` on one) or imported from M3U files and
+//! youtube's `watch_videos` URLs, and exported the same ways
+
+use std::fs::{read, read_to_string};
+use std::sync::Mutex;
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Span, Spans};
+use ratatui::widgets::{ListItem, Paragraph, Wrap};
+use serde::{Deserialize, Serialize};
+use simd_json::{from_slice, to_vec};
+
+use crate::structs::Node;
+use crate::utils::{base_url, data_path, write_data};
+
+/// File in the data directory the playlists are kept in
+const FILE: &str = "playlists.json";
+
+/// A video in a playlist
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Entry {
+	pub id: String,
+	/// Its title when it was added, or its ID if it was imported without one
+	pub title: String,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct LocalPlaylist {
+	pub name: String,
+	/// In the order they're played
+	pub entries: Vec<Entry>,
+}
+impl LocalPlaylist {
+	/// As an M3U playlist, with youtube URLs so players that use yt-dlp can play it
+	pub fn m3u(&self) -> String {
+		let mut m3u = "#EXTM3U\n".to_owned();
+
+		for entry in &self.entries {
+			m3u += &format!(
+				"#EXTINF:-1,{}\n{}/watch?v={}\n",
+				entry.title,
+				base_url(),
+				entry.id
+			);
+		}
+
+		m3u
+	}
+
+	/// A youtube URL that plays the videos as an anonymous playlist (only the first 50 are
+	/// included)
+	pub fn watch_videos_url(&self) -> String {
+		[
+			base_url(),
+			"/watch_videos?video_ids=",
+			&self
+				.entries
+				.iter()
+				.map(|entry| entry.id.as_str())
+				.collect::<Vec<_>>()
+				.join(","),
+		]
+		.concat()
+	}
+}
+
+/// Loaded from the file the first time it's needed, in the order they were made
+static PLAYLISTS: Mutex<Option<Vec<LocalPlaylist>>> = Mutex::new(None);
+
+/// Runs `f` on the playlists, loading them first if they haven't been. Saves them afterwards if
+/// `f` returns true.
+fn with_playlists<T>(f: impl FnOnce(&mut Vec<LocalPlaylist>) -> (T, bool)) -> T {
+	let mut playlists = PLAYLISTS.lock().unwrap_or_else(|error| error.into_inner());
+
+	let playlists = playlists.get_or_insert_with(|| {
+		read(data_path(FILE))
+			.ok()
+			.and_then(|mut file| from_slice(&mut file).ok())
+			.unwrap_or_default()
+	});
+
+	let (result, changed) = f(playlists);
+
+	if changed {
+		write_data(
+			FILE,
+			&to_vec(playlists).expect("Should be able to serialize playlists"),
+		);
+	}
+
+	result
+}
+
+/// Adds videos to the end of a playlist, making it if it doesn't exist. Videos that are already in
+/// it are skipped, returns how many were added.
+pub fn add(name: &str, entries: Vec<Entry>) -> usize {
+	with_playlists(|playlists| {
+		let playlist = match playlists.iter().position(|playlist| playlist.name == name) {
+			Some(index) => &mut playlists[index],
+			None => {
+				playlists.push(LocalPlaylist {
+					name: name.to_owned(),
+					entries: Vec::new(),
+				});
+
+				playlists.last_mut().expect("A playlist was just added")
+			}
+		};
+
+		let length = playlist.entries.len();
+
+		for entry in entries {
+			if !playlist.entries.iter().any(|added| added.id == entry.id) {
+				playlist.entries.push(entry);
+			}
+		}
+
+		(playlist.entries.len() - length, true)
+	})
+}
+
+/// The names of the playlists
+pub fn names() -> Vec<String> {
+	with_playlists(|playlists| {
+		(
+			playlists
+				.iter()
+				.map(|playlist| playlist.name.clone())
+				.collect(),
+			false,
+		)
+	})
+}
+
+pub fn get(name: &str) -> Option<LocalPlaylist> {
+	with_playlists(|playlists| {
+		(
+			playlists
+				.iter()
+				.find(|playlist| playlist.name == name)
+				.cloned(),
+			false,
+		)
+	})
+}
+
+/// Removes the video at `index` in a playlist
+pub fn remove(name: &str, index: usize) {
+	with_playlists(
+		|playlists| match playlists.iter_mut().find(|playlist| playlist.name == name) {
+			Some(playlist) if index < playlist.entries.len() => {
+				playlist.entries.remove(index);
+
+				((), true)
+			}
+			_ => ((), false),
+		},
+	);
+}
+
+/// Removes a whole playlist
+pub fn remove_playlist(name: &str) {
+	with_playlists(|playlists| {
+		let length = playlists.len();
+		playlists.retain(|playlist| playlist.name != name);
+
+		((), playlists.len() != length)
+	});
+}
+
+/// Moves the video at `index` in a playlist up or down one, returning where it is now (`None` if
+/// it couldn't be moved)
+pub fn shift(name: &str, index: usize, up: bool) -> Option<usize> {
+	with_playlists(|playlists| {
+		let Some(playlist) = playlists.iter_mut().find(|playlist| playlist.name == name) else {
+			return (None, false);
+		};
+
+		let new_index = if up {
+			index.checked_sub(1)
+		} else {
+			Some(index + 1)
+		}
+		.filter(|new_index| index < playlist.entries.len() && *new_index < playlist.entries.len());
+
+		if let Some(new_index) = new_index {
+			playlist.entries.swap(index, new_index);
+		}
+
+		(new_index, new_index.is_some())
+	})
+}
+
+/// Takes the video ID out of a youtube URL, i.e. `https://www.youtube.com/watch?v=ID` or
+/// `https://youtu.be/ID`
+fn video_id(url: &str) -> Option<&str> {
+	let id = url
+		.split(['?', '&'])
+		.find_map(|parameter| parameter.strip_prefix("v="))
+		.or_else(|| {
+			["youtu.be/", "/shorts/"]
+				.into_iter()
+				.find_map(|prefix| Some(&url[url.find(prefix)? + prefix.len()..]))
+		})?;
+
+	let id = &id[..id
+		.find(|c: char| !c.is_ascii_alphanumeric() && c != '-' && c != '_')
+		.unwrap_or(id.len())];
+
+	(id.len() == 11).then_some(id)
+}
+
+/// Reads the videos from a `watch_videos` URL or an M3U file (the youtube URLs in it, with their
+/// titles if it has them). `None` if the file can't be read or there aren't any.
+pub fn import(source: &str) -> Option<Vec<Entry>> {
+	let mut entries = Vec::new();
+
+	if let Some(ids) = source
+		.split_once("video_ids=")
+		.map(|(_, ids)| ids.split('&').next().unwrap_or_default())
+	{
+		for id in ids.replace("%2C", ",").replace("%2c", ",").split(',') {
+			if !id.is_empty() {
+				entries.push(Entry {
+					id: id.to_owned(),
+					title: id.to_owned(),
+				});
+			}
+		}
+	} else {
+		let mut title = None;
+
+		for line in read_to_string(source).ok()?.lines().map(str::trim) {
+			if let Some(info) = line.strip_prefix("#EXTINF:") {
+				title = info.split_once(',').map(|(_, title)| title.to_owned());
+			} else if !line.is_empty() && !line.starts_with('#') {
+				// The title only belongs to the line after it
+				let title = title.take();
+
+				if let Some(id) = video_id(line) {
+					entries.push(Entry {
+						id: id.to_owned(),
+						title: title.unwrap_or_else(|| id.to_owned()),
+					});
+				}
+			}
+		}
+	}
+
+	(!entries.is_empty()).then_some(entries)
+}
+
+/// The playlists page: the playlists, with how many videos they have
+pub fn playlists_widgets<'a>() -> (Vec<ListItem<'a>>, Vec<(Paragraph<'a>, Node)>) {
+	with_playlists(|playlists| {
+		let mut titles = Vec::with_capacity(playlists.len());
+		let mut info = Vec::with_capacity(playlists.len());
+
+		for playlist in playlists.iter() {
+			titles.push(ListItem::new(Spans(vec![
+				playlist.name.clone().into(),
+				Span::styled(
+					format!(" ({})", playlist.entries.len()),
+					Style::default().fg(Color::DarkGray),
+				),
+			])));
+			info.push((
+				Paragraph::new("Enter to open, d to delete the playlist, g to import one")
+					.wrap(Wrap { trim: false }),
+				Node::LocalPlaylist(playlist.name.clone()),
+			));
+		}
+
+		if playlists.is_empty() {
+			titles.push(ListItem::new("No playlists yet"));
+			info.push((
+				Paragraph::new("v on a video to add it to one, g to import one")
+					.wrap(Wrap { trim: false }),
+				Node::None,
+			));
+		}
+
+		((titles, info), false)
+	})
+}
+
+/// A playlist's page: a link to play it on youtube, then its videos
+pub fn playlist_widgets<'a>(name: &str) -> (Vec<ListItem<'a>>, Vec<(Paragraph<'a>, Node)>) {
+	let playlist = get(name).unwrap_or(LocalPlaylist {
+		name: name.to_owned(),
+		entries: Vec::new(),
+	});
+
+	let mut titles = Vec::with_capacity(playlist.entries.len() + 1);
+	let mut info = Vec::with_capacity(playlist.entries.len() + 1);
+
+	titles.push(ListItem::new(vec![
+		Spans::from(Span::styled(
+			format!("{} videos", playlist.entries.len()),
+			Style {
+				add_modifier: Modifier::UNDERLINED,
+				..Style::default()
+			},
+		)),
+		Spans::default(),
+	]));
+
+	let url = playlist.watch_videos_url();
+	info.push((
+		Paragraph::new(vec![
+			Spans::from(url.clone()),
+			Spans::default(),
+			Spans::from(
+				"Enter to open it on youtube, z to play them shuffled, x to export them to an M3U \
+				 file",
+			),
+		])
+		.wrap(Wrap { trim: false }),
+		Node::Url(url),
+	));
+
+	for (index, entry) in playlist.entries.into_iter().enumerate() {
+		titles.push(ListItem::new(vec![
+			Spans(vec![
+				Span::styled(
+					format!("{}. ", index + 1),
+					Style::default().fg(Color::DarkGray),
+				),
+				entry.title.into(),
+			]),
+			Spans::default(),
+		]));
+		info.push((
+			Paragraph::new("Enter to play from here, d to remove it, Ctrl+Up/Down to move it")
+				.wrap(Wrap { trim: false }),
+			Node::PlaylistEntry(index),
+		));
+	}
+
+	(titles, info)
+}
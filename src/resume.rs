@@ -0,0 +1,130 @@
+//! Where videos were left off, kept between runs so they can be resumed. Read from the watch later
+//! file the player writes when it quits (mpv's `--save-position-on-quit`).
+
+use std::collections::HashMap;
+use std::fs::{create_dir_all, read, read_dir, read_to_string, remove_dir_all};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use ratatui::style::{Color, Style};
+use ratatui::text::{Span, Spans};
+use serde::{Deserialize, Serialize};
+use simd_json::{from_slice, to_vec};
+
+use crate::config::{PROGRESS_BAR_WIDTH, RESUME_MARGIN};
+use crate::utils::{data_path, format_timestamp, write_data};
+
+/// File in the data directory the positions are kept in
+const FILE: &str = "positions.json";
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Position {
+	/// Where it was left off, in milliseconds
+	pub position: u64,
+	/// The video's length, in milliseconds
+	pub length: u64,
+}
+impl Position {
+	/// A bar showing how much has been watched, with the position and length
+	pub fn progress_bar<'a>(self) -> Spans<'a> {
+		let watched = (self.position * PROGRESS_BAR_WIDTH as u64)
+			.checked_div(self.length)
+			.unwrap_or_default()
+			.min(PROGRESS_BAR_WIDTH as u64) as usize;
+
+		Spans(vec![
+			Span::styled("━".repeat(watched), Style::default().fg(Color::Red)),
+			Span::styled(
+				"─".repeat(PROGRESS_BAR_WIDTH - watched),
+				Style::default().fg(Color::DarkGray),
+			),
+			Span::styled(
+				[
+					" ",
+					&format_timestamp(self.position),
+					" / ",
+					&format_timestamp(self.length),
+				]
+				.concat(),
+				Style::default().fg(Color::DarkGray),
+			),
+		])
+	}
+}
+
+/// Loaded from the file the first time it's needed, by video ID
+static POSITIONS: Mutex<Option<HashMap<String, Position>>> = Mutex::new(None);
+
+/// Runs `f` on the positions, loading them first if they haven't been. Saves them afterwards if
+/// `f` returns true.
+fn with_positions<T>(f: impl FnOnce(&mut HashMap<String, Position>) -> (T, bool)) -> T {
+	let mut positions = POSITIONS.lock().unwrap_or_else(|error| error.into_inner());
+
+	let positions = positions.get_or_insert_with(|| {
+		read(data_path(FILE))
+			.ok()
+			.and_then(|mut file| from_slice(&mut file).ok())
+			.unwrap_or_default()
+	});
+
+	let (result, changed) = f(positions);
+
+	if changed {
+		write_data(
+			FILE,
+			&to_vec(positions).expect("Should be able to serialize positions"),
+		);
+	}
+
+	result
+}
+
+/// Where the player writes its watch later file
+pub fn watch_later_directory() -> PathBuf {
+	data_path("watch_later")
+}
+
+/// Empties the watch later directory before a video is played, so the only file in it afterwards
+/// is that video's. Returns the directory.
+pub fn prepare() -> PathBuf {
+	let directory = watch_later_directory();
+
+	let _ = remove_dir_all(&directory);
+	let _ = create_dir_all(&directory);
+
+	directory
+}
+
+/// Reads where the player left off after it quits, remembering it for the video with the given ID
+/// and length (in milliseconds). It's forgotten if the video was watched to the end (there's no
+/// watch later file), or if it was left within `RESUME_MARGIN` of the start or end.
+pub fn record(id: &str, length: u64) {
+	let position = read_dir(watch_later_directory())
+		.into_iter()
+		.flatten()
+		.filter_map(|entry| read_to_string(entry.ok()?.path()).ok())
+		.find_map(|file| {
+			file.lines()
+				.find_map(|line| line.strip_prefix("start=")?.parse::<f64>().ok())
+		})
+		.map(|seconds| (seconds * 1000.0) as u64)
+		.filter(|position| *position > RESUME_MARGIN && position + RESUME_MARGIN < length);
+
+	with_positions(|positions| {
+		let changed = match position {
+			Some(position) => {
+				positions.insert(id.to_owned(), Position { position, length });
+
+				true
+			}
+			None => positions.remove(id).is_some(),
+		};
+
+		((), changed)
+	});
+}
+
+/// Where the video with the given ID was left off, if it was
+pub fn get(id: &str) -> Option<Position> {
+	with_positions(|positions| (positions.get(id).copied(), false))
+}
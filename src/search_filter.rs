@@ -0,0 +1,572 @@
+//! Search filters, encoded into the `params` youtube expects for searches (the `sp` URL parameter),
+//! so they can be combined in the filter panel (`f` in a search) and given on the command line
+
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Span, Spans};
+use ratatui::widgets::{ListItem, Paragraph, Wrap};
+use serde::{Deserialize, Serialize};
+
+use crate::structs::Node;
+
+/// An option in a group of filters
+pub struct FilterOption {
+	/// What it's called on the command line, i.e. `week`
+	pub name: &'static str,
+	/// What it's called in the filter panel, i.e. `This week`
+	pub label: &'static str,
+	/// Its value in the protobuf, or its field number for features
+	value: u64,
+}
+
+const UPLOAD_DATES: &[FilterOption] = &[
+	FilterOption {
+		name: "hour",
+		label: "Last hour",
+		value: 1,
+	},
+	FilterOption {
+		name: "today",
+		label: "Today",
+		value: 2,
+	},
+	FilterOption {
+		name: "week",
+		label: "This week",
+		value: 3,
+	},
+	FilterOption {
+		name: "month",
+		label: "This month",
+		value: 4,
+	},
+	FilterOption {
+		name: "year",
+		label: "This year",
+		value: 5,
+	},
+];
+
+const TYPES: &[FilterOption] = &[
+	FilterOption {
+		name: "video",
+		label: "Video",
+		value: 1,
+	},
+	FilterOption {
+		name: "channel",
+		label: "Channel",
+		value: 2,
+	},
+	FilterOption {
+		name: "playlist",
+		label: "Playlist",
+		value: 3,
+	},
+	FilterOption {
+		name: "movie",
+		label: "Movie",
+		value: 4,
+	},
+];
+
+const DURATIONS: &[FilterOption] = &[
+	FilterOption {
+		name: "short",
+		label: "Under 4 minutes",
+		value: 1,
+	},
+	FilterOption {
+		name: "medium",
+		label: "4 - 20 minutes",
+		value: 3,
+	},
+	FilterOption {
+		name: "long",
+		label: "Over 20 minutes",
+		value: 2,
+	},
+];
+
+const FEATURES: &[FilterOption] = &[
+	FilterOption {
+		name: "live",
+		label: "Live",
+		value: 8,
+	},
+	FilterOption {
+		name: "4k",
+		label: "4K",
+		value: 14,
+	},
+	FilterOption {
+		name: "hd",
+		label: "HD",
+		value: 4,
+	},
+	FilterOption {
+		name: "subtitles",
+		label: "Subtitles/CC",
+		value: 5,
+	},
+	FilterOption {
+		name: "cc",
+		label: "Creative Commons",
+		value: 6,
+	},
+];
+
+const SORTS: &[FilterOption] = &[
+	FilterOption {
+		name: "relevance",
+		label: "Relevance",
+		value: 0,
+	},
+	FilterOption {
+		name: "date",
+		label: "Upload date",
+		value: 2,
+	},
+	FilterOption {
+		name: "views",
+		label: "View count",
+		value: 3,
+	},
+	FilterOption {
+		name: "rating",
+		label: "Rating",
+		value: 1,
+	},
+];
+
+/// A group of filters, only one option can be picked from each one except features
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum FilterGroup {
+	UploadDate,
+	Type,
+	Duration,
+	Features,
+	Sort,
+}
+impl FilterGroup {
+	/// In the order they're shown
+	const ALL: [FilterGroup; 5] = [
+		FilterGroup::UploadDate,
+		FilterGroup::Type,
+		FilterGroup::Duration,
+		FilterGroup::Features,
+		FilterGroup::Sort,
+	];
+
+	/// The command line argument for this group, i.e. `--type`
+	fn argument(self) -> &'static str {
+		match self {
+			FilterGroup::UploadDate => "--upload-date",
+			FilterGroup::Type => "--type",
+			FilterGroup::Duration => "--duration",
+			FilterGroup::Features => "--features",
+			FilterGroup::Sort => "--sort",
+		}
+	}
+
+	pub fn from_argument(argument: &str) -> Option<FilterGroup> {
+		FilterGroup::ALL
+			.into_iter()
+			.find(|group| group.argument() == argument)
+	}
+
+	fn title(self) -> &'static str {
+		match self {
+			FilterGroup::UploadDate => "Upload date",
+			FilterGroup::Type => "Type",
+			FilterGroup::Duration => "Duration",
+			FilterGroup::Features => "Features",
+			FilterGroup::Sort => "Sort by",
+		}
+	}
+
+	pub fn options(self) -> &'static [FilterOption] {
+		match self {
+			FilterGroup::UploadDate => UPLOAD_DATES,
+			FilterGroup::Type => TYPES,
+			FilterGroup::Duration => DURATIONS,
+			FilterGroup::Features => FEATURES,
+			FilterGroup::Sort => SORTS,
+		}
+	}
+}
+
+/// Appends a protobuf varint
+fn varint(bytes: &mut Vec<u8>, mut value: u64) {
+	while value >= 0x80 {
+		bytes.push(value as u8 | 0x80);
+		value >>= 7;
+	}
+
+	bytes.push(value as u8);
+}
+
+/// Reads a protobuf varint from the start of `bytes`, moving past it
+fn read_varint(bytes: &mut &[u8]) -> Option<u64> {
+	let mut value = 0;
+
+	for shift in (0..64).step_by(7) {
+		let (byte, rest) = bytes.split_first()?;
+		*bytes = rest;
+
+		value |= u64::from(byte & 0x7f) << shift;
+
+		if byte & 0x80 == 0 {
+			return Some(value);
+		}
+	}
+
+	None
+}
+
+/// Decodes base64 (standard or URL safe, padding is optional)
+fn unbase64(encoded: &str) -> Option<Vec<u8>> {
+	let mut bytes = Vec::with_capacity(encoded.len() / 4 * 3);
+	let mut block = 0u32;
+	let mut bits = 0;
+
+	for c in encoded.trim_end_matches('=').bytes() {
+		let value = match c {
+			b'A'..=b'Z' => c - b'A',
+			b'a'..=b'z' => c - b'a' + 26,
+			b'0'..=b'9' => c - b'0' + 52,
+			b'+' | b'-' => 62,
+			b'/' | b'_' => 63,
+			_ => return None,
+		};
+
+		block = block << 6 | u32::from(value);
+		bits += 6;
+
+		if bits >= 8 {
+			bits -= 8;
+			bytes.push((block >> bits) as u8);
+			block &= (1 << bits) - 1;
+		}
+	}
+
+	Some(bytes)
+}
+
+/// Standard base64, with padding
+fn base64(bytes: &[u8]) -> String {
+	const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+	let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+	for chunk in bytes.chunks(3) {
+		let block = chunk
+			.iter()
+			.enumerate()
+			.fold(0, |block, (i, byte)| block | (*byte as u32) << (16 - i * 8));
+
+		for i in 0..4 {
+			encoded.push(if i <= chunk.len() {
+				ALPHABET[(block >> (18 - i * 6)) as usize & 63] as char
+			} else {
+				'='
+			});
+		}
+	}
+
+	encoded
+}
+
+/// Filters for a search, as indices into their group's options
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchFilters {
+	upload_date: Option<usize>,
+	kind: Option<usize>,
+	duration: Option<usize>,
+	/// In the order they were picked
+	features: Vec<usize>,
+	/// Relevance by default
+	sort: usize,
+	/// Params from youtube that these can't express (see `from_params`), they're searched with
+	/// until a filter is picked
+	custom: Option<String>,
+}
+impl SearchFilters {
+	/// The filters a search's params (i.e. from a link, a bookmark or the search history) stand
+	/// for. If they have anything else, they're kept as they are and shown as custom.
+	pub fn from_params(params: Option<&str>) -> SearchFilters {
+		params.map_or_else(SearchFilters::default, |params| {
+			SearchFilters::decode(params).unwrap_or_else(|| SearchFilters {
+				custom: Some(params.to_owned()),
+				..SearchFilters::default()
+			})
+		})
+	}
+
+	/// The reverse of `params`, `None` if there's anything it wouldn't encode
+	fn decode(params: &str) -> Option<SearchFilters> {
+		let message = unbase64(
+			&params
+				.replace("%3D", "=")
+				.replace("%2B", "+")
+				.replace("%2F", "/"),
+		)?;
+		let mut message = &message[..];
+		let mut filters = SearchFilters::default();
+
+		while !message.is_empty() {
+			match read_varint(&mut message)? {
+				// Field 1, a varint
+				0x08 => {
+					let value = read_varint(&mut message)?;
+					filters.sort = SORTS.iter().position(|option| option.value == value)?;
+				}
+				// Field 2, length delimited
+				0x12 => {
+					let length = read_varint(&mut message)? as usize;
+					if length > message.len() {
+						return None;
+					}
+					let mut inner;
+					(inner, message) = message.split_at(length);
+
+					while !inner.is_empty() {
+						let key = read_varint(&mut inner)?;
+						// They're all varints
+						if key & 7 != 0 {
+							return None;
+						}
+						let value = read_varint(&mut inner)?;
+
+						let (single, group) = match key >> 3 {
+							1 => (&mut filters.upload_date, FilterGroup::UploadDate),
+							2 => (&mut filters.kind, FilterGroup::Type),
+							3 => (&mut filters.duration, FilterGroup::Duration),
+							field => {
+								let index = FEATURES
+									.iter()
+									.position(|option| option.value == field && value == 1)?;
+								filters.features.push(index);
+
+								continue;
+							}
+						};
+
+						*single = Some(
+							group
+								.options()
+								.iter()
+								.position(|option| option.value == value)?,
+						);
+					}
+				}
+				_ => return None,
+			}
+		}
+
+		Some(filters)
+	}
+
+	pub fn selected(&self, group: FilterGroup, index: usize) -> bool {
+		match group {
+			FilterGroup::UploadDate => self.upload_date == Some(index),
+			FilterGroup::Type => self.kind == Some(index),
+			FilterGroup::Duration => self.duration == Some(index),
+			FilterGroup::Features => self.features.contains(&index),
+			FilterGroup::Sort => self.sort == index,
+		}
+	}
+
+	/// Picks an option, or unpicks it if it already was (except for the sort). Custom params are
+	/// replaced.
+	pub fn toggle(&mut self, group: FilterGroup, index: usize) {
+		self.custom = None;
+
+		let single = match group {
+			FilterGroup::UploadDate => &mut self.upload_date,
+			FilterGroup::Type => &mut self.kind,
+			FilterGroup::Duration => &mut self.duration,
+			FilterGroup::Features => {
+				match self.features.iter().position(|feature| *feature == index) {
+					Some(position) => {
+						self.features.remove(position);
+					}
+					None => self.features.push(index),
+				}
+
+				return;
+			}
+			FilterGroup::Sort => {
+				self.sort = index;
+
+				return;
+			}
+		};
+
+		*single = (*single != Some(index)).then_some(index);
+	}
+
+	/// Picks options by their command line names, features can be separated by commas. Returns
+	/// the name that doesn't exist, if one doesn't.
+	pub fn set<'a>(&mut self, group: FilterGroup, names: &'a str) -> Result<(), &'a str> {
+		for name in names.split(',') {
+			let index = group
+				.options()
+				.iter()
+				.position(|option| option.name.eq_ignore_ascii_case(name))
+				.ok_or(name)?;
+
+			if !self.selected(group, index) {
+				self.toggle(group, index);
+			}
+		}
+
+		Ok(())
+	}
+
+	/// The labels of the picked options
+	pub fn summary(&self) -> Vec<&'static str> {
+		if self.custom.is_some() {
+			return vec!["Custom filters"];
+		}
+
+		FilterGroup::ALL
+			.into_iter()
+			.flat_map(|group| {
+				group
+					.options()
+					.iter()
+					.enumerate()
+					// The default sort isn't worth mentioning
+					.filter(move |(index, _)| {
+						self.selected(group, *index) && !(group == FilterGroup::Sort && *index == 0)
+					})
+					.map(|(_, option)| option.label)
+			})
+			.collect()
+	}
+
+	/// Encoded for a search request, `None` without any filters
+	pub fn params(&self) -> Option<String> {
+		if let Some(custom) = &self.custom {
+			return Some(custom.clone());
+		}
+
+		let mut filters = Vec::new();
+
+		for (field, group, index) in [
+			(1, FilterGroup::UploadDate, self.upload_date),
+			(2, FilterGroup::Type, self.kind),
+			(3, FilterGroup::Duration, self.duration),
+		] {
+			if let Some(index) = index {
+				varint(&mut filters, field << 3);
+				varint(&mut filters, group.options()[index].value);
+			}
+		}
+
+		// Features are flags, each in its own field
+		for index in &self.features {
+			varint(&mut filters, FEATURES[*index].value << 3);
+			varint(&mut filters, 1);
+		}
+
+		let mut message = Vec::new();
+
+		if SORTS[self.sort].value != 0 {
+			varint(&mut message, 1 << 3);
+			varint(&mut message, SORTS[self.sort].value);
+		}
+
+		if !filters.is_empty() {
+			// Length delimited
+			varint(&mut message, 2 << 3 | 2);
+			varint(&mut message, filters.len() as u64);
+			message.extend(filters);
+		}
+
+		// Youtube URL encodes them
+		(!message.is_empty()).then(|| base64(&message).replace('=', "%3D"))
+	}
+
+	/// The filter panel for a search for `query`
+	pub fn widgets<'a>(&self, query: &str) -> (Vec<ListItem<'a>>, Vec<(Paragraph<'a>, Node)>) {
+		let mut titles = Vec::new();
+		let mut info = Vec::new();
+
+		titles.push(ListItem::new(Span {
+			content: ["Search for \"", query, "\""].concat().into(),
+			style: Style {
+				add_modifier: Modifier::UNDERLINED,
+				..Style::default()
+			},
+		}));
+		info.push((
+			Paragraph::new(match self.summary().join(", ") {
+				summary if summary.is_empty() => "Without filters".to_owned(),
+				summary => ["With ", &summary].concat(),
+			})
+			.wrap(Wrap { trim: false }),
+			Node::Search(query.to_owned(), self.params()),
+		));
+
+		for group in FilterGroup::ALL {
+			titles.push(ListItem::new(""));
+			info.push((Paragraph::new(""), Node::None));
+
+			titles.push(ListItem::new(Span {
+				content: group.title().into(),
+				style: Style {
+					add_modifier: Modifier::UNDERLINED,
+					..Style::default()
+				},
+			}));
+			info.push((Paragraph::new(""), Node::None));
+
+			for (index, option) in group.options().iter().enumerate() {
+				let selected = self.selected(group, index);
+
+				titles.push(ListItem::new(Spans(vec![
+					match (group, selected) {
+						(FilterGroup::Features, true) => "[x] ",
+						(FilterGroup::Features, false) => "[ ] ",
+						(_, true) => "(*) ",
+						(_, false) => "( ) ",
+					}
+					.into(),
+					Span {
+						content: option.label.into(),
+						style: Style {
+							// Picked options are bold
+							add_modifier: if selected {
+								Modifier::BOLD
+							} else {
+								Modifier::empty()
+							},
+							..Style::default()
+						},
+					},
+				])));
+				info.push((
+					Paragraph::new(
+						[
+							if group == FilterGroup::Features {
+								"Enter to toggle, `"
+							} else {
+								"Enter to pick, `"
+							},
+							group.argument(),
+							" ",
+							option.name,
+							"` on the command line",
+						]
+						.concat(),
+					)
+					.wrap(Wrap { trim: false }),
+					Node::SearchFilter(group, index),
+				));
+			}
+		}
+
+		(titles, info)
+	}
+}
@@ -1,14 +1,22 @@
 //! Structures returned from a comments response.
 
-use ratatui::style::Style;
+use ratatui::style::{self, Modifier, Style};
 use ratatui::text::{Span, Spans};
 use ratatui::widgets::{ListItem, Paragraph, Wrap};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use super::{
-	int_to_colour, spaced, AccessibleText, Color, ContinuationEndpoint, ContinuationItemRenderer,
-	Endpoint, IntoWidgets, Node, SimpleText, Text,
+	add_links, int_to_colour, spaced, AccessibleText, Color, ContinuationEndpoint,
+	ContinuationItemRenderer, Endpoint, IntoWidgets, Menu, Node, SimpleText, Text, Unknown,
 };
+use crate::config::REPLY_INDENT;
+
+/// Comments that can be narrowed down by a filter
+pub trait Filter {
+	/// Whether this should be kept, `filter` should be lowercase. Anything that isn't a comment
+	/// (i.e. continuation tokens) is always kept.
+	fn matches(&self, filter: &str) -> bool;
+}
 
 #[derive(Deserialize)]
 struct PinnedCommentBadgeRenderer {
@@ -33,8 +41,32 @@ struct AuthorCommentBadge {
 	authorCommentBadgeRenderer: AuthorCommentBadgeRenderer,
 }
 
+#[derive(Deserialize)]
+struct CreatorHeartRenderer {
+	isHearted: bool,
+	// Ignore `creatorThumbnail`, `heartIcon`, `heartColor`, `heartedTooltip` and others
+}
+
+#[derive(Deserialize)]
+struct CreatorHeart {
+	creatorHeartRenderer: CreatorHeartRenderer,
+}
+
+#[derive(Deserialize)]
+struct CommentActionButtonsRenderer {
+	/// Only there if the uploader can heart comments
+	creatorHeart: Option<CreatorHeart>,
+	// Ignore `likeButton`, `replyButton`, `dislikeButton` and others
+}
+
+#[derive(Deserialize)]
+struct ActionButtons {
+	commentActionButtonsRenderer: CommentActionButtonsRenderer,
+}
+
 #[derive(Deserialize)]
 pub struct CommentRenderer {
+	commentId: String,
 	authorText: SimpleText,
 	authorEndpoint: Endpoint,
 	contentText: Text,
@@ -45,8 +77,89 @@ pub struct CommentRenderer {
 	authorCommentBadge: Option<AuthorCommentBadge>,
 	/// Max is 501, so fits in u16
 	replyCount: Option<u16>,
-	// Ignore `authorThumbnails`, `isLiked`, `commentId`, `actionButtons`, `voteStatus`,
-	// `trackingParams`, `expandButton`, `collapseButton` and `loggingDirective`
+	actionButtons: Option<ActionButtons>,
+	// Ignore `authorThumbnails`, `isLiked`, `voteStatus`, `trackingParams`, `expandButton`,
+	// `collapseButton` and `loggingDirective`
+}
+
+impl CommentRenderer {
+	/// Whether the author or text contains `filter` (which should be lowercase), ignoring case
+	fn matches(&self, filter: &str) -> bool {
+		self.authorText.simpleText.to_lowercase().contains(filter)
+			|| self
+				.contentText
+				.runs
+				.iter()
+				.map(|run| run.text.as_str())
+				.collect::<String>()
+				.to_lowercase()
+				.contains(filter)
+	}
+
+	/// This comment's details, for exporting. `parent` is the ID of the comment this replies to.
+	fn export(&self, parent: Option<&str>) -> ExportedComment {
+		ExportedComment {
+			id: self.commentId.clone(),
+			parent: parent.map(str::to_owned),
+			author: self.authorText.simpleText.clone(),
+			channel_id: self.authorEndpoint.browseEndpoint.browseId.clone(),
+			text: self
+				.contentText
+				.runs
+				.iter()
+				.map(|run| run.text.as_str())
+				.collect(),
+			// Comments that haven't been liked yet don't have a like count
+			likes: self
+				.voteCount
+				.as_ref()
+				.and_then(|vote_count| vote_count.simpleText.clone())
+				.unwrap_or_else(|| "0".to_owned()),
+			replies: self.replyCount.unwrap_or(0),
+			pinned: self.pinnedCommentBadge.is_some(),
+			hearted: self.actionButtons.as_ref().is_some_and(|action_buttons| {
+				action_buttons
+					.commentActionButtonsRenderer
+					.creatorHeart
+					.as_ref()
+					.is_some_and(|creator_heart| creator_heart.creatorHeartRenderer.isHearted)
+			}),
+			published: self
+				.publishedTimeText
+				.runs
+				.iter()
+				.map(|run| run.text.as_str())
+				.collect(),
+		}
+	}
+}
+
+/// A comment's details, as they're exported
+#[derive(Serialize)]
+pub struct ExportedComment {
+	pub id: String,
+	/// The ID of the comment this replies to, if it's a reply
+	pub parent: Option<String>,
+	pub author: String,
+	pub channel_id: String,
+	pub text: String,
+	/// Rounded like it's shown, i.e. `1.2K`
+	pub likes: String,
+	pub replies: u16,
+	pub pinned: bool,
+	/// Whether the uploader hearted it
+	pub hearted: bool,
+	/// Relative, i.e. `1 day ago`
+	pub published: String,
+}
+
+/// What an item in a comment section or a comment's replies holds, for exporting
+pub enum Exported {
+	/// With the token for its replies, if it has any
+	Comment(ExportedComment, Option<String>),
+	/// Token for more comments or replies
+	Continuation(String),
+	None,
 }
 
 #[derive(Deserialize)]
@@ -75,17 +188,54 @@ pub enum Comment {
 	ContinuationItem {
 		continuationItemRenderer: CommentContinuationItemRenderer,
 	},
+	Unknown(Unknown),
 }
-impl IntoWidgets for Comment {
-	/// Should only return a continuation token for replies
-	fn into_widgets<'a>(
+impl Filter for Comment {
+	fn matches(&self, filter: &str) -> bool {
+		match self {
+			Comment::Comment { commentRenderer } => commentRenderer.matches(filter),
+			_ => true,
+		}
+	}
+}
+impl Comment {
+	/// Adds this to `titles` and `info` with its title indented by `indent`. Should only return a
+	/// continuation token for replies.
+	fn add_to<'a>(
 		self,
+		indent: &'static str,
 		titles: &mut Vec<ListItem<'a>>,
 		info: &mut Vec<(Paragraph<'a>, Node)>,
 	) -> Option<String> {
 		match self {
-			Comment::Comment { commentRenderer } => {
-				titles.push(spaced(commentRenderer.authorText.simpleText));
+			Comment::Comment {
+				mut commentRenderer,
+			} => {
+				// Highlight the video uploader's name, and mark pinned comments
+				let mut title = vec![
+					indent.into(),
+					Span {
+						content: commentRenderer.authorText.simpleText.into(),
+						style: if commentRenderer.authorIsChannelOwner {
+							Style {
+								fg: Some(style::Color::Yellow),
+								add_modifier: Modifier::BOLD,
+								..Style::default()
+							}
+						} else {
+							Style::default()
+						},
+					},
+				];
+				if commentRenderer.pinnedCommentBadge.is_some() {
+					title.push(Span::styled(
+						" (pinned)",
+						Style::default().fg(style::Color::Cyan),
+					));
+				}
+				titles.push(spaced(Spans(title)));
+
+				let links = commentRenderer.contentText.links();
 
 				let mut lines = vec![
 					commentRenderer.contentText.into(),
@@ -159,6 +309,9 @@ impl IntoWidgets for Comment {
 
 				info.push((Paragraph::new(lines).wrap(Wrap { trim: false }), Node::None));
 
+				// Links in the comment, if there are any
+				add_links(links, indent, titles, info);
+
 				None
 			}
 			Comment::ContinuationItem {
@@ -171,9 +324,62 @@ impl IntoWidgets for Comment {
 					.continuationCommand
 					.token,
 			),
+			Comment::Unknown(unknown) => unknown.into_widgets(titles, info),
 		}
 	}
 }
+impl Comment {
+	/// This reply's details, or the token for more replies. `parent` is the ID of the comment
+	/// these are replies to.
+	pub fn export(self, parent: &str) -> Exported {
+		match self {
+			Comment::Comment { commentRenderer } => {
+				Exported::Comment(commentRenderer.export(Some(parent)), None)
+			}
+			Comment::ContinuationItem {
+				continuationItemRenderer,
+			} => Exported::Continuation(
+				continuationItemRenderer
+					.button
+					.buttonRenderer
+					.command
+					.continuationCommand
+					.token,
+			),
+			Comment::Unknown(_) => Exported::None,
+		}
+	}
+}
+impl IntoWidgets for Comment {
+	/// Should only return a continuation token for replies
+	fn into_widgets<'a>(
+		self,
+		titles: &mut Vec<ListItem<'a>>,
+		info: &mut Vec<(Paragraph<'a>, Node)>,
+	) -> Option<String> {
+		self.add_to("", titles, info)
+	}
+}
+
+/// A reply shown under its comment in the comment section's tree mode, indented
+#[derive(Deserialize)]
+#[serde(transparent)]
+pub struct Reply(Comment);
+impl Filter for Reply {
+	fn matches(&self, filter: &str) -> bool {
+		self.0.matches(filter)
+	}
+}
+impl IntoWidgets for Reply {
+	/// Only returns a continuation token for more replies
+	fn into_widgets<'a>(
+		self,
+		titles: &mut Vec<ListItem<'a>>,
+		info: &mut Vec<(Paragraph<'a>, Node)>,
+	) -> Option<String> {
+		self.0.add_to(REPLY_INDENT, titles, info)
+	}
+}
 
 #[derive(Deserialize)]
 struct Content {
@@ -191,6 +397,13 @@ struct Replies {
 	commentRepliesRenderer: CommentRepliesRenderer,
 }
 
+#[derive(Deserialize)]
+pub struct CommentsHeaderRenderer {
+	/// The different orders comments can be sorted in
+	sortMenu: Option<Menu>,
+	// Ignore `countText`, `createRenderer`, `titleText`, `commentsCount` and others
+}
+
 #[derive(Deserialize)]
 pub struct CommentThreadRenderer {
 	comment: Comment,
@@ -210,8 +423,57 @@ pub enum ContinuationItem {
 	ContinuationItem {
 		continuationItemRenderer: ContinuationItemRenderer,
 	},
-	/// Ignore the header
-	CommentsHeader {},
+	/// Only has the sorts, which aren't shown in the list
+	CommentsHeader {
+		commentsHeaderRenderer: CommentsHeaderRenderer,
+	},
+	Unknown(Unknown),
+}
+impl Filter for ContinuationItem {
+	fn matches(&self, filter: &str) -> bool {
+		match self {
+			ContinuationItem::CommentThread {
+				commentThreadRenderer,
+			} => commentThreadRenderer.comment.matches(filter),
+			_ => true,
+		}
+	}
+}
+impl ContinuationItem {
+	/// This comment's details and the token for its replies, or the token for more comments
+	pub fn export(self) -> Exported {
+		match self {
+			ContinuationItem::CommentThread {
+				commentThreadRenderer,
+			} => match commentThreadRenderer.comment {
+				Comment::Comment { commentRenderer } => Exported::Comment(
+					commentRenderer.export(None),
+					commentThreadRenderer.replies.map(|mut replies| {
+						replies
+							.commentRepliesRenderer
+							.contents
+							.swap_remove(0)
+							.continuationItemRenderer
+							.continuationEndpoint
+							.continuationCommand
+							.token
+					}),
+				),
+				_ => Exported::None,
+			},
+			ContinuationItem::ContinuationItem {
+				continuationItemRenderer,
+			} => Exported::Continuation(
+				continuationItemRenderer
+					.continuationEndpoint
+					.continuationCommand
+					.token,
+			),
+			ContinuationItem::CommentsHeader { .. } | ContinuationItem::Unknown(_) => {
+				Exported::None
+			}
+		}
+	}
 }
 impl IntoWidgets for ContinuationItem {
 	fn into_widgets<'a>(
@@ -223,12 +485,15 @@ impl IntoWidgets for ContinuationItem {
 			ContinuationItem::CommentThread {
 				commentThreadRenderer,
 			} => {
+				let index = info.len();
+
 				// Ignore continuation token, there shouldn't be one
 				commentThreadRenderer.comment.into_widgets(titles, info);
 
 				// Set the link of the just added comment to link to the replies, if there are any.
+				// Its links (if any) come after it.
 				if let Some(mut replies) = commentThreadRenderer.replies {
-					info.last_mut().expect("An item was just added").1 = Node::Comment(
+					info[index].1 = Node::Comment(
 						replies
 							.commentRepliesRenderer
 							.contents
@@ -251,7 +516,10 @@ impl IntoWidgets for ContinuationItem {
 				);
 			}
 			// Ignore the header
-			ContinuationItem::CommentsHeader {} => (),
+			ContinuationItem::CommentsHeader { .. } => (),
+			ContinuationItem::Unknown(unknown) => {
+				unknown.into_widgets(titles, info);
+			}
 		}
 
 		None
@@ -274,6 +542,61 @@ pub struct CommentsResponse {
 	onResponseReceivedEndpoints: Vec<OnResponseReceivedEndpoint>,
 	// Ignore `responseContext` and `trackingParams`
 }
+impl CommentsResponse {
+	/// The comments (and header) in this response
+	pub fn into_items(self) -> Vec<ContinuationItem> {
+		self.onResponseReceivedEndpoints
+			.into_iter()
+			.flat_map(|endpoint| endpoint.reloadContinuationItemsCommand.continuationItems)
+			.collect()
+	}
+
+	/// The orders the comments can be sorted in (names and tokens for the first comments), and the
+	/// index of the current one
+	pub fn sorts(&self) -> (Vec<(String, String)>, usize) {
+		let Some(menu) = self
+			.onResponseReceivedEndpoints
+			.iter()
+			.flat_map(|endpoint| &endpoint.reloadContinuationItemsCommand.continuationItems)
+			.find_map(|item| match item {
+				ContinuationItem::CommentsHeader {
+					commentsHeaderRenderer,
+				} => commentsHeaderRenderer.sortMenu.as_ref(),
+				_ => None,
+			})
+		else {
+			return (Vec::new(), 0);
+		};
+
+		let sub_menu_items = &menu.sortFilterSubMenuRenderer.subMenuItems;
+
+		(
+			sub_menu_items
+				.iter()
+				.map(|item| {
+					(
+						item.title.clone(),
+						item.serviceEndpoint.continuationCommand.token.clone(),
+					)
+				})
+				.collect(),
+			sub_menu_items
+				.iter()
+				.position(|item| item.selected == Some(true))
+				.unwrap_or(0),
+		)
+	}
+
+	/// Removes comments that don't match `filter` (which should be lowercase)
+	pub fn retain(&mut self, filter: &str) {
+		for endpoint in &mut self.onResponseReceivedEndpoints {
+			endpoint
+				.reloadContinuationItemsCommand
+				.continuationItems
+				.retain(|item| item.matches(filter));
+		}
+	}
+}
 impl IntoWidgets for CommentsResponse {
 	fn into_widgets<'a>(
 		self,
@@ -0,0 +1,454 @@
+//! Structures returned from a channel's community tab, or a single post's page
+
+#![allow(non_snake_case)]
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Span, Spans};
+use ratatui::widgets::{ListItem, Paragraph, Wrap};
+use serde::Deserialize;
+
+use super::{
+	spaced, ContinuationItemRenderer, Endpoint, IntoWidgets, Node, SimpleText, Text, Unknown,
+};
+use crate::filter::{self, Details};
+use crate::utils::parse_timestamp;
+
+/// `params` for the community tab of a channel
+pub const COMMUNITY_TAB_PARAMS: &str = "Egljb21tdW5pdHnyBgQKAkoA";
+
+#[derive(Deserialize)]
+struct PollChoice {
+	text: Text,
+	/// I.e. `45%`, not always sent
+	votePercentage: Option<SimpleText>,
+	// Ignore `selected`, `voteRatio`, `signinEndpoint` and others
+}
+
+#[derive(Deserialize)]
+struct PollRenderer {
+	choices: Vec<PollChoice>,
+	/// I.e. `1.2K votes`
+	totalVotes: SimpleText,
+	// Ignore `trackingParams`, `type` and `liveChatPollId`
+}
+
+#[derive(Deserialize)]
+struct Thumbnail {
+	url: String,
+	// Ignore `width` and `height`
+}
+
+#[derive(Deserialize)]
+struct Thumbnails {
+	/// Smallest first
+	thumbnails: Vec<Thumbnail>,
+}
+
+#[derive(Deserialize)]
+struct BackstageImageRenderer {
+	image: Thumbnails,
+	// Ignore `trackingParams`
+}
+
+#[derive(Deserialize)]
+struct BackstageImage {
+	backstageImageRenderer: BackstageImageRenderer,
+}
+
+#[derive(Deserialize)]
+struct PostMultiImageRenderer {
+	images: Vec<BackstageImage>,
+}
+
+/// A video attached to a post
+#[derive(Deserialize)]
+struct AttachedVideoRenderer {
+	/// Missing if the video is unavailable
+	videoId: Option<String>,
+	title: Option<Text>,
+	ownerText: Option<Text>,
+	/// Without the accessibility label `VideoRenderer` has
+	lengthText: Option<SimpleText>,
+	// Ignore everything else, it's the same as `VideoRenderer` but not all there
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BackstageAttachment {
+	Poll {
+		pollRenderer: PollRenderer,
+	},
+	Image {
+		backstageImageRenderer: BackstageImageRenderer,
+	},
+	MultiImage {
+		postMultiImageRenderer: PostMultiImageRenderer,
+	},
+	Video {
+		videoRenderer: AttachedVideoRenderer,
+	},
+	Unknown(Unknown),
+}
+
+#[derive(Deserialize)]
+struct ReplyButtonRenderer {
+	/// Leads to the post's own page, which has the comments
+	navigationEndpoint: Endpoint,
+	// Ignore `text`, `icon`, `accessibility` and `trackingParams`
+}
+
+#[derive(Deserialize)]
+struct ReplyButton {
+	buttonRenderer: ReplyButtonRenderer,
+}
+
+#[derive(Deserialize)]
+struct CommentActionButtonsRenderer {
+	/// Not there if comments are turned off
+	replyButton: Option<ReplyButton>,
+	// Ignore `likeButton`, `trackingParams` and `style`
+}
+
+#[derive(Deserialize)]
+struct ActionButtons {
+	commentActionButtonsRenderer: CommentActionButtonsRenderer,
+}
+
+#[derive(Deserialize)]
+struct BackstagePostRenderer {
+	authorText: Text,
+	/// Missing on image only posts
+	contentText: Option<Text>,
+	backstageAttachment: Option<BackstageAttachment>,
+	publishedTimeText: Text,
+	/// I.e. `1.2K`
+	voteCount: Option<SimpleText>,
+	actionButtons: Option<ActionButtons>,
+	// Ignore `postId`, `authorEndpoint`, `authorThumbnail`, `expandButton`, `voteStatus`,
+	// `surface`, `trackingParams` and `loggingDirectives`
+}
+impl BackstagePostRenderer {
+	/// Adds the post, followed by its attached video (if there is one)
+	fn into_widgets<'a>(
+		self,
+		titles: &mut Vec<ListItem<'a>>,
+		info: &mut Vec<(Paragraph<'a>, Node)>,
+	) {
+		let mut title = self.authorText.with_style(Style {
+			add_modifier: Modifier::BOLD,
+			..Style::default()
+		});
+		title.0.push(" ".into());
+		title.0.extend(
+			self.publishedTimeText
+				.with_style(Style::default().fg(Color::DarkGray))
+				.0,
+		);
+		titles.push(spaced(title));
+
+		let mut lines = Vec::new();
+
+		if let Some(content_text) = self.contentText {
+			lines.push(content_text.with_style(Style::default()));
+			lines.push(Spans::default());
+		}
+
+		// Shown after the post
+		let mut video = None;
+
+		match self.backstageAttachment {
+			Some(BackstageAttachment::Poll { pollRenderer }) => {
+				for choice in pollRenderer.choices {
+					let mut line = Spans(Vec::with_capacity(3));
+
+					if let Some(vote_percentage) = choice.votePercentage {
+						line.0.push(Span::styled(
+							format!("{:>4} ", vote_percentage.simpleText),
+							Style::default().fg(Color::Yellow),
+						));
+					}
+					line.0.extend(choice.text.with_style(Style::default()).0);
+
+					lines.push(line);
+				}
+
+				lines.push(pollRenderer.totalVotes.simpleText.into());
+				lines.push(Spans::default());
+			}
+			Some(BackstageAttachment::Image {
+				backstageImageRenderer,
+			}) => {
+				if let Some(thumbnail) = backstageImageRenderer.image.thumbnails.into_iter().last()
+				{
+					lines.push(["Image: ", &thumbnail.url].concat().into());
+					lines.push(Spans::default());
+				}
+			}
+			Some(BackstageAttachment::MultiImage {
+				postMultiImageRenderer,
+			}) => {
+				for (index, image) in postMultiImageRenderer.images.into_iter().enumerate() {
+					if let Some(thumbnail) = image
+						.backstageImageRenderer
+						.image
+						.thumbnails
+						.into_iter()
+						.last()
+					{
+						lines.push(format!("Image {}: {}", index + 1, thumbnail.url).into());
+					}
+				}
+				lines.push(Spans::default());
+			}
+			Some(BackstageAttachment::Video { videoRenderer }) => video = Some(videoRenderer),
+			Some(BackstageAttachment::Unknown(unknown)) => {
+				lines.push(unknown.into_line());
+				lines.push(Spans::default());
+			}
+			None => (),
+		}
+
+		if let Some(vote_count) = self.voteCount {
+			lines.push([&vote_count.simpleText, " likes"].concat().into());
+		}
+
+		let node = if let Some(reply_button) = self
+			.actionButtons
+			.and_then(|action_buttons| action_buttons.commentActionButtonsRenderer.replyButton)
+		{
+			lines.push("Enter to see comments".into());
+
+			Node::Post(
+				reply_button
+					.buttonRenderer
+					.navigationEndpoint
+					.browseEndpoint
+					.params
+					.unwrap_or_default(),
+			)
+		} else {
+			lines.push("Comments are turned off".into());
+
+			Node::None
+		};
+
+		info.push((Paragraph::new(lines).wrap(Wrap { trim: false }), node));
+
+		if let Some(video) = video {
+			if let Some(video_id) = &video.videoId {
+				filter::describe(
+					video_id,
+					Details {
+						channel_id: video.ownerText.as_ref().and_then(Text::channel_id),
+						channel: video
+							.ownerText
+							.as_ref()
+							.map(Text::plain)
+							.unwrap_or_default(),
+						title: video.title.as_ref().map(Text::plain).unwrap_or_default(),
+						duration: video
+							.lengthText
+							.as_ref()
+							.and_then(|length| parse_timestamp(&length.simpleText)),
+						..Details::default()
+					},
+				);
+			}
+
+			let mut title = Spans::from("  Attached video: ");
+			match video.title {
+				Some(video_title) => title.0.extend(video_title.with_style(Style::default()).0),
+				None => title.0.push("Unavailable".into()),
+			}
+			titles.push(spaced(title));
+
+			info.push(match video.videoId {
+				Some(video_id) => (Paragraph::new("Enter to play"), Node::Video(video_id)),
+				None => (Paragraph::new("This video isn't available"), Node::None),
+			});
+		}
+	}
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Post {
+	BackstagePost {
+		/// Newer responses use `postRenderer`, with the same contents
+		#[serde(alias = "postRenderer")]
+		backstagePostRenderer: Box<BackstagePostRenderer>,
+	},
+	/// I.e. `sharedPostRenderer`
+	Unknown(Unknown),
+}
+
+#[derive(Deserialize)]
+pub struct BackstagePostThreadRenderer {
+	post: Post,
+	// Ignore `trackingParams` and `loggingDirectives`
+}
+
+#[derive(Deserialize)]
+pub struct MessageRenderer {
+	text: Text,
+	// Ignore `trackingParams`
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum CommunityItem {
+	BackstagePostThread {
+		backstagePostThreadRenderer: BackstagePostThreadRenderer,
+	},
+	/// I.e. when the channel hasn't posted anything
+	Message {
+		messageRenderer: MessageRenderer,
+	},
+	/// The end, with continuation token
+	ContinuationItem {
+		continuationItemRenderer: ContinuationItemRenderer,
+	},
+	Unknown(Unknown),
+}
+impl IntoWidgets for CommunityItem {
+	fn into_widgets<'a>(
+		self,
+		titles: &mut Vec<ListItem<'a>>,
+		info: &mut Vec<(Paragraph<'a>, Node)>,
+	) -> Option<String> {
+		match self {
+			CommunityItem::BackstagePostThread {
+				backstagePostThreadRenderer,
+			} => match backstagePostThreadRenderer.post {
+				Post::BackstagePost {
+					backstagePostRenderer,
+				} => backstagePostRenderer.into_widgets(titles, info),
+				Post::Unknown(unknown) => {
+					unknown.into_widgets(titles, info);
+				}
+			},
+			CommunityItem::Message { messageRenderer } => {
+				titles.push(ListItem::new(
+					messageRenderer.text.with_style(Style::default()),
+				));
+				info.push((Paragraph::new(""), Node::None));
+			}
+			CommunityItem::ContinuationItem {
+				continuationItemRenderer,
+			} => {
+				return Some(
+					continuationItemRenderer
+						.continuationEndpoint
+						.continuationCommand
+						.token,
+				)
+			}
+			CommunityItem::Unknown(unknown) => return unknown.into_widgets(titles, info),
+		}
+
+		None
+	}
+}
+
+#[derive(Deserialize)]
+struct ItemSectionRenderer {
+	contents: Vec<CommunityItem>,
+	// Ignore `trackingParams` and `sectionIdentifier`
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SectionListRendererContent {
+	ItemSection {
+		itemSectionRenderer: ItemSectionRenderer,
+	},
+	Item(CommunityItem),
+}
+
+#[derive(Deserialize)]
+struct SectionListRenderer {
+	contents: Vec<SectionListRendererContent>,
+	// Ignore `trackingParams`, `targetId` and others
+}
+
+#[derive(Deserialize)]
+struct TabRendererContent {
+	sectionListRenderer: SectionListRenderer,
+}
+
+#[derive(Deserialize)]
+struct TabRenderer {
+	/// Only the selected tab has content
+	content: Option<TabRendererContent>,
+	// Ignore `endpoint`, `title`, `selected` and `trackingParams`
+}
+
+#[derive(Deserialize)]
+struct Tab {
+	/// Missing on the search tab (`expandableTabRenderer`)
+	tabRenderer: Option<TabRenderer>,
+}
+
+#[derive(Deserialize)]
+struct TwoColumnBrowseResultsRenderer {
+	tabs: Vec<Tab>,
+}
+
+#[derive(Deserialize)]
+struct Contents {
+	twoColumnBrowseResultsRenderer: TwoColumnBrowseResultsRenderer,
+}
+
+/// A channel's community tab. A post's own page has the same layout, with the post followed by
+/// the token for its comments.
+#[derive(Deserialize)]
+pub struct CommunityResponse {
+	contents: Contents,
+	// Ignore `header`, `metadata`, `microformat`, `responseContext`, `topbar` and
+	// `trackingParams`
+}
+impl CommunityResponse {
+	pub fn into_widgets<'a>(
+		self,
+	) -> (
+		Vec<ListItem<'a>>,
+		Vec<(Paragraph<'a>, Node)>,
+		Option<String>,
+	) {
+		let mut titles = Vec::new();
+		let mut info = Vec::new();
+
+		// Continuation token
+		let mut continuation = None;
+
+		for tab in self.contents.twoColumnBrowseResultsRenderer.tabs {
+			for content in tab
+				.tabRenderer
+				.and_then(|tab_renderer| tab_renderer.content)
+				.into_iter()
+				.flat_map(|content| content.sectionListRenderer.contents)
+			{
+				let items = match content {
+					SectionListRendererContent::ItemSection {
+						itemSectionRenderer,
+					} => itemSectionRenderer.contents,
+					SectionListRendererContent::Item(item) => vec![item],
+				};
+
+				for item in items {
+					if let Some(continuation_token) = item.into_widgets(&mut titles, &mut info) {
+						continuation = Some(continuation_token);
+					}
+				}
+			}
+		}
+
+		// The list can't be empty
+		if titles.is_empty() {
+			titles.push(ListItem::new("No posts"));
+			info.push((Paragraph::new(""), Node::None));
+		}
+
+		(titles, info, continuation)
+	}
+}
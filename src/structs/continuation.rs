@@ -5,7 +5,7 @@
 use ratatui::widgets::{ListItem, Paragraph};
 use serde::Deserialize;
 
-use super::{ContinueOnResponseReceivedAction, IntoWidgets, Node};
+use super::{ContinueOnResponseReceivedAction, Filter, IntoWidgets, Node};
 
 #[derive(Deserialize)]
 pub struct ContinuationResponse<T: IntoWidgets> {
@@ -37,3 +37,23 @@ impl<T: IntoWidgets> IntoWidgets for ContinuationResponse<T> {
 		continuation
 	}
 }
+impl<T: IntoWidgets> ContinuationResponse<T> {
+	/// The items in this response
+	pub fn into_items(self) -> Vec<T> {
+		self.onResponseReceivedActions
+			.into_iter()
+			.flat_map(|action| action.appendContinuationItemsAction.continuationItems)
+			.collect()
+	}
+}
+impl<T: IntoWidgets + Filter> ContinuationResponse<T> {
+	/// Removes items that don't match `filter` (which should be lowercase)
+	pub fn retain(&mut self, filter: &str) {
+		for on_response_received_action in &mut self.onResponseReceivedActions {
+			on_response_received_action
+				.appendContinuationItemsAction
+				.continuationItems
+				.retain(|item| item.matches(filter));
+		}
+	}
+}
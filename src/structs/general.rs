@@ -4,7 +4,7 @@
 
 use ratatui::style::{Modifier, Style};
 use ratatui::text;
-use ratatui::text::Spans;
+use ratatui::text::{Span, Spans};
 use ratatui::widgets::{ListItem, Paragraph, Wrap};
 use serde::Deserialize;
 
@@ -118,7 +118,14 @@ struct TabRendererContent {
 
 #[derive(Deserialize)]
 struct TabRenderer {
+	/// Only the selected tab has content
 	content: Option<TabRendererContent>,
+	/// Not there on games' tabs
+	endpoint: Option<Endpoint>,
+	selected: Option<bool>,
+	/// Not there on games' tabs
+	title: Option<String>,
+	// Ignore `trackingParams`
 }
 
 #[derive(Deserialize)]
@@ -209,6 +216,11 @@ struct CarouselHeaderRenderer {
 	contents: Vec<CarouselHeaderRendererContent>, // Ignore `trackingParams`
 }
 
+#[derive(Deserialize)]
+struct FeedTabbedHeaderRenderer {
+	title: Text,
+}
+
 #[derive(Deserialize)]
 #[serde(untagged)]
 enum GeneralResponseHeader {
@@ -221,18 +233,24 @@ enum GeneralResponseHeader {
 	Carousel {
 		carouselHeaderRenderer: CarouselHeaderRenderer,
 	},
+	/// Trending's
+	FeedTabbed {
+		feedTabbedHeaderRenderer: FeedTabbedHeaderRenderer,
+	},
+	Unknown(Unknown),
 }
 
 /// A few requests have this
 #[derive(Deserialize)]
 pub struct GeneralResponse {
 	contents: Contents,
-	header: GeneralResponseHeader,
+	/// Not there on explore
+	header: Option<GeneralResponseHeader>,
 	// Ignore `frameworkUpdates`, `responseContext`, `topbar` and `trackingParams`
 }
 impl GeneralResponse {
 	pub fn into_widgets<'a>(
-		self,
+		mut self,
 	) -> (
 		Vec<ListItem<'a>>,
 		Vec<(Paragraph<'a>, Node)>,
@@ -259,9 +277,10 @@ impl GeneralResponse {
 		let mut continuation = None;
 
 		match self.header {
-			GeneralResponseHeader::InteractiveTabbed {
+			None => {}
+			Some(GeneralResponseHeader::InteractiveTabbed {
 				interactiveTabbedHeaderRenderer,
-			} => {
+			}) => {
 				titles.push(underlined(interactiveTabbedHeaderRenderer.title.simpleText));
 
 				info.push((
@@ -288,16 +307,16 @@ impl GeneralResponse {
 					Node::None,
 				));
 			}
-			GeneralResponseHeader::C4Tabbed {
+			Some(GeneralResponseHeader::C4Tabbed {
 				c4TabbedHeaderRenderer,
-			} => {
+			}) => {
 				titles.push(underlined(c4TabbedHeaderRenderer.title));
 
 				info.push((Paragraph::new(EMPTY_TEXT), Node::None));
 			}
-			GeneralResponseHeader::Carousel {
+			Some(GeneralResponseHeader::Carousel {
 				carouselHeaderRenderer,
-			} => {
+			}) => {
 				for content in carouselHeaderRenderer.contents {
 					match content {
 						CarouselHeaderRendererContent::TopicChannelDetails {
@@ -327,6 +346,20 @@ impl GeneralResponse {
 							carouselItemRenderer,
 						} => {
 							for carousel_item in carouselItemRenderer.carouselItems {
+								let panel = &carousel_item.defaultPromoPanelRenderer;
+								filter::describe(
+									&panel.inlinePlaybackRenderer.inlinePlaybackRenderer.videoId,
+									Details {
+										title: panel.title.plain(),
+										duration: panel
+											.inlinePlaybackRenderer
+											.inlinePlaybackRenderer
+											.lengthText
+											.duration(),
+										..Details::default()
+									},
+								);
+
 								titles.push(spaced(carousel_item.defaultPromoPanelRenderer.title));
 
 								info.push((
@@ -361,6 +394,55 @@ impl GeneralResponse {
 					}
 				}
 			}
+			Some(GeneralResponseHeader::FeedTabbed {
+				feedTabbedHeaderRenderer,
+			}) => {
+				titles.push(ListItem::new(feedTabbedHeaderRenderer.title.underlined()));
+
+				info.push((Paragraph::new(EMPTY_TEXT), Node::None));
+			}
+			Some(GeneralResponseHeader::Unknown(unknown)) => {
+				unknown.into_widgets(&mut titles, &mut info);
+			}
+		}
+
+		// Tabs with names (i.e. trending's), like the home page's tags
+		let mut tabs = false;
+		for tab in &mut self.contents.twoColumnBrowseResultsRenderer.tabs {
+			let Some(title) = tab.tabRenderer.title.take() else {
+				continue;
+			};
+			tabs = true;
+
+			if tab.tabRenderer.selected == Some(true) {
+				titles.push(ListItem::new(Span::styled(
+					title,
+					Style {
+						add_modifier: Modifier::BOLD,
+						..Style::default()
+					},
+				)));
+				info.push((Paragraph::new(EMPTY_TEXT), Node::None));
+			} else {
+				titles.push(ListItem::new(title));
+				info.push((
+					Paragraph::new(EMPTY_TEXT),
+					tab.tabRenderer
+						.endpoint
+						.take()
+						.map_or(Node::None, |endpoint| {
+							Node::Tab(endpoint.browseEndpoint.params)
+						}),
+				));
+			}
+		}
+
+		// Empty line
+		if tabs {
+			titles.push(ListItem::new(text::Text {
+				lines: vec![Spans(Vec::new())],
+			}));
+			info.push((Paragraph::new(EMPTY_TEXT), Node::None));
 		}
 
 		// Only one tab actually has content, but we have to check each one just in case
@@ -386,6 +468,9 @@ impl GeneralResponse {
 							ItemSectionRendererContent::HorizontalCardList {
 								horizontalCardListRenderer,
 							} => horizontalCardListRenderer.into_widgets(&mut titles, &mut info),
+							ItemSectionRendererContent::Unknown(unknown) => {
+								unknown.into_widgets(&mut titles, &mut info);
+							}
 						}
 					}
 				}
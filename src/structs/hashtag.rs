@@ -0,0 +1,231 @@
+//! Structures returned from a hashtag's page, and from resolving its URL
+
+#![allow(non_snake_case)]
+
+use ratatui::widgets::{ListItem, Paragraph, Wrap};
+use serde::Deserialize;
+
+use super::{
+	underlined, ContinuationItemRenderer, Endpoint, IntoWidgets, Node, RichItem,
+	RichSectionItemRendererContent, SimpleText, Unknown,
+};
+
+/// Returned when resolving a URL, i.e. `https://www.youtube.com/hashtag/rust`
+#[derive(Deserialize)]
+pub struct ResolveUrlResponse {
+	endpoint: Endpoint,
+	// Ignore `responseContext`
+}
+impl ResolveUrlResponse {
+	/// The `params` for the page the URL leads to
+	pub fn params(self) -> Option<String> {
+		self.endpoint.browseEndpoint.params
+	}
+}
+
+#[derive(Deserialize)]
+struct HashtagHeaderRenderer {
+	/// I.e. `#rust`
+	hashtag: SimpleText,
+	/// I.e. `1.2K videos • 400 channels`
+	hashtagInfoText: SimpleText,
+	// Ignore `hashtagBackgroundColor` and `trackingParams`
+}
+
+#[derive(Deserialize)]
+struct TextViewModel {
+	content: String,
+}
+
+#[derive(Deserialize)]
+struct MetadataPart {
+	text: TextViewModel,
+}
+
+#[derive(Deserialize)]
+struct MetadataRow {
+	metadataParts: Vec<MetadataPart>,
+}
+
+#[derive(Deserialize)]
+struct ContentMetadataViewModel {
+	metadataRows: Vec<MetadataRow>,
+	// Ignore `delimiter`
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+	contentMetadataViewModel: ContentMetadataViewModel,
+}
+
+#[derive(Deserialize)]
+struct PageHeaderViewModel {
+	metadata: Option<Metadata>,
+	// Ignore `title`, `image`, `actions` and others
+}
+
+#[derive(Deserialize)]
+struct PageHeaderRendererContent {
+	pageHeaderViewModel: PageHeaderViewModel,
+}
+
+#[derive(Deserialize)]
+struct PageHeaderRenderer {
+	/// I.e. `#rust`
+	pageTitle: String,
+	content: PageHeaderRendererContent,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HashtagHeader {
+	HashtagHeader {
+		hashtagHeaderRenderer: HashtagHeaderRenderer,
+	},
+	/// Newer responses use this
+	PageHeader {
+		pageHeaderRenderer: PageHeaderRenderer,
+	},
+	Unknown(Unknown),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum HashtagItem {
+	/// A video or short, boxed to greatly reduce enum's size (and to stop clippy warning)
+	RichItem(Box<RichItem<RichSectionItemRendererContent>>),
+	/// The end, with continuation token
+	ContinuationItem {
+		continuationItemRenderer: ContinuationItemRenderer,
+	},
+	Unknown(Unknown),
+}
+impl IntoWidgets for HashtagItem {
+	fn into_widgets<'a>(
+		self,
+		titles: &mut Vec<ListItem<'a>>,
+		info: &mut Vec<(Paragraph<'a>, Node)>,
+	) -> Option<String> {
+		match self {
+			HashtagItem::RichItem(rich_item) => rich_item
+				.richItemRenderer
+				.content
+				.into_widgets(titles, info),
+			HashtagItem::ContinuationItem {
+				continuationItemRenderer,
+			} => Some(
+				continuationItemRenderer
+					.continuationEndpoint
+					.continuationCommand
+					.token,
+			),
+			HashtagItem::Unknown(unknown) => unknown.into_widgets(titles, info),
+		}
+	}
+}
+
+#[derive(Deserialize)]
+struct RichGridRenderer {
+	contents: Vec<HashtagItem>,
+	// Ignore `trackingParams`, `targetId` and `style`
+}
+
+#[derive(Deserialize)]
+struct TabRendererContent {
+	richGridRenderer: RichGridRenderer,
+}
+
+#[derive(Deserialize)]
+struct TabRenderer {
+	/// Only the selected tab has content
+	content: Option<TabRendererContent>,
+	// Ignore `selected` and `trackingParams`
+}
+
+#[derive(Deserialize)]
+struct Tab {
+	tabRenderer: TabRenderer,
+}
+
+#[derive(Deserialize)]
+struct TwoColumnBrowseResultsRenderer {
+	tabs: Vec<Tab>,
+}
+
+#[derive(Deserialize)]
+struct Contents {
+	twoColumnBrowseResultsRenderer: TwoColumnBrowseResultsRenderer,
+}
+
+/// A hashtag's page, the videos and shorts that use it
+#[derive(Deserialize)]
+pub struct HashtagResponse {
+	contents: Contents,
+	header: HashtagHeader,
+	// Ignore `responseContext`, `topbar`, `trackingParams` and others
+}
+impl HashtagResponse {
+	pub fn into_widgets<'a>(
+		self,
+	) -> (
+		Vec<ListItem<'a>>,
+		Vec<(Paragraph<'a>, Node)>,
+		Option<String>,
+	) {
+		let mut titles = Vec::new();
+		let mut info = Vec::new();
+
+		// Header, with the numbers of videos and channels
+		match self.header {
+			HashtagHeader::HashtagHeader {
+				hashtagHeaderRenderer,
+			} => {
+				titles.push(underlined(hashtagHeaderRenderer.hashtag.simpleText));
+				info.push((
+					Paragraph::new(hashtagHeaderRenderer.hashtagInfoText.simpleText),
+					Node::None,
+				));
+			}
+			HashtagHeader::PageHeader { pageHeaderRenderer } => {
+				titles.push(underlined(pageHeaderRenderer.pageTitle));
+				info.push((
+					Paragraph::new(
+						pageHeaderRenderer
+							.content
+							.pageHeaderViewModel
+							.metadata
+							.into_iter()
+							.flat_map(|metadata| metadata.contentMetadataViewModel.metadataRows)
+							.flat_map(|row| row.metadataParts)
+							.map(|part| part.text.content)
+							.collect::<Vec<String>>()
+							.join(" • "),
+					)
+					.wrap(Wrap { trim: false }),
+					Node::None,
+				));
+			}
+			HashtagHeader::Unknown(unknown) => {
+				unknown.into_widgets(&mut titles, &mut info);
+			}
+		}
+
+		// Continuation token
+		let mut continuation = None;
+
+		for tab in self.contents.twoColumnBrowseResultsRenderer.tabs {
+			for item in tab
+				.tabRenderer
+				.content
+				.into_iter()
+				.flat_map(|content| content.richGridRenderer.contents)
+			{
+				if let Some(continuation_token) = item.into_widgets(&mut titles, &mut info) {
+					continuation = Some(continuation_token);
+				}
+			}
+		}
+
+		(titles, info, continuation)
+	}
+}
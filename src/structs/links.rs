@@ -0,0 +1,198 @@
+//! Links inside descriptions and comments, and the list of them shown when one is entered
+
+#![allow(non_snake_case)]
+
+use ratatui::style::{Color, Style};
+use ratatui::text::{Span, Spans};
+use ratatui::widgets::{ListItem, Paragraph, Wrap};
+use serde::Deserialize;
+use urldecode::decode;
+
+use super::{Node, Unknown};
+use crate::filter::{self, Details};
+use crate::utils::format_timestamp;
+
+#[derive(Deserialize)]
+pub struct LinkWatchEndpoint {
+	videoId: String,
+	/// Only there for timestamps
+	startTimeSeconds: Option<u64>,
+	// Ignore `watchEndpointSupportedOnesieConfig` and others
+}
+
+#[derive(Deserialize)]
+pub struct LinkReelWatchEndpoint {
+	videoId: String,
+	// Ignore `playerParams`, `overlay`, `params` and others
+}
+
+#[derive(Deserialize)]
+pub struct LinkBrowseEndpoint {
+	browseId: String,
+	params: Option<String>,
+	/// I.e. `/hashtag/rust` or `/@fireship`
+	canonicalBaseUrl: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct UrlEndpoint {
+	/// Usually goes through youtube's redirect page
+	url: String,
+	// Ignore `target` and `nofollow`
+}
+
+/// Where a link in some text leads
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum LinkEndpoint {
+	Watch {
+		watchEndpoint: LinkWatchEndpoint,
+	},
+	ReelWatch {
+		reelWatchEndpoint: LinkReelWatchEndpoint,
+	},
+	Browse {
+		browseEndpoint: LinkBrowseEndpoint,
+	},
+	Url {
+		urlEndpoint: UrlEndpoint,
+	},
+	/// I.e. `commandExecutorCommand`, ignored
+	Unknown(#[allow(dead_code)] Unknown),
+}
+impl LinkEndpoint {
+	/// The ID of the channel this leads to, if it leads to one
+	pub fn channel_id(&self) -> Option<&str> {
+		match self {
+			LinkEndpoint::Browse { browseEndpoint }
+				if browseEndpoint.browseId.starts_with("UC") =>
+			{
+				Some(&browseEndpoint.browseId)
+			}
+			_ => None,
+		}
+	}
+
+	/// Converts this to the node it leads to, `text` is the link's text. `None` if it doesn't lead
+	/// anywhere this program can go.
+	pub fn into_node(self, text: &str) -> Option<Node> {
+		Some(match self {
+			LinkEndpoint::Watch { watchEndpoint } => match watchEndpoint.startTimeSeconds {
+				Some(start) => Node::Timestamp(watchEndpoint.videoId, start * 1000),
+				None => Node::Video(watchEndpoint.videoId),
+			},
+			LinkEndpoint::ReelWatch { reelWatchEndpoint } => {
+				Node::Short(reelWatchEndpoint.videoId, None)
+			}
+			LinkEndpoint::Browse { browseEndpoint } if browseEndpoint.browseId == "FEhashtag" => {
+				Node::Hashtag(
+					browseEndpoint
+						.canonicalBaseUrl
+						.as_deref()
+						.and_then(|url| url.strip_prefix("/hashtag/"))
+						.unwrap_or_else(|| text.trim().trim_start_matches('#'))
+						.to_owned(),
+					browseEndpoint.params,
+				)
+			}
+			LinkEndpoint::Browse { browseEndpoint } => {
+				match browseEndpoint.browseId.strip_prefix("VL") {
+					Some(playlist_id) => Node::Playlist(playlist_id.to_owned()),
+					None => Node::Channel(browseEndpoint.browseId, browseEndpoint.params),
+				}
+			}
+			LinkEndpoint::Url { urlEndpoint } => Node::Url(
+				// The real URL is the `q` parameter of the redirect
+				urlEndpoint
+					.url
+					.strip_prefix("https://www.youtube.com/redirect?")
+					.and_then(|query| {
+						query
+							.split('&')
+							.find_map(|parameter| parameter.strip_prefix("q="))
+					})
+					.map_or_else(|| urlEndpoint.url.clone(), |url| decode(url.to_owned())),
+			),
+			LinkEndpoint::Unknown(_) => return None,
+		})
+	}
+}
+
+/// Adds an item after a description or comment that opens the list of its links, if it has any.
+/// Its title is indented by `indent`, like the comment's.
+pub fn add_links<'a>(
+	links: Vec<(String, Node)>,
+	indent: &'static str,
+	titles: &mut Vec<ListItem<'a>>,
+	info: &mut Vec<(Paragraph<'a>, Node)>,
+) {
+	if links.is_empty() {
+		return;
+	}
+
+	titles.push(ListItem::new(Spans(vec![
+		indent.into(),
+		"  ".into(),
+		Span::styled(
+			match links.len() {
+				1 => "1 link".to_owned(),
+				len => format!("{len} links"),
+			},
+			Style::default().fg(Color::DarkGray),
+		),
+	])));
+	info.push((
+		Paragraph::new(
+			links
+				.iter()
+				.map(|(text, _)| Spans::from(text.clone()))
+				.collect::<Vec<Spans>>(),
+		)
+		.wrap(Wrap { trim: false }),
+		Node::Links(links),
+	));
+}
+
+/// The list of links in a description or comment
+pub fn links_into_widgets<'a>(
+	links: Vec<(String, Node)>,
+) -> (Vec<ListItem<'a>>, Vec<(Paragraph<'a>, Node)>) {
+	let mut titles = Vec::with_capacity(links.len());
+	let mut info = Vec::with_capacity(links.len());
+
+	for (text, node) in links {
+		if let Node::Video(video_id) | Node::Short(video_id, _) = &node {
+			// Only the link's text is known
+			filter::describe(
+				video_id,
+				Details {
+					title: text.clone(),
+					short: matches!(node, Node::Short(..)),
+					..Details::default()
+				},
+			);
+		}
+
+		titles.push(ListItem::new(text));
+
+		let description = match &node {
+			Node::Timestamp(_, start) => {
+				["Enter to play from ", &format_timestamp(*start)].concat()
+			}
+			Node::Video(video_id) | Node::Short(video_id, _) => {
+				["Video ", video_id, ", enter to play"].concat()
+			}
+			Node::Channel(browse_id, _) => {
+				["Channel ", browse_id, ", c to see its community posts"].concat()
+			}
+			Node::Playlist(playlist_id) => ["Playlist ", playlist_id].concat(),
+			Node::Hashtag(tag, _) => ["Enter to see videos with #", tag].concat(),
+			Node::Url(url) => ["Enter to open in the browser: ", url].concat(),
+			_ => String::new(),
+		};
+
+		info.push((Paragraph::new(description).wrap(Wrap { trim: false }), node));
+	}
+
+	(titles, info)
+}
@@ -0,0 +1,601 @@
+//! Structures returned from polling a stream's live chat, or requesting its replay
+
+#![allow(non_snake_case)]
+
+use std::time::Duration;
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Span, Spans};
+use ratatui::widgets::{ListItem, Paragraph, Wrap};
+use serde::Deserialize;
+
+use super::{int_to_colour, IntoWidgets, Node, SimpleText, Unknown};
+use crate::config::LIVE_CHAT_POLL_INTERVAL;
+use crate::utils::format_timestamp;
+
+#[derive(Deserialize)]
+struct Emoji {
+	/// The emoji itself for standard emojis, an ID for custom ones
+	emojiId: String,
+	/// I.e. `:face-blue-smiling:`
+	shortcuts: Option<Vec<String>>,
+	isCustomEmoji: Option<bool>,
+	// Ignore `searchTerms`, `image` and `isLocked`
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MessageRun {
+	Text {
+		text: String,
+		// Ignore `navigationEndpoint`
+	},
+	Emoji {
+		emoji: Emoji,
+	},
+}
+
+#[derive(Deserialize)]
+struct Message {
+	runs: Vec<MessageRun>,
+}
+impl Message {
+	/// Custom emojis are shown as their shortcut, since they're images
+	fn into_spans<'a>(self, style: Style) -> Vec<Span<'a>> {
+		self.runs
+			.into_iter()
+			.map(|run| match run {
+				MessageRun::Text { text } => Span::styled(text, style),
+				MessageRun::Emoji { emoji } => Span::styled(
+					if emoji.isCustomEmoji == Some(true) {
+						emoji
+							.shortcuts
+							.and_then(|shortcuts| shortcuts.into_iter().next())
+							.unwrap_or(emoji.emojiId)
+					} else {
+						emoji.emojiId
+					},
+					style,
+				),
+			})
+			.collect()
+	}
+}
+
+/// Some text is sent as runs, some as a simple string
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ChatText {
+	Runs(Message),
+	SimpleText(SimpleText),
+}
+impl ChatText {
+	fn into_spans<'a>(self, style: Style) -> Vec<Span<'a>> {
+		match self {
+			ChatText::Runs(message) => message.into_spans(style),
+			ChatText::SimpleText(simple_text) => vec![Span::styled(simple_text.simpleText, style)],
+		}
+	}
+}
+
+#[derive(Deserialize)]
+struct Icon {
+	/// `OWNER`, `MODERATOR` or `VERIFIED`
+	iconType: String,
+}
+
+#[derive(Deserialize)]
+struct LiveChatAuthorBadgeRenderer {
+	/// Member badges have a `customThumbnail` instead
+	icon: Option<Icon>,
+	/// I.e. `Moderator`, `Member (6 months)`
+	tooltip: String,
+	// Ignore `customThumbnail` and `accessibility`
+}
+
+#[derive(Deserialize)]
+struct AuthorBadge {
+	liveChatAuthorBadgeRenderer: LiveChatAuthorBadgeRenderer,
+}
+
+/// The author of a chat item, shared between item types
+#[derive(Deserialize)]
+struct Author {
+	/// Missing for some system messages
+	authorName: Option<SimpleText>,
+	#[serde(default)]
+	authorBadges: Vec<AuthorBadge>,
+	/// Microseconds since the epoch
+	timestampUsec: String,
+	// Ignore `authorExternalChannelId`, `authorPhoto`, `contextMenuEndpoint`, `id` and
+	// `contextMenuAccessibility`
+}
+impl Author {
+	/// The author's name, coloured by their most important badge
+	fn name<'a>(&self) -> Span<'a> {
+		let colour = self
+			.authorBadges
+			.iter()
+			.map(|badge| {
+				match badge
+					.liveChatAuthorBadgeRenderer
+					.icon
+					.as_ref()
+					.map(|icon| icon.iconType.as_str())
+				{
+					Some("OWNER") => (0, Color::Yellow),
+					Some("MODERATOR") => (1, Color::Blue),
+					Some(_) => (3, Color::Reset),
+					// Only membership badges don't have an icon
+					None => (2, Color::Green),
+				}
+			})
+			.min_by_key(|(priority, _)| *priority)
+			.map_or(Color::Reset, |(_, colour)| colour);
+
+		Span::styled(
+			self.authorName
+				.as_ref()
+				.map_or(String::new(), |name| name.simpleText.clone()),
+			Style::default().fg(colour).add_modifier(Modifier::BOLD),
+		)
+	}
+
+	/// Lines shown at the top of the info panel
+	fn into_lines<'a>(self) -> Vec<Spans<'a>> {
+		let mut lines = vec![self.name().into()];
+
+		if !self.authorBadges.is_empty() {
+			lines.push(
+				[
+					"Badges: ",
+					&self
+						.authorBadges
+						.into_iter()
+						.map(|badge| badge.liveChatAuthorBadgeRenderer.tooltip)
+						.collect::<Vec<_>>()
+						.join(", "),
+				]
+				.concat()
+				.into(),
+			);
+		}
+
+		lines.push(usec_to_time(&self.timestampUsec).into());
+		lines.push("".into());
+
+		lines
+	}
+}
+
+/// Formats a timestamp in microseconds as `HH:MM:SS UTC`
+fn usec_to_time(timestamp_usec: &str) -> String {
+	let seconds = timestamp_usec.parse::<u64>().unwrap_or_default() / 1_000_000 % 86400;
+
+	format!(
+		"{:02}:{:02}:{:02} UTC",
+		seconds / 3600,
+		seconds / 60 % 60,
+		seconds % 60
+	)
+}
+
+#[derive(Deserialize)]
+struct LiveChatTextMessageRenderer {
+	#[serde(flatten)]
+	author: Author,
+	message: Message,
+}
+
+/// A super chat
+#[derive(Deserialize)]
+struct LiveChatPaidMessageRenderer {
+	#[serde(flatten)]
+	author: Author,
+	/// Super chats don't need a message
+	message: Option<Message>,
+	purchaseAmountText: SimpleText,
+	headerBackgroundColor: u32,
+	headerTextColor: u32,
+	bodyBackgroundColor: u32,
+	bodyTextColor: u32,
+	// Ignore `authorNameTextColor`, `timestampColor` and `textInputBackgroundColor`
+}
+
+/// A super sticker
+#[derive(Deserialize)]
+struct LiveChatPaidStickerRenderer {
+	#[serde(flatten)]
+	author: Author,
+	purchaseAmountText: SimpleText,
+	backgroundColor: u32,
+	moneyChipBackgroundColor: u32,
+	moneyChipTextColor: u32,
+	// Ignore `sticker`, `stickerDisplayWidth`, `stickerDisplayHeight` and
+	// `authorNameTextColor`
+}
+
+/// Someone becoming a member, or a member's milestone message
+#[derive(Deserialize)]
+struct LiveChatMembershipItemRenderer {
+	#[serde(flatten)]
+	author: Author,
+	/// I.e. `Member for 6 months`, only on milestones
+	headerPrimaryText: Option<ChatText>,
+	/// I.e. `Welcome to ...!`, or the membership level on milestones
+	headerSubtext: Option<ChatText>,
+	message: Option<Message>,
+}
+
+#[derive(Deserialize)]
+struct LiveChatPlaceholderItemRenderer {}
+
+/// Messages from youtube, i.e. the welcome message
+#[derive(Deserialize)]
+struct LiveChatViewerEngagementMessageRenderer {
+	message: ChatText,
+	// Ignore `icon`, `id`, `timestampUsec` and `actionButton`
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ChatItem {
+	TextMessage {
+		liveChatTextMessageRenderer: LiveChatTextMessageRenderer,
+	},
+	/// A super chat
+	PaidMessage {
+		liveChatPaidMessageRenderer: LiveChatPaidMessageRenderer,
+	},
+	/// A super sticker
+	PaidSticker {
+		liveChatPaidStickerRenderer: LiveChatPaidStickerRenderer,
+	},
+	MembershipItem {
+		liveChatMembershipItemRenderer: LiveChatMembershipItemRenderer,
+	},
+	ViewerEngagementMessage {
+		liveChatViewerEngagementMessageRenderer: LiveChatViewerEngagementMessageRenderer,
+	},
+	/// Stands in for a message that hasn't been sent yet, ignored
+	PlaceholderItem {
+		#[allow(dead_code)]
+		liveChatPlaceholderItemRenderer: LiveChatPlaceholderItemRenderer,
+	},
+	/// Anything else
+	Unknown(Unknown),
+}
+impl ChatItem {
+	/// Adds the chat item to the given lists with the given node, `prefix` goes before the title
+	/// (i.e. a timestamp)
+	fn add_to<'a>(
+		self,
+		prefix: Option<Span<'a>>,
+		node: Node,
+		titles: &mut Vec<ListItem<'a>>,
+		info: &mut Vec<(Paragraph<'a>, Node)>,
+	) {
+		let (mut title, lines) = match self {
+			ChatItem::TextMessage {
+				liveChatTextMessageRenderer: message,
+			} => {
+				let mut title = vec![message.author.name(), ": ".into()];
+				title.extend(message.message.into_spans(Style::default()));
+
+				let mut lines = message.author.into_lines();
+				lines.push(Spans(title[2..].to_vec()));
+
+				(title, lines)
+			}
+			ChatItem::PaidMessage {
+				liveChatPaidMessageRenderer: message,
+			} => {
+				let header_style = Style::default()
+					.fg(int_to_colour(message.headerTextColor))
+					.bg(int_to_colour(message.headerBackgroundColor));
+				let body_style = Style::default()
+					.fg(int_to_colour(message.bodyTextColor))
+					.bg(int_to_colour(message.bodyBackgroundColor));
+
+				let amount = Span::styled(message.purchaseAmountText.simpleText, header_style);
+
+				let mut title = vec![amount.clone(), " ".into(), message.author.name()];
+				let mut body = Vec::new();
+
+				if let Some(text) = message.message {
+					title.push(": ".into());
+					body = text.into_spans(body_style);
+					title.extend(body.clone());
+				}
+
+				let mut lines = message.author.into_lines();
+				lines.push(Spans(vec![amount]));
+				lines.push(Spans(body));
+
+				(title, lines)
+			}
+			ChatItem::PaidSticker {
+				liveChatPaidStickerRenderer: sticker,
+			} => {
+				let amount = Span::styled(
+					sticker.purchaseAmountText.simpleText,
+					Style::default()
+						.fg(int_to_colour(sticker.moneyChipTextColor))
+						.bg(int_to_colour(sticker.moneyChipBackgroundColor)),
+				);
+				let sent = Span::styled(
+					" sent a sticker",
+					Style::default().bg(int_to_colour(sticker.backgroundColor)),
+				);
+
+				let title = vec![amount.clone(), " ".into(), sticker.author.name(), sent];
+
+				let mut lines = sticker.author.into_lines();
+				lines.push(Spans(vec![amount]));
+
+				(title, lines)
+			}
+			ChatItem::MembershipItem {
+				liveChatMembershipItemRenderer: membership,
+			} => {
+				let style = Style::default().fg(Color::Green);
+
+				let mut header = Vec::new();
+
+				if let Some(text) = membership.headerPrimaryText {
+					header.extend(text.into_spans(style));
+					header.push(" ".into());
+				}
+				if let Some(text) = membership.headerSubtext {
+					header.extend(text.into_spans(style));
+				}
+
+				let mut title = vec![membership.author.name(), " ".into()];
+				title.extend(header.clone());
+
+				let mut lines = membership.author.into_lines();
+				lines.push(Spans(header));
+
+				if let Some(message) = membership.message {
+					let message = message.into_spans(Style::default());
+
+					title.push(": ".into());
+					title.extend(message.clone());
+					lines.push(Spans(message));
+				}
+
+				(title, lines)
+			}
+			ChatItem::ViewerEngagementMessage {
+				liveChatViewerEngagementMessageRenderer: message,
+			} => {
+				let message = message
+					.message
+					.into_spans(Style::default().add_modifier(Modifier::ITALIC));
+
+				(message.clone(), vec![Spans(message)])
+			}
+			ChatItem::PlaceholderItem { .. } => return,
+			ChatItem::Unknown(unknown) => {
+				unknown.into_widgets(titles, info);
+
+				return;
+			}
+		};
+
+		if let Some(prefix) = prefix {
+			title.insert(0, prefix);
+		}
+
+		titles.push(ListItem::new(Spans(title)));
+		info.push((Paragraph::new(lines).wrap(Wrap { trim: false }), node));
+	}
+}
+impl IntoWidgets for ChatItem {
+	/// Adds the chat item to the given lists, never returns a continuation token
+	fn into_widgets<'a>(
+		self,
+		titles: &mut Vec<ListItem<'a>>,
+		info: &mut Vec<(Paragraph<'a>, Node)>,
+	) -> Option<String> {
+		self.add_to(None, Node::None, titles, info);
+
+		None
+	}
+}
+
+#[derive(Deserialize)]
+struct AddChatItemAction {
+	item: ChatItem,
+	// Ignore `clientId`
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Action {
+	AddChatItem {
+		addChatItemAction: AddChatItemAction,
+	},
+	/// Deletions, tickers, banners and polls
+	Other {},
+}
+
+#[derive(Deserialize)]
+struct ContinuationData {
+	continuation: String,
+	/// How long to wait before polling again
+	timeoutMs: Option<u64>,
+	// Ignore `invalidationId` and `clickTrackingParams`
+}
+
+#[derive(Deserialize)]
+struct LiveChatContinuationItem {
+	/// The type depends on how busy the chat is, but they're all polled the same way
+	#[serde(alias = "timedContinuationData", alias = "reloadContinuationData")]
+	invalidationContinuationData: ContinuationData,
+}
+
+#[derive(Deserialize)]
+struct LiveChatContinuation {
+	continuations: Vec<LiveChatContinuationItem>,
+	/// Not sent if there's nothing new
+	#[serde(default)]
+	actions: Vec<Action>,
+	// Ignore `trackingParams`
+}
+
+#[derive(Deserialize)]
+struct LiveChatContinuationContents {
+	liveChatContinuation: LiveChatContinuation,
+}
+
+#[derive(Deserialize)]
+pub struct LiveChatResponse {
+	/// Missing once the stream has ended
+	continuationContents: Option<LiveChatContinuationContents>,
+	// Ignore `responseContext`
+}
+impl LiveChatResponse {
+	/// Adds the new chat items to the given lists, returning the token to poll with next and how
+	/// long to wait before doing so
+	pub fn into_widgets<'a>(
+		self,
+		titles: &mut Vec<ListItem<'a>>,
+		info: &mut Vec<(Paragraph<'a>, Node)>,
+	) -> Option<(String, Duration)> {
+		let live_chat_continuation = self.continuationContents?.liveChatContinuation;
+
+		for action in live_chat_continuation.actions {
+			if let Action::AddChatItem { addChatItemAction } = action {
+				addChatItemAction.item.into_widgets(titles, info);
+			}
+		}
+
+		live_chat_continuation
+			.continuations
+			.into_iter()
+			.next()
+			.map(|continuation| {
+				let data = continuation.invalidationContinuationData;
+
+				(
+					data.continuation,
+					data.timeoutMs
+						.map_or(LIVE_CHAT_POLL_INTERVAL, Duration::from_millis),
+				)
+			})
+	}
+}
+
+#[derive(Deserialize)]
+struct ReplayChatItemAction {
+	/// The same as live actions, usually one
+	actions: Vec<Action>,
+	/// When the message was sent, relative to the start of the stream
+	videoOffsetTimeMsec: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ReplayAction {
+	ReplayChatItem {
+		replayChatItemAction: ReplayChatItemAction,
+	},
+	/// Anything else
+	Other {},
+}
+
+#[derive(Deserialize)]
+struct ReplayContinuationData {
+	continuation: String,
+	// Ignore `timeUntilLastMessageMsec` and `clickTrackingParams`
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LiveChatReplayContinuationItem {
+	/// The messages after these ones
+	Replay {
+		liveChatReplayContinuationData: ReplayContinuationData,
+	},
+	/// Used to jump to a different part of the replay
+	PlayerSeek {
+		playerSeekContinuationData: ReplayContinuationData,
+	},
+	Other {},
+}
+
+#[derive(Deserialize)]
+struct LiveChatReplayContinuation {
+	#[serde(default)]
+	continuations: Vec<LiveChatReplayContinuationItem>,
+	#[serde(default)]
+	actions: Vec<ReplayAction>,
+	// Ignore `trackingParams`
+}
+
+#[derive(Deserialize)]
+struct LiveChatReplayContinuationContents {
+	liveChatContinuation: LiveChatReplayContinuation,
+}
+
+#[derive(Deserialize)]
+pub struct LiveChatReplayResponse {
+	/// Missing at the end of the replay
+	continuationContents: Option<LiveChatReplayContinuationContents>,
+	// Ignore `responseContext`
+}
+impl LiveChatReplayResponse {
+	/// Adds the messages to the given lists, each one starting with when it was sent. Returns the
+	/// token for the next messages and the token for seeking, if they're there.
+	pub fn into_widgets<'a>(
+		self,
+		titles: &mut Vec<ListItem<'a>>,
+		info: &mut Vec<(Paragraph<'a>, Node)>,
+	) -> (Option<String>, Option<String>) {
+		let mut continuation = None;
+		let mut seek_continuation = None;
+
+		if let Some(continuation_contents) = self.continuationContents {
+			for action in continuation_contents.liveChatContinuation.actions {
+				if let ReplayAction::ReplayChatItem {
+					replayChatItemAction,
+				} = action
+				{
+					let offset = replayChatItemAction
+						.videoOffsetTimeMsec
+						.parse()
+						.unwrap_or_default();
+
+					for action in replayChatItemAction.actions {
+						if let Action::AddChatItem { addChatItemAction } = action {
+							addChatItemAction.item.add_to(
+								Some(Span::styled(
+									format_timestamp(offset) + " ",
+									Style::default().fg(Color::DarkGray),
+								)),
+								Node::ReplayMessage(offset),
+								titles,
+								info,
+							);
+						}
+					}
+				}
+			}
+
+			for item in continuation_contents.liveChatContinuation.continuations {
+				match item {
+					LiveChatReplayContinuationItem::Replay {
+						liveChatReplayContinuationData,
+					} => continuation = Some(liveChatReplayContinuationData.continuation),
+					LiveChatReplayContinuationItem::PlayerSeek {
+						playerSeekContinuationData,
+					} => seek_continuation = Some(playerSeekContinuationData.continuation),
+					LiveChatReplayContinuationItem::Other {} => (),
+				}
+			}
+		}
+
+		(continuation, seek_continuation)
+	}
+}
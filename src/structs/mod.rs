@@ -3,10 +3,15 @@
 #![allow(non_snake_case)]
 
 pub mod comments;
+pub mod community;
 pub mod continuation;
 pub mod general;
+pub mod hashtag;
+pub mod links;
+pub mod live_chat;
 pub mod next;
 pub mod requests;
+pub mod shorts;
 pub mod transcript;
 pub mod video;
 
@@ -16,8 +21,12 @@ use std::borrow::Cow;
 use std::iter::once;
 
 pub use comments::*;
+pub use community::*;
 pub use continuation::*;
 pub use general::*;
+pub use hashtag::*;
+pub use links::*;
+pub use live_chat::*;
 pub use next::*;
 use ratatui::style::{self, Modifier, Style};
 use ratatui::text;
@@ -25,16 +34,34 @@ use ratatui::text::{Span, Spans};
 use ratatui::widgets::{ListItem, Paragraph, Wrap};
 pub use requests::*;
 pub use search::*;
-use serde::Deserialize;
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Deserializer, Serialize};
+pub use shorts::*;
+use simd_json::OwnedValue;
 pub use transcript::*;
 pub use video::*;
 
-/// A selectable item
+use crate::config::WATCHED_MARKER;
+use crate::filter::{self, Details};
+use crate::search_filter::FilterGroup;
+use crate::utils::{debug_log, debug_log_path, format_timestamp, parse_timestamp};
+use crate::{resume, watched};
+
+/// A selectable item, serialized for bookmarks
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+// simd-json writes externally tagged tuple variants without the closing bracket
+#[serde(tag = "type", content = "value")]
 pub enum Node {
 	/// A tag, property is the continuation token
 	Header(String),
 	/// A video, property is the video ID
 	Video(String),
+	/// A short, properties are the video ID and the params for the sequence after it (if they're
+	/// known)
+	Short(String, Option<String>),
+	/// A tab of the page it's on, property is its `params` field. Entering opens it in place of
+	/// the page.
+	Tab(Option<String>),
 	/// A game, properties are the browse ID and the `params` field
 	Game(String, Option<String>),
 	/// A search, properties are the query and params
@@ -49,6 +76,44 @@ pub enum Node {
 	CommentSection(String),
 	/// A comment with replies, property is the token to get the first section of replies
 	Comment(String),
+	/// A community post, property is the `params` for its page. Entering shows its comments.
+	Post(String),
+	/// A stream's live chat, property is the continuation token to start polling with
+	LiveChat(String),
+	/// A finished stream's chat replay, properties are the video ID and the token for the start of
+	/// the replay
+	ChatReplay(String, String),
+	/// A message in a chat replay, property is when it was sent in milliseconds. Entering seeks
+	/// the player there, if it's playing.
+	ReplayMessage(u64),
+	/// A chapter of a video, properties are the video ID, the chapter's start in milliseconds and
+	/// its title. Entering plays the video from the start of the chapter.
+	Chapter(String, u64, String),
+	/// A timestamp linked in a description or comment, properties are the video ID and the time
+	/// in milliseconds. Entering plays the video from there.
+	Timestamp(String, u64),
+	/// A hashtag, properties are the tag (without the `#`) and the params for its page (if they're
+	/// known)
+	Hashtag(String, Option<String>),
+	/// A link to another website, property is the URL. Entering opens it in the browser.
+	Url(String),
+	/// The links in a description or comment, properties are each link's text and where it leads
+	Links(Vec<(String, Node)>),
+	/// An option in the search filter panel, properties are its group and index in it. Entering
+	/// picks it.
+	SearchFilter(FilterGroup, usize),
+	/// A search in the search history, property is its index (oldest first). Entering searches
+	/// again.
+	PastSearch(usize),
+	/// A list of bookmarks, property is its name. Entering opens it.
+	BookmarkList(String),
+	/// A local playlist, property is its name. Entering opens it.
+	LocalPlaylist(String),
+	/// A video in the open local playlist, property is its index. Entering plays the playlist from
+	/// there.
+	PlaylistEntry(usize),
+	/// A request in the request inspector, property is its ID. Entering saves the response.
+	Request(usize),
 	/// Can be hovered over, but does nothing
 	None,
 }
@@ -60,6 +125,30 @@ fn spaced<'a>(line: impl Into<Spans<'a>>) -> ListItem<'a> {
 	})
 }
 
+/// Turns a video's title into a ratatui `ListItem`, like `spaced` but with a bar under it if it
+/// was left part way through, and dimmed with `WATCHED_MARKER` if it's been watched
+fn video_title<'a>(id: &str, title: impl Into<Spans<'a>>) -> ListItem<'a> {
+	let mut title = title.into();
+
+	if watched::is_watched(id) {
+		for span in &mut title.0 {
+			span.style = span.style.fg(style::Color::DarkGray);
+		}
+
+		title.0.insert(
+			0,
+			Span::styled(WATCHED_MARKER, Style::default().fg(style::Color::DarkGray)),
+		);
+	}
+
+	ListItem::new(text::Text {
+		lines: vec![
+			title,
+			resume::get(id).map_or_else(Spans::default, resume::Position::progress_bar),
+		],
+	})
+}
+
 /// Turns an `Into<Cow<str>>` into a ratatui `ListItem`, underlined
 fn underlined<'a>(line: impl Into<Cow<'a, str>>) -> ListItem<'a> {
 	ListItem::new(text::Text {
@@ -95,6 +184,77 @@ pub trait IntoWidgets {
 	) -> Option<String>;
 }
 
+/// Something youtube sent that this program doesn't understand, either a new renderer or one that's
+/// changed. Used as the last variant of untagged enums so the rest of the page still loads, it's
+/// shown as a placeholder and its JSON is written to the debug log.
+pub struct Unknown {
+	/// Name of the renderer, i.e. `fooRenderer`
+	renderer: String,
+	/// The raw JSON
+	json: String,
+}
+impl<'de> Deserialize<'de> for Unknown {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		/// Finds the renderer's name in some JSON, looking inside `content` for wrappers like
+		/// engagement panels
+		fn renderer_name(value: &OwnedValue) -> Option<&str> {
+			let OwnedValue::Object(object) = value else {
+				return None;
+			};
+
+			object
+				.keys()
+				.filter(|key| key.ends_with("Renderer"))
+				.min()
+				.map(String::as_str)
+				.or_else(|| renderer_name(object.get("content")?))
+				.or_else(|| object.keys().min().map(String::as_str))
+		}
+
+		let value = OwnedValue::deserialize(deserializer)?;
+
+		Ok(Self {
+			renderer: renderer_name(&value).unwrap_or("unknown").to_owned(),
+			json: simd_json::to_string(&value).unwrap_or_default(),
+		})
+	}
+}
+impl Unknown {
+	/// Logs the JSON and returns a placeholder line, for unknown parts of an item
+	fn into_line<'a>(self) -> Spans<'a> {
+		debug_log(&["Unknown renderer `", &self.renderer, "`: ", &self.json].concat());
+
+		Span::styled(
+			["Unsupported: ", &self.renderer].concat(),
+			Style::default().add_modifier(Modifier::ITALIC),
+		)
+		.into()
+	}
+}
+impl IntoWidgets for Unknown {
+	/// Add a placeholder, logging the JSON. Never returns a continuation token.
+	fn into_widgets<'a>(
+		self,
+		titles: &mut Vec<ListItem<'a>>,
+		info: &mut Vec<(Paragraph<'a>, Node)>,
+	) -> Option<String> {
+		let info_text = format!(
+			"Youtube sent a `{}`, which this program doesn't understand (it may be new, or have \
+			 changed). Its JSON was written to {}, please report it.",
+			self.renderer,
+			debug_log_path().display()
+		);
+
+		titles.push(spaced(self.into_line()));
+		info.push((
+			Paragraph::new(info_text).wrap(Wrap { trim: false }),
+			Node::None,
+		));
+
+		None
+	}
+}
+
 #[derive(Deserialize)]
 struct AppendContinuationItemsAction<T: IntoWidgets> {
 	continuationItems: Vec<T>, // Ignore `targetId`
@@ -130,6 +290,8 @@ struct Run {
 	text: String,
 	bold: Option<bool>,
 	italics: Option<bool>,
+	/// Where the run links to, if it's a link
+	navigationEndpoint: Option<LinkEndpoint>,
 }
 
 #[derive(Deserialize)]
@@ -137,6 +299,31 @@ struct Text {
 	runs: Vec<Run>,
 }
 impl Text {
+	/// The text without any formatting
+	fn plain(&self) -> String {
+		self.runs.iter().map(|run| run.text.as_str()).collect()
+	}
+
+	/// The ID of the first channel this links to, i.e. a video's uploader
+	fn channel_id(&self) -> Option<String> {
+		self.runs
+			.iter()
+			.find_map(|run| run.navigationEndpoint.as_ref()?.channel_id())
+			.map(str::to_owned)
+	}
+
+	/// Takes the links out of this, with their text
+	fn links(&mut self) -> Vec<(String, Node)> {
+		self.runs
+			.iter_mut()
+			.filter_map(|run| {
+				let node = run.navigationEndpoint.take()?.into_node(&run.text)?;
+
+				Some((run.text.clone(), node))
+			})
+			.collect()
+	}
+
 	/// Converts this to a `Spans`, applying style, adding the given style
 	fn with_style<'a>(self, style: Style) -> Spans<'a> {
 		Spans(
@@ -237,7 +424,14 @@ struct Accessibility {
 #[derive(Deserialize)]
 struct AccessibleText {
 	accessibility: Accessibility,
-	// Ignore `simpleText`
+	/// Shorter, i.e. `1.2K` for a comment's likes
+	simpleText: Option<String>,
+}
+impl AccessibleText {
+	/// The length this is the text of (i.e. `12:34`), in milliseconds
+	fn duration(&self) -> Option<u64> {
+		parse_timestamp(self.simpleText.as_deref()?)
+	}
 }
 
 /// Group of common text formats
@@ -258,6 +452,20 @@ impl<'a> From<ShortViewCountText> for Spans<'a> {
 	}
 }
 
+/// Whether a video is a livestream, from its badges or its view count (which says how many are
+/// watching for streams, or waiting for upcoming ones)
+fn is_live(
+	badges: Option<&Vec<Badge<MetadataBadgeRendererVideo>>>,
+	view_count: Option<&ShortViewCountText>,
+	upcoming: bool,
+) -> bool {
+	badges.is_some_and(|badges| {
+		badges
+			.iter()
+			.any(|badge| badge.metadataBadgeRenderer.label == "LIVE")
+	}) || (matches!(view_count, Some(ShortViewCountText::Stream(_))) && !upcoming)
+}
+
 #[derive(Deserialize)]
 struct VideoRenderer {
 	badges: Option<Vec<Badge<MetadataBadgeRendererVideo>>>,
@@ -268,6 +476,8 @@ struct VideoRenderer {
 	publishedTimeText: Option<SimpleText>,
 	shortViewCountText: ShortViewCountText,
 	title: Text,
+	/// Only there for streams and premieres that haven't started
+	upcomingEventData: Option<IgnoredAny>,
 	videoId: String,
 	// Ignore `channelThumbnailSupportedRenderers`, `inlinePlaybackEndpoint`, `menu`,
 	// `navigationEndpoint` `shortBylineText`, `showActionMenu`, `thumbnail`, `trackingParams` and
@@ -280,8 +490,26 @@ impl IntoWidgets for VideoRenderer {
 		titles: &mut Vec<ListItem<'a>>,
 		info: &mut Vec<(Paragraph<'a>, Node)>,
 	) -> Option<String> {
+		let upcoming = self.upcomingEventData.is_some();
+		filter::describe(
+			&self.videoId,
+			Details {
+				channel_id: self.ownerText.channel_id(),
+				channel: self.ownerText.plain(),
+				title: self.title.plain(),
+				duration: self.lengthText.as_ref().and_then(AccessibleText::duration),
+				live: is_live(
+					self.badges.as_ref(),
+					Some(&self.shortViewCountText),
+					upcoming,
+				),
+				upcoming,
+				short: false,
+			},
+		);
+
 		// Title on the left
-		titles.push(spaced(self.title));
+		titles.push(video_title(&self.videoId, self.title));
 
 		let mut lines = vec![
 			// Uploader
@@ -307,6 +535,18 @@ impl IntoWidgets for VideoRenderer {
 			lines.push(published_time_text.simpleText.into())
 		}
 
+		if let Some(position) = resume::get(&self.videoId) {
+			lines.push(
+				[
+					"Watched up to ",
+					&format_timestamp(position.position),
+					", it'll be resumed from there",
+				]
+				.concat()
+				.into(),
+			);
+		}
+
 		// Video badges, if any
 		if let Some(badges) = self.badges {
 			lines.push(
@@ -361,20 +601,35 @@ struct Endpoint {
 }
 
 #[derive(Deserialize)]
-struct ReelItemRenderer {
+struct ReelItemRendererReelWatchEndpoint {
+	sequenceParams: Option<String>,
+	// Ignore `videoId`, `playerParams`, `thumbnail`, `overlay`, `params`, `sequenceProvider`,
+	// `loggingContext` and `ustreamerConfig`
+}
+
+#[derive(Deserialize)]
+struct ReelItemRendererNavigationEndpoint {
+	reelWatchEndpoint: ReelItemRendererReelWatchEndpoint,
+	// Ignore `clickTrackingParams` and `commandMetadata`
+}
+
+#[derive(Deserialize)]
+pub struct ReelItemRenderer {
 	headline: SimpleText,
 	videoId: String,
 	viewCountText: AccessibleText,
-	// Ignore `accessibility`, `loggingDirectives`, `menu`, `navigationEndpoint`, `style`,
-	// `thumbnail`, `trackingParams`, `videoType`
+	navigationEndpoint: Option<ReelItemRendererNavigationEndpoint>,
+	// Ignore `accessibility`, `loggingDirectives`, `menu`, `style`, `thumbnail`,
+	// `trackingParams`, `videoType`
 }
 
 #[derive(Deserialize)]
 #[serde(untagged)]
-enum RichSectionItemRendererContent {
+pub enum RichSectionItemRendererContent {
 	Video(RichItemRendererContent),
 	ReelItem { reelItemRenderer: ReelItemRenderer },
 	Game { gameCardRenderer: GameCardRenderer },
+	Unknown(Unknown),
 }
 impl IntoWidgets for RichSectionItemRendererContent {
 	fn into_widgets<'a>(
@@ -389,6 +644,15 @@ impl IntoWidgets for RichSectionItemRendererContent {
 					.into_widgets(list, info_vec);
 			}
 			RichSectionItemRendererContent::ReelItem { reelItemRenderer } => {
+				filter::describe(
+					&reelItemRenderer.videoId,
+					Details {
+						title: reelItemRenderer.headline.simpleText.clone(),
+						short: true,
+						..Details::default()
+					},
+				);
+
 				list.push(spaced(reelItemRenderer.headline.simpleText));
 
 				info_vec.push((
@@ -400,7 +664,12 @@ impl IntoWidgets for RichSectionItemRendererContent {
 							.label,
 					)
 					.wrap(Wrap { trim: false }),
-					Node::Video(reelItemRenderer.videoId),
+					Node::Short(
+						reelItemRenderer.videoId,
+						reelItemRenderer
+							.navigationEndpoint
+							.and_then(|endpoint| endpoint.reelWatchEndpoint.sequenceParams),
+					),
 				));
 			}
 			RichSectionItemRendererContent::Game { gameCardRenderer } => {
@@ -409,6 +678,9 @@ impl IntoWidgets for RichSectionItemRendererContent {
 					.gameDetailsRenderer
 					.into_widgets(list, info_vec);
 			}
+			RichSectionItemRendererContent::Unknown(unknown) => {
+				unknown.into_widgets(list, info_vec);
+			}
 		}
 
 		None
@@ -442,6 +714,8 @@ pub struct GridVideoRenderer {
 	shortBylineText: Text,
 	shortViewCountText: Option<ShortViewCountText>,
 	title: Text,
+	/// Only there for streams and premieres that haven't started
+	upcomingEventData: Option<IgnoredAny>,
 	videoId: String,
 	// Ignore `menu`, `navigationEndpoint`, `richThumbnail`, `thumbnail`, `thumbnailOverlays`,
 	// `trackingParams` and `viewCountText`
@@ -571,7 +845,22 @@ impl HorizontalCardListRenderer {
 		for card in self.cards {
 			match card {
 				Card::Video { videoCardRenderer } => {
-					titles.push(spaced(videoCardRenderer.title));
+					filter::describe(
+						&videoCardRenderer.videoId,
+						Details {
+							title: videoCardRenderer.title.plain(),
+							duration: videoCardRenderer
+								.lengthText
+								.as_ref()
+								.and_then(AccessibleText::duration),
+							..Details::default()
+						},
+					);
+
+					titles.push(video_title(
+						&videoCardRenderer.videoId,
+						videoCardRenderer.title,
+					));
 
 					let mut lines =
 						vec![videoCardRenderer.metadataText.simpleText.into(), "".into()];
@@ -712,6 +1001,8 @@ struct VerticalListRendererItemVideoRenderer {
 	ownerText: Text,
 	shortViewCountText: ShortViewCountText,
 	detailedMetadataSnippets: Option<Vec<DetailedMetadataSnippet>>,
+	/// Only there for streams and premieres that haven't started
+	upcomingEventData: Option<IgnoredAny>,
 	// Ignore `thumbnail`, `longBylineText`, `viewCountText`, `navigationEndpoint`,
 	// `shortBylineText`, `trackingParams`, `showActionMenu`, `menu`,
 	// `channelThumbnailSupportedRenderers`, `thumbnailOverlays` and `searchVideoResultEntityKey`
@@ -728,7 +1019,32 @@ impl VerticalListRendererItem {
 		titles: &mut Vec<ListItem<'a>>,
 		info: &mut Vec<(Paragraph<'a>, Node)>,
 	) {
-		titles.push(spaced(self.videoRenderer.title));
+		let upcoming = self.videoRenderer.upcomingEventData.is_some();
+		filter::describe(
+			&self.videoRenderer.videoId,
+			Details {
+				channel_id: self.videoRenderer.ownerText.channel_id(),
+				channel: self.videoRenderer.ownerText.plain(),
+				title: self.videoRenderer.title.plain(),
+				duration: self
+					.videoRenderer
+					.lengthText
+					.as_ref()
+					.and_then(AccessibleText::duration),
+				live: is_live(
+					self.videoRenderer.badges.as_ref(),
+					Some(&self.videoRenderer.shortViewCountText),
+					upcoming,
+				),
+				upcoming,
+				short: false,
+			},
+		);
+
+		titles.push(video_title(
+			&self.videoRenderer.videoId,
+			self.videoRenderer.title,
+		));
 
 		let mut lines = vec![
 			self.videoRenderer.ownerText.into(),
@@ -814,6 +1130,7 @@ enum ShelfRendererContent {
 	VerticalList {
 		verticalListRenderer: VerticalListRenderer,
 	},
+	Unknown(Unknown),
 }
 
 #[derive(Deserialize)]
@@ -865,6 +1182,9 @@ impl ShelfRenderer {
 					item.into_widgets(titles, info);
 				}
 			}
+			ShelfRendererContent::Unknown(unknown) => {
+				unknown.into_widgets(titles, info);
+			}
 		}
 
 		None
@@ -883,6 +1203,7 @@ enum ItemSectionRendererContent {
 	ShelfRenderer {
 		shelfRenderer: ShelfRenderer,
 	},
+	Unknown(Unknown),
 }
 
 #[derive(Deserialize)]
@@ -927,7 +1248,9 @@ struct ContinuationEndpoint {
 struct SubMenuItem {
 	title: String,
 	serviceEndpoint: ContinuationEndpoint,
-	// Ignore `selected` and `trackingParams`
+	/// Only sent in comments' headers
+	selected: Option<bool>,
+	// Ignore `trackingParams`
 }
 
 #[derive(Deserialize)]
@@ -964,6 +1287,8 @@ pub enum RichGridRendererContent {
 	ContinuationItem {
 		continuationItemRenderer: ContinuationItemRenderer,
 	},
+	/// Anything else
+	Unknown(Unknown),
 }
 impl IntoWidgets for RichGridRendererContent {
 	/// Add this item/category to the given lists, returning continuation token if it's given
@@ -1028,7 +1353,29 @@ impl IntoWidgets for RichGridRendererContent {
 				info_vec.push((Paragraph::new(EMPTY_TEXT), Node::None));
 			}
 			RichGridRendererContent::GridVideoRenderer { gridVideoRenderer } => {
-				list.push(spaced(gridVideoRenderer.title));
+				let upcoming = gridVideoRenderer.upcomingEventData.is_some();
+				filter::describe(
+					&gridVideoRenderer.videoId,
+					Details {
+						channel_id: gridVideoRenderer.shortBylineText.channel_id(),
+						channel: gridVideoRenderer.shortBylineText.plain(),
+						title: gridVideoRenderer.title.plain(),
+						// Grids don't have lengths
+						duration: None,
+						live: is_live(
+							gridVideoRenderer.badges.as_ref(),
+							gridVideoRenderer.shortViewCountText.as_ref(),
+							upcoming,
+						),
+						upcoming,
+						short: false,
+					},
+				);
+
+				list.push(video_title(
+					&gridVideoRenderer.videoId,
+					gridVideoRenderer.title,
+				));
 
 				let mut lines = vec![gridVideoRenderer.shortBylineText.into(), "".into()];
 
@@ -1095,6 +1442,9 @@ impl IntoWidgets for RichGridRendererContent {
 						.token,
 				);
 			}
+			RichGridRendererContent::Unknown(unknown) => {
+				unknown.into_widgets(list, info_vec);
+			}
 		}
 
 		None
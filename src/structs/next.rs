@@ -2,17 +2,19 @@
 
 #![allow(non_snake_case)]
 
-use ratatui::style::Style;
+use ratatui::style::{Color, Style};
 use ratatui::text;
 use ratatui::text::{Span, Spans};
 use ratatui::widgets::{ListItem, Paragraph, Wrap};
 use serde::Deserialize;
 
 use super::{
-	int_to_colour, spaced, AccessibleText, Badge, CurrentVideoEndpoint, Endpoint, IntoWidgets,
-	Menu, MetadataBadgeRendererOwner, MetadataBadgeRendererVideo, Node, ShortViewCountText,
-	SimpleText, EMPTY_TEXT,
+	add_links, int_to_colour, is_live, spaced, video_title, AccessibleText, Badge,
+	CurrentVideoEndpoint, Endpoint, IntoWidgets, LinkEndpoint, Menu, MetadataBadgeRendererOwner,
+	MetadataBadgeRendererVideo, Node, ShortViewCountText, SimpleText, Unknown, EMPTY_TEXT,
 };
+use crate::filter::{self, Details};
+use crate::utils::{format_timestamp, parse_timestamp};
 
 #[derive(Deserialize)]
 pub struct CompactVideoRenderer {
@@ -38,6 +40,7 @@ pub enum SecondaryResultsResult {
 	ContinuationItem {
 		continuationItemRenderer: super::ContinuationItemRenderer,
 	},
+	Unknown(Unknown),
 }
 impl IntoWidgets for SecondaryResultsResult {
 	fn into_widgets<'a>(
@@ -49,7 +52,30 @@ impl IntoWidgets for SecondaryResultsResult {
 			SecondaryResultsResult::CompactVideo {
 				compactVideoRenderer,
 			} => {
-				titles.push(spaced(compactVideoRenderer.title.simpleText));
+				filter::describe(
+					&compactVideoRenderer.videoId,
+					Details {
+						channel_id: compactVideoRenderer.longBylineText.channel_id(),
+						channel: compactVideoRenderer.longBylineText.plain(),
+						title: compactVideoRenderer.title.simpleText.clone(),
+						duration: compactVideoRenderer
+							.lengthText
+							.as_ref()
+							.and_then(AccessibleText::duration),
+						live: is_live(
+							compactVideoRenderer.badges.as_ref(),
+							Some(&compactVideoRenderer.shortViewCountText),
+							false,
+						),
+						upcoming: false,
+						short: false,
+					},
+				);
+
+				titles.push(video_title(
+					&compactVideoRenderer.videoId,
+					compactVideoRenderer.title.simpleText,
+				));
 
 				let mut lines = vec![
 					compactVideoRenderer.longBylineText.into(),
@@ -109,6 +135,7 @@ impl IntoWidgets for SecondaryResultsResult {
 					.continuationCommand
 					.token,
 			),
+			SecondaryResultsResult::Unknown(unknown) => unknown.into_widgets(titles, info),
 		}
 	}
 }
@@ -124,9 +151,37 @@ struct SecondaryResultsOuter {
 	secondaryResults: SecondaryResultsInner,
 }
 
+#[derive(Deserialize)]
+struct ReloadContinuationData {
+	continuation: String,
+	// Ignore `clickTrackingParams`
+}
+
+#[derive(Deserialize)]
+struct LiveChatRendererContinuation {
+	reloadContinuationData: ReloadContinuationData,
+}
+
+#[derive(Deserialize)]
+struct LiveChatRenderer {
+	continuations: Vec<LiveChatRendererContinuation>,
+	/// Set for chat replays of finished streams
+	isReplay: Option<bool>,
+	// Ignore `header`, `ticker`, `trackingParams`, `clientMessages`, `initialDisplayState`,
+	// `showHideButton` and others
+}
+
+#[derive(Deserialize)]
+struct ConversationBar {
+	/// Not present if chat is disabled (`conversationBarRenderer`)
+	liveChatRenderer: Option<LiveChatRenderer>,
+}
+
 #[derive(Deserialize)]
 struct TwoColumnWatchNextResults {
 	secondaryResults: SecondaryResultsOuter,
+	/// Only on streams and premieres
+	conversationBar: Option<ConversationBar>,
 	// Ignore `autoplay` and `results`
 }
 
@@ -162,10 +217,26 @@ struct StyleRun {
 	fontColor: u32,
 }
 
+#[derive(Deserialize)]
+struct CommandRunOnTap {
+	innertubeCommand: LinkEndpoint,
+}
+
+#[derive(Deserialize)]
+struct CommandRun {
+	startIndex: usize,
+	length: usize,
+	onTap: CommandRunOnTap,
+	// Ignore `loggingDirectives`
+}
+
 #[derive(Deserialize)]
 struct AttributedDescriptionBodyText {
 	content: String,
-	styleRuns: Vec<StyleRun>, // Ignore `commandRuns`
+	styleRuns: Vec<StyleRun>,
+	/// Links, not there if the description has none
+	#[serde(default)]
+	commandRuns: Vec<CommandRun>,
 }
 
 #[derive(Deserialize)]
@@ -183,6 +254,7 @@ enum StructuredDescriptionContentItem {
 	ExpandableVideoDescriptionBody {
 		expandableVideoDescriptionBodyRenderer: ExpandableVideoDescriptionBodyRenderer,
 	},
+	Unknown(Unknown),
 }
 
 #[derive(Deserialize)]
@@ -229,6 +301,100 @@ struct SearchableTranscriptContent {
 	continuationItemRenderer: ContinuationItemRenderer,
 }
 
+#[derive(Deserialize)]
+struct OnTapWatchEndpoint {
+	/// Not there for the first chapter
+	#[serde(default)]
+	startTimeSeconds: u64,
+	// Ignore `videoId` and `watchEndpointSupportedOnesieConfig`
+}
+
+#[derive(Deserialize)]
+struct OnTap {
+	watchEndpoint: OnTapWatchEndpoint,
+	// Ignore `clickTrackingParams` and `commandMetadata`
+}
+
+#[derive(Deserialize)]
+struct MacroMarkersListItemRenderer {
+	title: SimpleText,
+	onTap: OnTap,
+	// Ignore `timeDescription`, `thumbnail`, `shareButton`, `repeatButton`, `trackingParams` and
+	// `layout`
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MacroMarkersListItem {
+	MacroMarkersListItem {
+		macroMarkersListItemRenderer: MacroMarkersListItemRenderer,
+	},
+	/// I.e. `macroMarkersInfoItemRenderer`, saying the chapters were generated automatically
+	Unknown(#[allow(dead_code)] Unknown),
+}
+
+#[derive(Deserialize)]
+struct MacroMarkersListRenderer {
+	contents: Vec<MacroMarkersListItem>,
+	// Ignore `syncButtonLabel` and `trackingParams`
+}
+
+#[derive(Deserialize)]
+struct MacroMarkersContent {
+	macroMarkersListRenderer: MacroMarkersListRenderer,
+}
+
+/// Finds chapters in a description, written as lines with a timestamp and a title. Like youtube,
+/// they only count if there are at least 3, in order and starting at `0:00`.
+fn description_chapters(description: &str) -> Vec<(u64, String)> {
+	let mut chapters = Vec::new();
+
+	for line in description.lines() {
+		// Timestamp anywhere on the line, i.e. `0:00 Intro`, `Intro - 0:00` or `(0:00) Intro`
+		let Some((word, start)) = line.split_whitespace().find_map(|word| {
+			let timestamp = word.trim_matches(|char| matches!(char, '(' | ')' | '[' | ']'));
+
+			// Don't treat any number as a timestamp
+			timestamp
+				.contains(':')
+				.then(|| parse_timestamp(timestamp))
+				.flatten()
+				.map(|start| (word, start))
+		}) else {
+			continue;
+		};
+
+		if chapters.last().is_some_and(|(last, _)| *last >= start) {
+			return Vec::new();
+		}
+
+		chapters.push((
+			start,
+			line.replacen(word, "", 1)
+				.trim_matches(|char: char| {
+					char.is_whitespace() || matches!(char, '-' | '–' | '|' | ':')
+				})
+				.to_owned(),
+		));
+	}
+
+	if chapters.len() < 3 || chapters[0].0 != 0 {
+		return Vec::new();
+	}
+
+	chapters
+}
+
+/// Ignore ads
+#[derive(Deserialize)]
+struct AdsEngagementPanelContentRenderer {}
+
+#[derive(Deserialize)]
+struct AdsContent {
+	#[allow(dead_code)]
+	adsEngagementPanelContentRenderer: AdsEngagementPanelContentRenderer,
+}
+
 #[derive(Deserialize)]
 #[serde(untagged)]
 enum EngagementPanelSectionListRenderer {
@@ -243,9 +409,15 @@ enum EngagementPanelSectionListRenderer {
 	SearchableTranscript {
 		content: SearchableTranscriptContent,
 	},
+	/// Chapters, or key moments (which look the same)
+	MacroMarkers { content: MacroMarkersContent },
 	/// Something to do with ads, ignore it
-	/// Can't be unit because of serde untagged stuff
-	Ads {},
+	Ads {
+		#[allow(dead_code)]
+		content: AdsContent,
+	},
+	/// Any other panel
+	Unknown(Unknown),
 }
 
 #[derive(Deserialize)]
@@ -325,6 +497,10 @@ impl NextResponse {
 		// Continuation token
 		let mut continuation = None;
 
+		// Chapters from their own panel, or failing that from the description
+		let mut chapters = Vec::new();
+		let mut chapters_from_description = Vec::new();
+
 		for engagement_panels in self.engagementPanels {
 			match engagement_panels.engagementPanelSectionListRenderer {
 				// Video info
@@ -333,6 +509,9 @@ impl NextResponse {
 					// this.
 					let mut lines = Vec::with_capacity(5);
 
+					// Links in the description
+					let mut description_links = Vec::new();
+
 					for item in content.structuredDescriptionContentRenderer.items {
 						match item {
 							StructuredDescriptionContentItem::VideoDescriptionHeader {
@@ -352,52 +531,85 @@ impl NextResponse {
 							StructuredDescriptionContentItem::ExpandableVideoDescriptionBody {
 								expandableVideoDescriptionBodyRenderer,
 							} => {
-								let mut chars = expandableVideoDescriptionBodyRenderer
-									.attributedDescriptionBodyText
-									.content
-									.chars();
-
-								let mut current_line = Spans(Vec::with_capacity(1));
+								chapters_from_description = description_chapters(
+									&expandableVideoDescriptionBodyRenderer
+										.attributedDescriptionBodyText
+										.content,
+								);
 
+								let description = expandableVideoDescriptionBodyRenderer
+									.attributedDescriptionBodyText;
+
+								// Indexes are in characters, not bytes
+								let chars = description.content.chars().collect::<Vec<char>>();
+								let text = |start: usize, end: usize| {
+									chars[start.min(chars.len())..end.min(chars.len())]
+										.iter()
+										.collect::<String>()
+								};
+
+								description_links = description
+									.commandRuns
+									.into_iter()
+									.filter_map(|command_run| {
+										let text = text(
+											command_run.startIndex,
+											command_run.startIndex + command_run.length,
+										);
+										let node =
+											command_run.onTap.innertubeCommand.into_node(&text)?;
+
+										Some((text, node))
+									})
+									.collect();
+
+								// Split into sections of text, each one coloured or not
+								let mut sections = Vec::new();
 								let mut index = 0;
 
-								// For each section
-								for style_run in expandableVideoDescriptionBodyRenderer
-									.attributedDescriptionBodyText
-									.styleRuns
-								{
-									let section = (&mut chars)
-										.take(style_run.startIndex - index)
-										.collect::<String>();
-									let mut section_lines = section.lines();
-
-									// Add the text before this section, if there is any.
-									if let Some(next) = section_lines.next() {
-										// First line is added separately
-										current_line.0.push(next.to_owned().into());
-
-										for line in section_lines {
-											lines.push(current_line);
-											current_line = Spans(vec![line.to_owned().into()]);
-										}
-									}
+								for style_run in description.styleRuns {
+									// Runs can be nested in or overlap earlier ones, only
+									// the part after those is coloured
+									let start = style_run.startIndex.max(index);
+									let end = (style_run.startIndex + style_run.length).max(start);
 
-									current_line.0.push(Span {
-										content: (&mut chars)
-											.take(style_run.length)
-											.collect::<String>()
-											.into(),
-										style: Style {
+									sections.push((text(index, start), Style::default()));
+									sections.push((
+										text(start, end),
+										Style {
 											fg: Some(int_to_colour(style_run.fontColor)),
 											..Style::default()
 										},
-									});
+									));
 
-									index = style_run.startIndex + style_run.length;
+									index = end;
+								}
+
+								sections.push((text(index, chars.len()), Style::default()));
+
+								// Then split the sections into lines
+								let mut current_line = Spans(Vec::with_capacity(1));
+
+								for (section, style) in sections {
+									for (line_index, line) in section.split('\n').enumerate() {
+										if line_index != 0 {
+											lines.push(current_line);
+											current_line = Spans(Vec::with_capacity(1));
+										}
+
+										if !line.is_empty() {
+											current_line
+												.0
+												.push(Span::styled(line.to_owned(), style));
+										}
+									}
 								}
 
 								lines.push(current_line);
 							}
+							StructuredDescriptionContentItem::Unknown(unknown) => {
+								lines.push(unknown.into_line());
+							}
 						};
 					}
 
@@ -405,6 +617,8 @@ impl NextResponse {
 						Paragraph::new(lines).wrap(Wrap { trim: false }),
 						Node::Video(self.currentVideoEndpoint.watchEndpoint.videoId.clone()),
 					));
+
+					add_links(description_links, "", &mut titles, &mut info);
 				}
 				// Video transcript
 				EngagementPanelSectionListRenderer::SearchableTranscript { content } => {
@@ -429,25 +643,111 @@ impl NextResponse {
 					title.0.push(" Comments".into());
 					titles.push(ListItem::new(title));
 
-					info.push((Paragraph::new(EMPTY_TEXT), Node::None));
-
-					for sub_menu_item in header
-						.engagementPanelTitleHeaderRenderer
-						.menu
-						.sortFilterSubMenuRenderer
-						.subMenuItems
-					{
-						titles.push(ListItem::new(sub_menu_item.title));
-						info.push((
-							Paragraph::new(EMPTY_TEXT),
-							Node::CommentSection(
-								sub_menu_item.serviceEndpoint.continuationCommand.token,
+					// The first sort is the default, the order can be changed on the comments'
+					// page
+					info.push(
+						match header
+							.engagementPanelTitleHeaderRenderer
+							.menu
+							.sortFilterSubMenuRenderer
+							.subMenuItems
+							.into_iter()
+							.next()
+						{
+							Some(sub_menu_item) => (
+								Paragraph::new(["Sorted by ", &sub_menu_item.title].concat()),
+								Node::CommentSection(
+									sub_menu_item.serviceEndpoint.continuationCommand.token,
+								),
 							),
-						));
+							None => (Paragraph::new(EMPTY_TEXT), Node::None),
+						},
+					);
+				}
+				// Chapters, shown after the other panels. Only the first list is used, the others
+				// are key moments.
+				EngagementPanelSectionListRenderer::MacroMarkers { content } => {
+					if chapters.is_empty() {
+						chapters = content
+							.macroMarkersListRenderer
+							.contents
+							.into_iter()
+							.filter_map(|item| match item {
+								MacroMarkersListItem::MacroMarkersListItem {
+									macroMarkersListItemRenderer,
+								} => Some((
+									macroMarkersListItemRenderer
+										.onTap
+										.watchEndpoint
+										.startTimeSeconds * 1000,
+									macroMarkersListItemRenderer.title.simpleText,
+								)),
+								MacroMarkersListItem::Unknown(_) => None,
+							})
+							.collect();
 					}
 				}
 				// Ignore
-				EngagementPanelSectionListRenderer::Ads {} => (),
+				EngagementPanelSectionListRenderer::Ads { .. } => (),
+				EngagementPanelSectionListRenderer::Unknown(unknown) => {
+					unknown.into_widgets(&mut titles, &mut info);
+				}
+			}
+		}
+
+		if chapters.is_empty() {
+			chapters = chapters_from_description;
+		}
+
+		if !chapters.is_empty() {
+			titles.push(ListItem::new("Chapters"));
+			info.push((Paragraph::new(EMPTY_TEXT), Node::None));
+
+			for (start, title) in chapters {
+				let timestamp = format_timestamp(start);
+
+				titles.push(ListItem::new(Spans(vec![
+					"  ".into(),
+					Span::styled(
+						[&timestamp, " "].concat(),
+						Style::default().fg(Color::DarkGray),
+					),
+					title.clone().into(),
+				])));
+				info.push((
+					Paragraph::new(["Enter to play from ", &timestamp].concat()),
+					Node::Chapter(
+						self.currentVideoEndpoint.watchEndpoint.videoId.clone(),
+						start,
+						title,
+					),
+				));
+			}
+		}
+
+		// Live chat, or its replay once the stream has finished
+		if let Some(live_chat_renderer) = self
+			.contents
+			.twoColumnWatchNextResults
+			.conversationBar
+			.and_then(|conversation_bar| conversation_bar.liveChatRenderer)
+		{
+			if let Some(continuation) = live_chat_renderer.continuations.into_iter().next() {
+				let continuation = continuation.reloadContinuationData.continuation;
+
+				if live_chat_renderer.isReplay == Some(true) {
+					titles.push(ListItem::new("Chat replay"));
+					info.push((
+						Paragraph::new(EMPTY_TEXT),
+						Node::ChatReplay(
+							self.currentVideoEndpoint.watchEndpoint.videoId.clone(),
+							continuation,
+						),
+					));
+				} else {
+					titles.push(ListItem::new("Live chat"));
+					info.push((Paragraph::new(EMPTY_TEXT), Node::LiveChat(continuation)));
+				}
 			}
 		}
 
@@ -459,6 +759,30 @@ impl NextResponse {
 
 		// The 'next up' video
 
+		let autoplay = &self
+			.playerOverlays
+			.playerOverlayRenderer
+			.autoplay
+			.playerOverlayAutoplayRenderer;
+		filter::describe(
+			&autoplay.videoId,
+			Details {
+				channel_id: autoplay.byline.channel_id(),
+				channel: autoplay.byline.plain(),
+				title: autoplay.videoTitle.simpleText.clone(),
+				duration: autoplay
+					.thumbnailOverlays
+					.iter()
+					.find_map(|thumbnail_overlay| {
+						thumbnail_overlay
+							.thumbnailOverlayTimeStatusRenderer
+							.text
+							.duration()
+					}),
+				..Details::default()
+			},
+		);
+
 		titles.push(spaced("Autoplay video"));
 
 		let mut lines = vec![
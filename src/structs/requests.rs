@@ -21,8 +21,10 @@ pub struct MainAppWebInfo {
 
 #[derive(Serialize)]
 pub struct Client {
-	pub hl: Option<&'static str>,
-	pub gl: Option<&'static str>,
+	/// Language, set by the program
+	pub hl: Option<String>,
+	/// Region, set by the program
+	pub gl: Option<String>,
 	pub remoteHost: Option<&'static str>,
 	pub deviceMake: Option<&'static str>,
 	pub deviceModel: Option<&'static str>,
@@ -50,7 +52,7 @@ pub struct Client {
 	pub utcOffsetMinutes: Option<i16>,
 	pub userInterfaceTheme: Option<&'static str>,
 	pub mainAppWebInfo: Option<MainAppWebInfo>,
-	pub timeZone: Option<&'static str>,
+	pub timeZone: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -144,6 +146,43 @@ pub struct NextRequest {
 	pub captionsRequested: Option<bool>,
 }
 
+#[derive(Serialize)]
+pub struct PlayerRequest {
+	pub videoId: String,
+}
+
+/// A request for a single short, used to get the params for the sequence after it
+#[derive(Serialize)]
+pub struct ReelItemWatchRequest {
+	pub context: Context,
+	pub playerRequest: PlayerRequest,
+	pub params: Option<&'static str>,
+	pub disablePlayerResponse: Option<bool>,
+}
+
+/// A request for the next shorts in a sequence
+#[derive(Serialize)]
+pub struct ReelWatchSequenceRequest {
+	pub context: Context,
+	/// Either the params from a short or a continuation token
+	pub sequenceParams: String,
+}
+
+#[derive(Serialize)]
+pub struct CurrentPlayerState {
+	/// Milliseconds into the video
+	pub playerOffsetMs: String,
+}
+
+/// A request for part of a finished stream's chat replay
+#[derive(Serialize)]
+pub struct LiveChatReplayRequest {
+	pub context: Context,
+	pub continuation: String,
+	/// Where to get messages from, used when seeking
+	pub currentPlayerState: CurrentPlayerState,
+}
+
 #[derive(Serialize)]
 pub struct SearchRequest {
 	pub context: Context,
@@ -151,3 +190,10 @@ pub struct SearchRequest {
 	pub params: Option<String>,
 	pub webSearchboxStatsUrl: Option<&'static str>,
 }
+
+/// A request to find where a URL leads, used for hashtags typed in the search box
+#[derive(Serialize)]
+pub struct ResolveUrlRequest {
+	pub context: Context,
+	pub url: String,
+}
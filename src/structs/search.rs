@@ -1,7 +1,7 @@
 //! Structs returned in search responses
 
-use ratatui::style::{Modifier, Style};
-use ratatui::text::{self, Span, Spans};
+use ratatui::style::Style;
+use ratatui::text::{self, Spans};
 use ratatui::widgets::{ListItem, Paragraph, Wrap};
 use serde::Deserialize;
 
@@ -9,8 +9,9 @@ use super::{
 	int_to_colour, spaced, underlined, AccessibleText, Color, ContinuationItemRenderer,
 	ContinueOnResponseReceivedAction, CurrentVideoEndpoint, Endpoint, HorizontalCardListRenderer,
 	IntoWidgets, Node, ReelShelfRenderer, SearchFilterRendererNavigationEndpoint, ShelfRenderer,
-	SimpleText, Text, VerticalListRendererItem, EMPTY_TEXT,
+	SimpleText, Text, Unknown, VerticalListRendererItem, EMPTY_TEXT,
 };
+use crate::filter::{self, Details};
 
 #[derive(Deserialize)]
 struct DidYouMeanRenderer {
@@ -92,6 +93,7 @@ enum SectionListRendererContentItemSectionRendererContent {
 		#[allow(dead_code)]
 		adSlotRenderer: AdSlotRenderer,
 	},
+	Unknown(Unknown),
 }
 
 #[derive(Deserialize)]
@@ -130,6 +132,15 @@ impl SectionListRendererContentItemSectionRenderer {
 
 					// First few videos
 					for video in playlistRenderer.videos {
+						filter::describe(
+							&video.childVideoRenderer.videoId,
+							Details {
+								title: video.childVideoRenderer.title.simpleText.clone(),
+								duration: video.childVideoRenderer.lengthText.duration(),
+								..Details::default()
+							},
+						);
+
 						titles.push(ListItem::new(video.childVideoRenderer.title.simpleText));
 						info.push((
 							Paragraph::new(
@@ -201,6 +212,9 @@ impl SectionListRendererContentItemSectionRenderer {
 				} => horizontalCardListRenderer.into_widgets(titles, info),
 				// Ignore ads
 				SectionListRendererContentItemSectionRendererContent::AdSlot { .. } => (),
+				SectionListRendererContentItemSectionRendererContent::Unknown(unknown) => {
+					unknown.into_widgets(titles, info);
+				}
 			}
 		}
 	}
@@ -215,6 +229,7 @@ enum SectionListRendererContent {
 	ContinuationItem {
 		continuationItemRenderer: ContinuationItemRenderer,
 	},
+	Unknown(Unknown),
 }
 impl IntoWidgets for SectionListRendererContent {
 	/// Convert this into widgets, returning the continuation token if there is one
@@ -239,53 +254,16 @@ impl IntoWidgets for SectionListRendererContent {
 					.continuationCommand
 					.token,
 			),
+			SectionListRendererContent::Unknown(unknown) => unknown.into_widgets(titles, info),
 		}
 	}
 }
 
-#[derive(Deserialize)]
-struct SearchFilterRenderer {
-	label: SimpleText,
-	/// Present on selected filters
-	status: Option<String>,
-	/// Not present on selected sort or unavailable filters
-	navigationEndpoint: Option<SearchFilterRendererNavigationEndpoint>,
-	tooltip: String,
-	// Ignore `trackingParams`
-}
-
-#[derive(Deserialize)]
-struct Filter {
-	searchFilterRenderer: SearchFilterRenderer,
-}
-
-#[derive(Deserialize)]
-struct SearchFilterGroupRenderer {
-	filters: Vec<Filter>,
-	title: SimpleText, // Ignore `trackingParams`
-}
-
-#[derive(Deserialize)]
-struct Group {
-	searchFilterGroupRenderer: SearchFilterGroupRenderer,
-}
-
-#[derive(Deserialize)]
-struct SearchSubMenuRenderer {
-	groups: Vec<Group>,
-	// Ignore `button`, `title` and `trackingParams`
-}
-
-#[derive(Deserialize)]
-struct SubMenu {
-	searchSubMenuRenderer: SearchSubMenuRenderer,
-}
-
 #[derive(Deserialize)]
 struct SectionListRenderer {
 	contents: Vec<SectionListRendererContent>,
-	subMenu: SubMenu,
-	// Ignore `hideBottomSeparator`, `targetId` and `trackingParams`
+	// Ignore `subMenu` (filters are encoded by `SearchFilters` instead), `hideBottomSeparator`,
+	// `targetId` and `trackingParams`
 }
 
 #[derive(Deserialize)]
@@ -436,61 +414,6 @@ impl SearchResponse {
 			info.push((Paragraph::new(EMPTY_TEXT), Node::None));
 		}
 
-		// Filters
-		titles.push(underlined("Filters"));
-		info.push((Paragraph::new(EMPTY_TEXT), Node::None));
-
-		for group in self
-			.contents
-			.twoColumnSearchResultsRenderer
-			.primaryContents
-			.sectionListRenderer
-			.subMenu
-			.searchSubMenuRenderer
-			.groups
-		{
-			titles.push(underlined(group.searchFilterGroupRenderer.title.simpleText));
-			info.push((Paragraph::new(EMPTY_TEXT), Node::None));
-
-			for filter in group.searchFilterGroupRenderer.filters {
-				titles.push(ListItem::new(Span {
-					content: filter.searchFilterRenderer.label.simpleText.into(),
-					style: Style {
-						add_modifier: match filter.searchFilterRenderer.status.as_deref() {
-							// Selected filters are bold
-							Some("FILTER_STATUS_SELECTED") => Modifier::BOLD,
-							// Unavailable filters have strikethrough
-							Some("FILTER_STATUS_DISABLED") => Modifier::CROSSED_OUT,
-							_ => Modifier::empty(),
-						},
-						..Style::default()
-					},
-				}));
-
-				info.push((
-					Paragraph::new(filter.searchFilterRenderer.tooltip).wrap(Wrap { trim: false }),
-					if let Some(navigation_endpoint) =
-						filter.searchFilterRenderer.navigationEndpoint
-					{
-						// Most filters
-						Node::Search(
-							navigation_endpoint.searchEndpoint.query,
-							navigation_endpoint.searchEndpoint.params,
-						)
-					} else {
-						// Selected sort
-						Node::None
-					},
-				));
-			}
-		}
-
-		// Empty line
-		titles.push(ListItem::new(text::Text {
-			lines: vec![Spans(Vec::new())],
-		}));
-		info.push((Paragraph::new(EMPTY_TEXT), Node::None));
-
 		// The section on the right
 		if let Some(secondary_contents) = self
 			.contents
@@ -498,6 +421,16 @@ impl SearchResponse {
 			.secondaryContents
 		{
 			for content in secondary_contents.secondarySearchContainerRenderer.contents {
+				// The videos on the card are the channel's
+				let header = &content
+					.universalWatchCardRenderer
+					.header
+					.watchCardRichHeaderRenderer;
+				let channel_id = Some(&header.titleNavigationEndpoint.browseEndpoint.browseId)
+					.filter(|id| id.starts_with("UC"))
+					.cloned();
+				let channel = header.title.simpleText.clone();
+
 				// Title
 				titles.push(
 					spaced(
@@ -570,6 +503,21 @@ impl SearchResponse {
 					),
 				));
 
+				let hero = &content
+					.universalWatchCardRenderer
+					.callToAction
+					.watchCardHeroVideoRenderer;
+				filter::describe(
+					&hero.navigationEndpoint.watchEndpoint.videoId,
+					Details {
+						channel_id: channel_id.clone(),
+						channel: channel.clone(),
+						title: hero.title.simpleText.clone(),
+						duration: hero.lengthText.duration(),
+						..Details::default()
+					},
+				);
+
 				titles.push(spaced(
 					content
 						.universalWatchCardRenderer
@@ -613,6 +561,28 @@ impl SearchResponse {
 				for section in content.universalWatchCardRenderer.sections {
 					for list in section.watchCardSectionSequenceRenderer.lists {
 						for item in list.verticalWatchCardListRenderer.items {
+							filter::describe(
+								&item
+									.watchCardCompactVideoRenderer
+									.navigationEndpoint
+									.watchEndpoint
+									.videoId,
+								Details {
+									channel_id: channel_id.clone(),
+									channel: channel.clone(),
+									title: item
+										.watchCardCompactVideoRenderer
+										.title
+										.simpleText
+										.clone(),
+									duration: item
+										.watchCardCompactVideoRenderer
+										.lengthText
+										.duration(),
+									..Details::default()
+								},
+							);
+
 							titles
 								.push(spaced(item.watchCardCompactVideoRenderer.title.simpleText));
 							info.push((
@@ -687,6 +657,9 @@ impl SearchResponse {
 							.token,
 					)
 				}
+				SectionListRendererContent::Unknown(unknown) => {
+					unknown.into_widgets(&mut titles, &mut info);
+				}
 			}
 		}
 
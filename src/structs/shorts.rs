@@ -0,0 +1,77 @@
+//! Structures returned from the shorts (`reel`) endpoints
+
+#![allow(non_snake_case)]
+
+use ratatui::widgets::{ListItem, Paragraph, Wrap};
+use serde::Deserialize;
+
+use super::{spaced, ContinuationEndpoint, IntoWidgets, Node};
+
+/// Returned when requesting a single short, only used to find the sequence after it
+#[derive(Deserialize)]
+pub struct ReelItemWatchResponse {
+	sequenceContinuation: Option<String>,
+	// Ignore `responseContext`, `overlay`, `replacementEndpoint`, `engagementPanels` and
+	// `trackingParams`
+}
+impl ReelItemWatchResponse {
+	/// Returns the `sequenceParams` to get the shorts after this one
+	pub fn sequence_params(self) -> Option<String> {
+		self.sequenceContinuation
+	}
+}
+
+#[derive(Deserialize)]
+struct ReelWatchEndpoint {
+	videoId: String,
+	// Ignore `playerParams`, `thumbnail`, `overlay`, `params`, `sequenceProvider`,
+	// `sequenceParams`, `loggingContext` and `ustreamerConfig`
+}
+
+#[derive(Deserialize)]
+struct Command {
+	reelWatchEndpoint: ReelWatchEndpoint, // Ignore `clickTrackingParams` and `commandMetadata`
+}
+
+#[derive(Deserialize)]
+struct Entry {
+	command: Command,
+}
+
+/// The shorts after one, in the order they'd be scrolled through
+#[derive(Deserialize)]
+pub struct ReelWatchSequenceResponse {
+	entries: Vec<Entry>,
+	continuationEndpoint: Option<ContinuationEndpoint>,
+	// Ignore `responseContext` and `trackingParams`
+}
+impl IntoWidgets for ReelWatchSequenceResponse {
+	/// Adds the shorts to the given lists, returning the token for the next ones
+	fn into_widgets<'a>(
+		self,
+		titles: &mut Vec<ListItem<'a>>,
+		info: &mut Vec<(Paragraph<'a>, Node)>,
+	) -> Option<String> {
+		for entry in self.entries {
+			short_into_widgets(entry.command.reelWatchEndpoint.videoId, None, titles, info);
+		}
+
+		self.continuationEndpoint
+			.map(|continuation_endpoint| continuation_endpoint.continuationCommand.token)
+	}
+}
+
+/// Adds a short in a sequence to the given lists. Titles aren't in the sequence, so only the first
+/// short (the reel item it was opened from) has one, the video ID is used for the others.
+pub fn short_into_widgets<'a>(
+	video_id: String,
+	title: Option<String>,
+	titles: &mut Vec<ListItem<'a>>,
+	info: &mut Vec<(Paragraph<'a>, Node)>,
+) {
+	titles.push(spaced(title.unwrap_or_else(|| video_id.clone())));
+	info.push((
+		Paragraph::new("Enter to play shorts back to back from here").wrap(Wrap { trim: false }),
+		Node::Short(video_id, None),
+	));
+}
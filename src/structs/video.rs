@@ -3,22 +3,63 @@
 #![allow(non_snake_case)]
 
 use std::cmp::Ordering;
-use std::io::stdout;
-use std::process::Command;
-
-use crossterm::execute;
-use crossterm::terminal::{
-	disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
-};
-use js_sandbox::Script;
+use std::fmt::Write;
+use std::fs::{create_dir_all, remove_file, write};
+use std::io::{stdin, stdout, Read, Write as _};
+use std::path::PathBuf;
+use std::process::{self, Child, Command, Stdio};
+
 use serde::Deserialize;
 use urldecode::decode;
 
 use super::SimpleText;
 use crate::config::*;
+use crate::decipher::{DecipherError, Decipherer};
+use crate::utils::{data_path, format_timestamp};
+use crate::{player, resume, watched};
+
+/// Where chapters are written for the player, in the data directory (not the shared temp directory)
+/// with this process's ID so more than one can run
+fn chapters_path() -> PathBuf {
+	data_path(&format!("chapters-{}.ffmetadata", process::id()))
+}
+
+/// Writes chapters (start in milliseconds and title) to a file in ffmetadata format for the player,
+/// `length` is the video's length in milliseconds (the end of the last chapter)
+fn write_chapters(chapters: &[(u64, String)], length: u64) -> Option<PathBuf> {
+	let mut metadata = String::from(";FFMETADATA1\n");
+
+	for (index, (start, title)) in chapters.iter().enumerate() {
+		let end = chapters.get(index + 1).map_or(length, |(next, _)| *next);
+
+		let _ = write!(
+			metadata,
+			"\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={start}\nEND={end}\ntitle="
+		);
+
+		// These are special in ffmetadata
+		for char in title.chars() {
+			if matches!(char, '=' | ';' | '#' | '\\' | '\n') {
+				metadata.push('\\');
+			}
+			metadata.push(char);
+		}
+
+		metadata.push('\n');
+	}
+
+	let path = chapters_path();
+
+	if let Some(directory) = path.parent() {
+		let _ = create_dir_all(directory);
+	}
+	write(&path, metadata).ok()?;
+
+	Some(path)
+}
 
 /// Formats a number of seconds to be human readable
-fn seconds_to_human(seconds: String) -> String {
+fn seconds_to_human(seconds: &str) -> String {
 	// This is needed since expressions can't be used in match conditions
 	const MINUTE: i64 = 60;
 	const HOUR: i64 = 60 * MINUTE;
@@ -38,8 +79,11 @@ fn seconds_to_human(seconds: String) -> String {
 	}
 }
 
-/// Deciphers the given signature, calling the `s` function in the given `Script`
-fn decipher_signature(script: &mut Script, signature_cipher: String) -> String {
+/// Deciphers the given signature, calling the `s` function in the given `Decipherer`
+fn decipher_signature(
+	decipherer: &mut Decipherer,
+	signature_cipher: String,
+) -> Result<String, DecipherError> {
 	// Iter over params in the string, assuming they're in alphabetical
 	// order (`s`, `sp`, `url`).
 	let mut split = signature_cipher.split('&');
@@ -48,18 +92,16 @@ fn decipher_signature(script: &mut Script, signature_cipher: String) -> String {
 	let sp = &split.next_back().unwrap()[3..];
 	let s = decode(split.next_back().unwrap()[2..].to_owned());
 
-	[
+	Ok([
 		// Url decode two levels
 		&decode(url.replace("%25", "%")),
 		"&",
 		sp,
 		"=",
-		&script
-			.call::<_, String>(
-				"s",
-				&('\0'..unsafe { char::from_u32_unchecked(s.len() as u32) }).collect::<String>(),
-			)
-			.expect("`sig` function shouldn't fail")
+		&decipherer
+			.signature(
+				('\0'..unsafe { char::from_u32_unchecked(s.len() as u32) }).collect::<String>(),
+			)?
 			.bytes()
 			.map(|i| {
 				s.chars()
@@ -68,7 +110,28 @@ fn decipher_signature(script: &mut Script, signature_cipher: String) -> String {
 			})
 			.collect::<String>(),
 	]
-	.concat()
+	.concat())
+}
+
+/// Solves the `n` challenge in the given URL in place. `solved` caches the result, since the same
+/// `n` is used for video and audio: it's `None` if it hasn't been tried yet and `Some(None)` if the
+/// `n` function failed, in which case the URL is left as is (it still works, but is throttled).
+fn solve_n(decipherer: &mut Decipherer, url: &mut String, solved: &mut Option<Option<String>>) {
+	// Extract n parameter from URL
+	let Some(n_start) = url.find("&n=").map(|n_start| n_start + 3) else {
+		return;
+	};
+
+	let n_end = n_start + url[n_start..].find('&').unwrap_or(url.len() - n_start);
+
+	if solved.is_none() {
+		// Failures are logged by the decipherer
+		*solved = Some(decipherer.n(&url[n_start..n_end]).ok());
+	}
+
+	if let Some(Some(n)) = solved {
+		url.replace_range(n_start..n_end, n);
+	}
 }
 
 #[derive(Deserialize)]
@@ -227,52 +290,24 @@ pub struct VideoResponse {
 	// `playbackTracking`, `playerAds`, `playerConfig`, `responseContext`, `storyboards`,
 	// `trackingParams`
 }
-impl VideoResponse {
-	/// Play this video using the user's config
-	pub fn play(self, script: &mut Script) {
-		let _ = disable_raw_mode();
-
-		// We want to be in a normal terminal
-		let _ = execute!(stdout(), LeaveAlternateScreen);
-
-		// Print some video info
-		println!(
-			"Title: {}",
-			self.microformat.playerMicroformatRenderer.title.simpleText
-		);
-
-		if let Some(description) = self.microformat.playerMicroformatRenderer.description {
-			println!("Description: {}", description.simpleText);
-		}
-
-		println!(
-			"
-Length: {}
-{}amily friendly
-{}nlisted
-Views: {}
-Category: {}
-Uploader: {}
-Uploaded: {}
-",
-			seconds_to_human(self.microformat.playerMicroformatRenderer.lengthSeconds),
-			if self.microformat.playerMicroformatRenderer.isFamilySafe {
-				"F"
-			} else {
-				"Not f"
-			},
-			if self.microformat.playerMicroformatRenderer.isUnlisted {
-				"U"
-			} else {
-				"Not u"
-			},
-			self.microformat.playerMicroformatRenderer.viewCount,
-			self.microformat.playerMicroformatRenderer.category,
-			self.microformat.playerMicroformatRenderer.ownerChannelName,
-			self.microformat.playerMicroformatRenderer.uploadDate
-		);
+/// What to play, with deciphered URLs
+#[derive(Debug, PartialEq)]
+pub enum Playback {
+	Video {
+		video_url: String,
+		audio_url: String,
+		/// `None` if `CAPTION_LANGUAGE` isn't set or there are no matching subtitles
+		subtitle_url: Option<String>,
+	},
+	Stream {
+		hls_manifest_url: String,
+	},
+}
 
-		let _ = match self.streamingData {
+impl VideoResponse {
+	/// Picks the formats to play using the user's config and works out their URLs
+	pub fn playback(self, decipherer: &mut Decipherer) -> Result<Playback, DecipherError> {
+		Ok(match self.streamingData {
 			StreamingData::Video { adaptiveFormats } => {
 				// Video, we need to pick the appropriate URL
 
@@ -391,61 +426,35 @@ Uploaded: {}
 				}
 
 				// The same n is used for video and audio, we cache it
-				#[allow(unused_assignments)]
-				let mut decrypted_n = None;
+				let mut solved_n = None;
 
-				let (program, args) = video_player(
-					match ideal_video.expect("Should be at least one video track") {
+				Playback::Video {
+					video_url: match ideal_video.expect("Should be at least one video track") {
 						AdaptiveFormat::Video { mut url, .. } => {
-							// Extract n parameter from URL
-							let n_start = url
-								.find("&n=")
-								.expect("Video URL should contain `n` challenge")
-								+ 3;
-
-							let n_end = n_start + url[n_start..].find('&').unwrap_or(url.len());
-
-							// Eval `n` function and store result
-							decrypted_n = Some(
-								script
-									.call::<_, String>("f", &&url[n_start..n_end])
-									.expect("`n` function shouldn't fail"),
-							);
-
-							url.replace_range(
-								n_start..n_end,
-								decrypted_n.as_ref().expect("We just set it"),
-							);
+							solve_n(decipherer, &mut url, &mut solved_n);
 
 							url
 						}
 						AdaptiveFormat::VideoCipher {
 							signatureCipher, ..
-						} => decipher_signature(script, signatureCipher),
+						} => decipher_signature(decipherer, signatureCipher)?,
 						_ => unreachable!("`ideal_video` should always be a video"),
 					},
-					match ideal_audio.expect("Should be at least one audio track") {
+					audio_url: match ideal_audio.expect("Should be at least one audio track") {
 						AdaptiveFormat::Audio { mut url, .. } => {
-							let start = url
-								.find("&n=")
-								.expect("Audio URL should contain `n` challenge")
-								+ 3;
-
-							url.replace_range(
-								start..start + url[start..].find('&').unwrap_or(url.len()),
-								&decrypted_n.expect("`n` challenge should be solved"),
-							);
+							solve_n(decipherer, &mut url, &mut solved_n);
 
 							url
 						}
 						AdaptiveFormat::AudioCipher {
 							signatureCipher, ..
-						} => decipher_signature(script, signatureCipher),
+						} => decipher_signature(decipherer, signatureCipher)?,
 						_ => {
 							unreachable!("`ideal_audio` should always be audio")
 						}
 					},
-					self.captions
+					subtitle_url: self
+						.captions
 						.zip_with(CAPTION_LANGUAGE, |captions, language| {
 							captions
 								.playerCaptionsTracklistRenderer
@@ -455,21 +464,204 @@ Uploaded: {}
 								.map(|track| track.baseUrl)
 						})
 						.flatten(),
+				}
+			}
+			// It's a stream, the player can handle the resolution stuff
+			StreamingData::Stream { hlsManifestUrl } => Playback::Stream {
+				hls_manifest_url: hlsManifestUrl,
+			},
+		})
+	}
+
+	/// Play this video using the user's config, with `shorts_player` if `short` is set. `start` is
+	/// where to start playing from in milliseconds, `chapters` are passed to the player (start and
+	/// title). Should be called outside the TUI (see `leave_tui`), returns false if the video
+	/// couldn't be played.
+	pub fn play(
+		self,
+		decipherer: &mut Decipherer,
+		short: bool,
+		start: u64,
+		chapters: &[(u64, String)],
+	) -> bool {
+		// Print some video info
+		println!(
+			"Title: {}",
+			self.microformat.playerMicroformatRenderer.title.simpleText
+		);
+
+		if let Some(description) = &self.microformat.playerMicroformatRenderer.description {
+			println!("Description: {}", description.simpleText);
+		}
+
+		println!(
+			"
+Length: {}
+{}amily friendly
+{}nlisted
+Views: {}
+Category: {}
+Uploader: {}
+Uploaded: {}
+",
+			seconds_to_human(&self.microformat.playerMicroformatRenderer.lengthSeconds),
+			if self.microformat.playerMicroformatRenderer.isFamilySafe {
+				"F"
+			} else {
+				"Not f"
+			},
+			if self.microformat.playerMicroformatRenderer.isUnlisted {
+				"U"
+			} else {
+				"Not u"
+			},
+			self.microformat.playerMicroformatRenderer.viewCount,
+			self.microformat.playerMicroformatRenderer.category,
+			self.microformat.playerMicroformatRenderer.ownerChannelName,
+			self.microformat.playerMicroformatRenderer.uploadDate
+		);
+
+		// The end of the last chapter
+		let length = self
+			.microformat
+			.playerMicroformatRenderer
+			.lengthSeconds
+			.parse::<u64>()
+			.unwrap_or_default()
+			* 1000;
+
+		let id = self.videoDetails.videoId.clone();
+
+		// Offer to carry on from where it was left off, unless it's being played from somewhere
+		// in particular
+		let start = match resume::get(&id) {
+			Some(position) if start == 0 && !short => {
+				print!(
+					"Resume from {} of {}? (enter to resume, s to start over) ",
+					format_timestamp(position.position),
+					format_timestamp(position.length)
+				);
+				let _ = stdout().flush();
+
+				let mut input = String::new();
+				let _ = stdin().read_line(&mut input);
+
+				if input.trim().eq_ignore_ascii_case("s") {
+					0
+				} else {
+					position.position
+				}
+			}
+			_ => start,
+		};
+
+		// Only videos can be resumed
+		let mut resumable = false;
+
+		let _ = match self.playback(decipherer) {
+			Ok(Playback::Video {
+				video_url,
+				audio_url,
+				subtitle_url,
+			}) if short => {
+				let (program, args) = shorts_player(video_url, audio_url, subtitle_url);
+
+				Command::new(program).args(args).spawn()
+			}
+			Ok(Playback::Video {
+				video_url,
+				audio_url,
+				subtitle_url,
+			}) => {
+				let chapters_file = if chapters.is_empty() {
+					None
+				} else {
+					write_chapters(chapters, length)
+				};
+
+				let (program, args) = video_player(
+					video_url,
+					audio_url,
+					subtitle_url,
+					start,
+					chapters_file,
+					resume::prepare(),
 				);
+				resumable = true;
 
 				Command::new(program).args(args).spawn()
 			}
-			StreamingData::Stream { hlsManifestUrl } => {
-				// It's a stream, the player can handle the resolution stuff
-				let (program, args) = stream_player(hlsManifestUrl);
+			Ok(Playback::Stream { hls_manifest_url }) => {
+				let (program, args) = stream_player(hls_manifest_url);
 
 				Command::new(program).args(args).spawn()
 			}
+			// We can't play a video without its signature, go back to the UI
+			Err(error) => {
+				print!(
+					"Couldn't decipher signature with player {} ({error}) (press enter to \
+					 continue) ",
+					decipherer.player_version
+				);
+
+				// Wait for enter
+				let _ = stdin().read(&mut [0]);
+
+				return false;
+			}
 		}
 		.unwrap_or_else(|_| panic!("Should be able to spawn PLAYER"))
 		.wait();
 
-		let _ = enable_raw_mode();
-		let _ = execute!(stdout(), EnterAlternateScreen);
+		let _ = remove_file(chapters_path());
+
+		if resumable {
+			resume::record(&id, length);
+		}
+
+		watched::mark(&id);
+
+		true
+	}
+
+	/// Starts `replay_player` in the background, so a chat replay can follow it. Should be called
+	/// outside the TUI, returns the player if it was started.
+	pub fn play_in_background(self, decipherer: &mut Decipherer) -> Option<Child> {
+		match self.playback(decipherer) {
+			Ok(Playback::Video {
+				video_url,
+				audio_url,
+				subtitle_url,
+			}) => {
+				let (program, args) =
+					replay_player(video_url, audio_url, subtitle_url, player::socket());
+
+				// So the replay doesn't talk to a player from before
+				let _ = remove_file(player::socket());
+
+				// Keep the player's output out of the TUI
+				Command::new(program)
+					.args(args)
+					.stdin(Stdio::null())
+					.stdout(Stdio::null())
+					.stderr(Stdio::null())
+					.spawn()
+					.ok()
+			}
+			// Replays are only for finished streams
+			Ok(Playback::Stream { .. }) => None,
+			Err(error) => {
+				print!(
+					"Couldn't decipher signature with player {} ({error}) (press enter to \
+					 continue) ",
+					decipherer.player_version
+				);
+
+				// Wait for enter
+				let _ = stdin().read(&mut [0]);
+
+				None
+			}
+		}
 	}
 }
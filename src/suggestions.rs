@@ -0,0 +1,68 @@
+//! Search suggestions for the search box, fetched in the background while the query is typed
+
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::thread::spawn;
+
+use simd_json::from_slice;
+
+use crate::config::SUGGESTION_DELAY;
+use crate::utils::{new_easy, request_get, suggestions_url};
+
+/// Fetches suggestions on another thread, so typing isn't held up by requests. The thread stops
+/// when this is dropped.
+pub struct Suggester {
+	/// Queries to get suggestions for
+	queries: Sender<String>,
+	/// Suggestions, with the query they're for
+	suggestions: Receiver<(String, Vec<String>)>,
+}
+impl Suggester {
+	pub fn new() -> Suggester {
+		let (queries, query_receiver) = channel::<String>();
+		let (suggestion_sender, suggestions) = channel();
+
+		spawn(move || {
+			let mut easy = new_easy();
+
+			while let Ok(mut query) = query_receiver.recv() {
+				// Wait until typing stops, only the newest query matters
+				loop {
+					match query_receiver.recv_timeout(SUGGESTION_DELAY) {
+						Ok(newer) => query = newer,
+						Err(RecvTimeoutError::Timeout) => break,
+						Err(RecvTimeoutError::Disconnected) => return,
+					}
+				}
+
+				let url = suggestions_url(&mut easy, &query);
+
+				// The response is the query, then the suggestions. They're just left out if the
+				// request fails.
+				let suggestions =
+					from_slice::<(String, Vec<String>)>(&mut request_get(&mut easy, &url))
+						.map_or_else(|_| Vec::new(), |(_, suggestions)| suggestions);
+
+				if suggestion_sender.send((query, suggestions)).is_err() {
+					return;
+				}
+			}
+		});
+
+		Suggester {
+			queries,
+			suggestions,
+		}
+	}
+
+	/// Asks for suggestions for `query`, they arrive after it hasn't changed for
+	/// `SUGGESTION_DELAY`
+	pub fn request(&self, query: &str) {
+		let _ = self.queries.send(query.to_owned());
+	}
+
+	/// The newest suggestions that have arrived since this was last called, with the query
+	/// they're for
+	pub fn receive(&self) -> Option<(String, Vec<String>)> {
+		self.suggestions.try_iter().last()
+	}
+}
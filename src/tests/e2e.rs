@@ -0,0 +1,1404 @@
+//! End to end tests, browsing pages and resolving playback URLs against the mock server in
+//! `mock.rs`
+
+use std::fs::remove_file;
+use std::time::Duration;
+
+use curl::easy::Easy;
+use ratatui::widgets::{List, ListItem, Paragraph};
+
+use super::fixtures::render;
+use super::{data_directory, lock_globals, mock};
+use crate::config::REQUEST_LOG_MAX_RESPONSE;
+use crate::decipher::Decipherer;
+use crate::export::{export_bookmarks, export_comments, Format};
+use crate::filter::{self, title_pattern, Blocklist};
+use crate::inspector::{self, Record};
+use crate::playlists::{self, Entry};
+use crate::resume::{self, Position};
+use crate::search_filter::{FilterGroup, SearchFilters};
+use crate::structs::*;
+use crate::suggestions::Suggester;
+use crate::utils::{
+	api_url, base_url, extract_json, home_url, locale, new_easy, request_get, request_post,
+	set_locale, shuffle, widget_text,
+};
+use crate::{bookmarks, history, toggle_replies, watched, Page};
+
+/// Starts a session like the program does, by loading the home page (which sets the visitor
+/// cookie)
+fn session() -> Easy {
+	mock::start();
+	// Pages remember things (i.e. searches), not in the real data directory
+	data_directory();
+
+	let mut easy = new_easy();
+	request_get(&mut easy, base_url());
+
+	easy
+}
+
+/// A page to go back to, never requested
+fn previous() -> (Box<Page>, usize) {
+	(Box::new(Page::Home { continuation: None }), 0)
+}
+
+/// A video as it's shown next to another one, from `channel` (its ID is `UC<channel>`)
+fn compact_video(id: &str, title: &str, channel: &str, length: &str) -> SecondaryResultsResult {
+	simd_json::from_slice(
+		&mut format!(
+			r#"{{"compactVideoRenderer":{{"videoId":"{id}","title":{{"simpleText":"{title}"}},"longBylineText":{{"runs":[{{"text":"{channel}","navigationEndpoint":{{"browseEndpoint":{{"browseId":"UC{channel}"}}}}}}]}},"lengthText":{{"accessibility":{{"accessibilityData":{{"label":"{length}"}}}},"simpleText":"{length}"}},"shortViewCountText":{{"accessibility":{{"accessibilityData":{{"label":"1 view"}}}}}}}}}}"#
+		)
+		.into_bytes(),
+	)
+	.expect("Video JSON should be valid")
+}
+
+/// Takes the nodes out of a page's info
+fn nodes(info: Vec<(Paragraph, Node)>) -> Vec<Node> {
+	info.into_iter().map(|(_, node)| node).collect()
+}
+
+/// Returns a page's continuation token
+fn continuation(page: &Page) -> Option<&str> {
+	match page {
+		Page::Home { continuation }
+		| Page::Category { continuation, .. }
+		| Page::Game { continuation, .. }
+		| Page::Trending { continuation, .. }
+		| Page::Explore { continuation, .. }
+		| Page::Search { continuation, .. }
+		| Page::Hashtag { continuation, .. }
+		| Page::Next { continuation, .. }
+		| Page::CommentSection { continuation, .. }
+		| Page::Comment { continuation, .. }
+		| Page::Community { continuation, .. } => continuation.as_deref(),
+		Page::Shorts { continuation, .. }
+		| Page::LiveChat { continuation, .. }
+		| Page::ChatReplay { continuation, .. } => continuation.as_deref(),
+		Page::Transcript { .. }
+		| Page::SearchFilters { .. }
+		| Page::SearchHistory { .. }
+		| Page::Bookmarks { .. }
+		| Page::BookmarkList { .. }
+		| Page::Playlists { .. }
+		| Page::LocalPlaylist { .. }
+		| Page::Links { .. }
+		| Page::Inspector { .. } => None,
+	}
+}
+
+/// Requests a page, then continues it, returning the nodes after each step
+fn browse(page: &mut Page) -> (Vec<Node>, Vec<Node>) {
+	let mut easy = session();
+
+	let (mut list, mut info) = page.request(&mut easy);
+	let first = info.iter().map(|(_, node)| node.clone()).collect();
+
+	let length = list.len();
+	page.r#continue(&mut easy, &mut list, &mut info);
+
+	assert_eq!(list.len(), info.len(), "Should be one info panel per item");
+
+	(first, nodes(info.split_off(length)))
+}
+
+#[test]
+fn home() {
+	let mut page = Page::Home { continuation: None };
+
+	let mut easy = session();
+	let (_, info) = page.request(&mut easy);
+
+	assert!(nodes(info).contains(&Node::Video("zF34dRivLOw".to_owned())));
+	assert_eq!(continuation(&page), Some("HOME_CONTINUATION"));
+
+	let (_, continued) = browse(&mut page);
+
+	assert_eq!(continued, [Node::Video("zF34dRivLOw".to_owned())]);
+	assert_eq!(continuation(&page), Some("HOME_CONTINUATION_2"));
+}
+
+#[test]
+fn locale_switcher() {
+	let _globals = lock_globals();
+	let default = locale();
+
+	let mut switched = default.clone();
+	switched.switch("jp ja");
+	assert_eq!(switched.region.as_deref(), Some("JP"));
+	assert_eq!(switched.language.as_deref(), Some("ja"));
+	assert_eq!(switched.to_string(), "JP ja");
+	switched.switch("");
+	assert_eq!(switched.to_string(), "default");
+
+	// Every request is sent in them, the home page's through its URL (and `Accept-Language`)
+	set_locale(|locale| locale.switch("JP ja"));
+	let mut easy = session();
+	let request = simd_json::to_string(
+		&BrowseRequest::new(&mut easy).expect("The mock server should set the visitor cookie"),
+	)
+	.expect("Request should serialize");
+	assert!(request.contains(r#""hl":"ja","gl":"JP""#));
+	assert_eq!(home_url(), [base_url(), "/?gl=JP&hl=ja"].concat());
+	assert!(!request_get(&mut easy, &home_url()).is_empty());
+
+	set_locale(|locale| *locale = default);
+}
+
+#[test]
+fn category() {
+	let (first, _) = browse(&mut Page::Category {
+		continuation: Some("CHIP_MUSIC".to_owned()),
+		previous: previous(),
+	});
+
+	assert_eq!(first, [Node::Video("zF34dRivLOw".to_owned())]);
+}
+
+#[test]
+fn game() {
+	let mut page = Page::Game {
+		browse_id: "UCgame".to_owned(),
+		continuation: None,
+		params: None,
+		previous: previous(),
+	};
+
+	let (first, continued) = browse(&mut page);
+
+	assert!(first.contains(&Node::Video("gridvideo01".to_owned())));
+	assert_eq!(continued, [Node::Video("zF34dRivLOw".to_owned())]);
+}
+
+#[test]
+fn trending() {
+	let mut page = Page::Trending {
+		params: None,
+		continuation: None,
+		previous: previous(),
+	};
+
+	let mut easy = session();
+	let (_, info) = page.request(&mut easy);
+	let first = nodes(info);
+
+	assert!(first.contains(&Node::Video("trending001".to_owned())));
+	assert!(first.contains(&Node::Tab(Some("TRENDING_MUSIC".to_owned()))));
+	assert_eq!(continuation(&page), None);
+
+	// Tabs open in place of the page
+	page = Page::Trending {
+		params: Some("TRENDING_MUSIC".to_owned()),
+		continuation: None,
+		previous: previous(),
+	};
+	let (_, info) = page.request(&mut easy);
+	assert!(nodes(info).contains(&Node::Video("trending001".to_owned())));
+}
+
+#[test]
+fn search() {
+	let mut page = Page::Search {
+		query: "rust".to_owned(),
+		params: None,
+		filters: SearchFilters::default(),
+		continuation: None,
+		previous: previous(),
+	};
+
+	let (first, continued) = browse(&mut page);
+
+	assert!(first.contains(&Node::Video("zF34dRivLOw".to_owned())));
+	assert!(continued.contains(&Node::Playlist("PLrust".to_owned())));
+	assert_eq!(continuation(&page), Some("SEARCH_CONTINUATION_2"));
+}
+
+#[test]
+fn search_blocklist() {
+	// So the blocklist file isn't there
+	let _globals = lock_globals();
+	let mut easy = session();
+	let mut page = Page::Search {
+		query: "rust".to_owned(),
+		params: None,
+		filters: SearchFilters::default(),
+		continuation: None,
+		previous: previous(),
+	};
+
+	let (mut list, mut info) = page.request(&mut easy);
+	page.r#continue(&mut easy, &mut list, &mut info);
+
+	// The video by its channel's name, the playlist's first video by its length
+	Blocklist::parse("name fireship\nmax-duration 5:00").apply(
+		&page.to_string(),
+		&mut list,
+		&mut info,
+	);
+
+	assert_eq!(list.len(), info.len(), "Should be one info panel per item");
+	let nodes = nodes(info);
+	assert!(!nodes.contains(&Node::Video("zF34dRivLOw".to_owned())));
+	assert!(!nodes.contains(&Node::Video("playlistv01".to_owned())));
+	assert!(nodes.contains(&Node::Playlist("PLrust".to_owned())));
+	assert!(nodes.contains(&Node::Channel("UCaYhcUwRBNscFNUKTjgPFiA".to_owned(), None)));
+}
+
+#[test]
+fn search_filters() {
+	let mut filters = SearchFilters::default();
+	assert_eq!(filters.params(), None);
+
+	// Like `--type video --duration long --sort date`
+	assert_eq!(filters.set(FilterGroup::Type, "video"), Ok(()));
+	assert_eq!(filters.set(FilterGroup::Duration, "long"), Ok(()));
+	assert_eq!(filters.params().as_deref(), Some("EgQQARgC"));
+	assert_eq!(filters.set(FilterGroup::Sort, "date"), Ok(()));
+	assert_eq!(filters.params().as_deref(), Some("CAISBBABGAI%3D"));
+	assert_eq!(
+		filters.summary(),
+		["Video", "Over 20 minutes", "Upload date"]
+	);
+	assert_eq!(filters.set(FilterGroup::Features, "4k,nope"), Err("nope"));
+
+	// Picking an option in the panel replaces the other one in its group
+	let (_, info) = Page::SearchFilters {
+		query: "rust".to_owned(),
+		filters: filters.clone(),
+		previous: previous(),
+	}
+	.request(&mut session());
+	let nodes = nodes(info);
+	assert_eq!(nodes[0], Node::Search("rust".to_owned(), filters.params()));
+	assert!(nodes.contains(&Node::SearchFilter(FilterGroup::Type, 1)));
+
+	filters.toggle(FilterGroup::Type, 1);
+	assert_eq!(filters.summary()[0], "Channel");
+	filters.toggle(FilterGroup::Type, 1);
+	assert_eq!(filters.summary()[0], "Over 20 minutes");
+
+	// Searches opened with params (from links, bookmarks or the history) show their filters
+	assert_eq!(
+		SearchFilters::from_params(Some("CAISBBABGAI%3D")).summary(),
+		["Video", "Over 20 minutes", "Upload date"]
+	);
+	filters
+		.set(FilterGroup::Features, "4k")
+		.expect("4K should exist");
+	assert_eq!(
+		SearchFilters::from_params(filters.params().as_deref()),
+		filters
+	);
+	assert_eq!(SearchFilters::from_params(None), SearchFilters::default());
+	// Unless they have something else, then they're kept until a filter is picked
+	let mut custom = SearchFilters::from_params(Some("Zm9v"));
+	assert_eq!(custom.summary(), ["Custom filters"]);
+	assert_eq!(custom.params().as_deref(), Some("Zm9v"));
+	custom.toggle(FilterGroup::Type, 0);
+	assert_eq!(custom.params().as_deref(), Some("EgIQAQ%3D%3D"));
+}
+
+#[test]
+fn suggestions() {
+	// Points the suggestions at the mock server
+	session();
+
+	let suggester = Suggester::new();
+
+	// Only the newest query is requested, once typing stops
+	suggester.request("ru");
+	suggester.request("rust");
+
+	let mut received = None;
+	for _ in 0..50 {
+		received = suggester.receive();
+
+		if received.is_some() {
+			break;
+		}
+
+		std::thread::sleep(Duration::from_millis(100));
+	}
+
+	let (query, suggestions) = received.expect("Suggestions should arrive");
+	assert_eq!(query, "rust");
+	assert_eq!(suggestions, ["rust", "rust game", "rust tutorial", "rustc"]);
+}
+
+#[test]
+fn search_history() {
+	let _globals = lock_globals();
+	let directory = data_directory();
+
+	history::record("rust", None);
+	history::record("ratatui", Some("EgIQAQ%3D%3D"));
+	// Searching again moves it to the top
+	history::record("rust", None);
+
+	assert_eq!(history::queries(), ["ratatui", "rust"]);
+
+	let (titles, info) = history::into_widgets();
+	assert_eq!(
+		render(List::new(titles), 3)[1..],
+		["rust", "ratatui (filtered)"]
+	);
+	assert_eq!(nodes(info)[1..], [Node::PastSearch(1), Node::PastSearch(0)]);
+
+	// It's kept in the data directory
+	assert!(directory.join("search_history.json").exists());
+
+	// Nothing is recorded while recording is off
+	assert!(!history::toggle_recording());
+	history::record("cargo", None);
+	assert_eq!(history::queries(), ["ratatui", "rust"]);
+	assert!(history::toggle_recording());
+
+	history::remove(0);
+	assert_eq!(history::queries(), ["rust"]);
+	assert_eq!(history::get(0).map(|search| search.params), Some(None));
+
+	let _ = remove_file(directory.join("search_history.json"));
+}
+
+#[test]
+fn bookmark_lists() {
+	let _globals = lock_globals();
+	let directory = data_directory();
+	let video = Node::Video("zF34dRivLOw".to_owned());
+	let search = Node::Search("lofi beats".to_owned(), Some("EgIQAQ%3D%3D".to_owned()));
+
+	// The snapshot is the text that was shown, wide characters and all
+	let title = widget_text(List::new(vec![ListItem::new("日本語 video")]), 1);
+	assert_eq!(title, "日本語 video");
+
+	assert!(bookmarks::add("", video.clone(), title, "Info".to_owned()));
+	// Already there
+	assert!(!bookmarks::add(
+		"Watch later",
+		video.clone(),
+		String::new(),
+		String::new()
+	));
+	assert!(bookmarks::add(
+		"Music",
+		search.clone(),
+		"lofi beats".to_owned(),
+		String::new()
+	));
+	assert!(!bookmarks::can_save(&Node::None));
+	// Their pages don't exist yet
+	assert!(!bookmarks::can_save(&Node::Playlist("PLAYLIST".to_owned())));
+	assert!(!bookmarks::can_save(&Node::CommentSection(
+		"COMMENTS_CONTINUATION".to_owned()
+	)));
+
+	assert_eq!(bookmarks::names(), ["Watch later", "Music"]);
+
+	let (titles, info) = bookmarks::list_widgets("Watch later");
+	assert_eq!(widget_text(List::new(titles), 2), "日本語 video");
+	// Entering it works like it did on its page
+	assert_eq!(nodes(info), [video]);
+
+	// They're kept in the data directory, nodes and all
+	let mut file =
+		std::fs::read(directory.join("bookmarks.json")).expect("Bookmarks should be saved");
+	let lists = simd_json::from_slice::<Vec<bookmarks::BookmarkList>>(&mut file)
+		.expect("Bookmarks should be valid JSON");
+	assert_eq!(lists[1].bookmarks[0].node, search);
+
+	let mut csv = Vec::new();
+	assert_eq!(
+		export_bookmarks(
+			&mut new_easy(),
+			&bookmarks::lists(None),
+			Format::Csv,
+			&mut csv
+		),
+		Ok(2)
+	);
+	let csv = String::from_utf8(csv).expect("Export should be UTF-8");
+	let rows = csv.lines().collect::<Vec<_>>();
+	assert_eq!(rows[0], "list,title,info,url,saved");
+	assert!(rows[1].starts_with(
+		&[
+			"Watch later,日本語 video,Info,",
+			base_url(),
+			"/watch?v=zF34dRivLOw,"
+		]
+		.concat()
+	));
+	assert!(rows[2].contains("/results?search_query=lofi%20beats&sp=EgIQAQ%3D%3D"));
+
+	let mut json = Vec::new();
+	export_bookmarks(
+		&mut new_easy(),
+		&bookmarks::lists(Some("Music")),
+		Format::Json,
+		&mut json,
+	)
+	.expect("Export should be written");
+	// A full disk is an error, not a panic
+	assert!(export_bookmarks(
+		&mut new_easy(),
+		&bookmarks::lists(None),
+		Format::Json,
+		&mut &mut [][..],
+	)
+	.is_err());
+	assert_eq!(
+		String::from_utf8(json)
+			.expect("Export should be UTF-8")
+			.lines()
+			.count(),
+		1
+	);
+
+	// An empty list is kept, with a placeholder
+	bookmarks::remove("Watch later", 0);
+	assert_eq!(
+		nodes(bookmarks::list_widgets("Watch later").1),
+		[Node::None]
+	);
+
+	bookmarks::remove_list("Music");
+	assert_eq!(bookmarks::names(), ["Watch later"]);
+
+	let _ = remove_file(directory.join("bookmarks.json"));
+}
+
+#[test]
+fn local_playlists() {
+	let _globals = lock_globals();
+	let directory = data_directory();
+	let entry = |id: &str, title: &str| Entry {
+		id: id.to_owned(),
+		title: title.to_owned(),
+	};
+
+	assert_eq!(
+		playlists::add(
+			"Mix",
+			vec![
+				entry("aaaaaaaaaaa", "First"),
+				entry("bbbbbbbbbbb", "Second")
+			]
+		),
+		2
+	);
+	// Already in it
+	assert_eq!(
+		playlists::add("Mix", vec![entry("aaaaaaaaaaa", "First")]),
+		0
+	);
+	assert_eq!(
+		playlists::add("Mix", vec![entry("ccccccccccc", "Third")]),
+		1
+	);
+
+	// Move the third one up, it can't go past the top
+	assert_eq!(playlists::shift("Mix", 2, true), Some(1));
+	assert_eq!(playlists::shift("Mix", 0, true), None);
+
+	let (titles, info) = playlists::playlist_widgets("Mix");
+	assert_eq!(
+		render(List::new(titles), 8),
+		["3 videos", "", "1. First", "", "2. Third", "", "3. Second"]
+	);
+	let playlist = playlists::get("Mix").expect("It was just made");
+	let url = [
+		base_url(),
+		"/watch_videos?video_ids=aaaaaaaaaaa,ccccccccccc,bbbbbbbbbbb",
+	]
+	.concat();
+	assert_eq!(playlist.watch_videos_url(), url);
+	assert_eq!(
+		nodes(info)[..2],
+		[Node::Url(url.clone()), Node::PlaylistEntry(0)]
+	);
+
+	// Exported and imported again, with titles
+	let m3u = directory.join("mix.m3u");
+	std::fs::write(&m3u, playlist.m3u()).expect("Should be able to write the M3U file");
+	assert_eq!(
+		playlists::import(m3u.to_str().expect("Path should be UTF-8")),
+		Some(playlist.entries.clone())
+	);
+	// Only the IDs are in the URL
+	assert_eq!(
+		playlists::import(&url.replace(',', "%2C")).map(|entries| entries[1].title.clone()),
+		Some("ccccccccccc".to_owned())
+	);
+	assert_eq!(playlists::import("/not/a/file.m3u"), None);
+
+	let mut shuffled = playlist.entries.clone();
+	shuffle(&mut shuffled);
+	shuffled.sort_by(|a, b| a.id.cmp(&b.id));
+	assert_eq!(
+		shuffled,
+		[
+			entry("aaaaaaaaaaa", "First"),
+			entry("bbbbbbbbbbb", "Second"),
+			entry("ccccccccccc", "Third")
+		]
+	);
+
+	playlists::remove("Mix", 0);
+	assert_eq!(
+		playlists::get("Mix").map(|playlist| playlist.entries.len()),
+		Some(2)
+	);
+	playlists::remove_playlist("Mix");
+	assert!(playlists::names().is_empty());
+
+	let _ = remove_file(m3u);
+	let _ = remove_file(directory.join("playlists.json"));
+}
+
+#[test]
+fn resume_position() {
+	let _globals = lock_globals();
+	let directory = data_directory();
+
+	// As mpv writes it when it quits
+	let watch_later = resume::prepare();
+	std::fs::write(
+		watch_later.join("0123456789ABCDEF"),
+		"# https://example.com/video\nstart=123.456000\nvolume=80\n",
+	)
+	.expect("Should be able to write the watch later file");
+
+	resume::record("resumevid01", 600_000);
+	let position = resume::get("resumevid01").expect("The position should be kept");
+	assert_eq!(
+		position,
+		Position {
+			position: 123_456,
+			length: 600_000
+		}
+	);
+	assert_eq!(
+		widget_text(Paragraph::new(position.progress_bar()), 1),
+		"━━━━──────────────── 2:03 / 10:00"
+	);
+
+	// Too close to the start to bother
+	std::fs::write(watch_later.join("0123456789ABCDEF"), "start=5.000000\n")
+		.expect("Should be able to write the watch later file");
+	resume::record("resumevid02", 600_000);
+	assert_eq!(resume::get("resumevid02"), None);
+
+	// Watched to the end, so there's no file
+	resume::prepare();
+	resume::record("resumevid01", 600_000);
+	assert_eq!(resume::get("resumevid01"), None);
+
+	let _ = std::fs::remove_dir_all(watch_later);
+	let _ = remove_file(directory.join("positions.json"));
+}
+
+#[test]
+fn watched_videos() {
+	let _globals = lock_globals();
+	let directory = data_directory();
+	let render_page = |page: &Page| {
+		filter::clear();
+
+		let mut titles = Vec::new();
+		let mut info = Vec::new();
+		compact_video("watchedvid1", "Seen it", "Someone", "1:00")
+			.into_widgets(&mut titles, &mut info);
+		compact_video("watchedvid2", "New", "Someone", "1:00").into_widgets(&mut titles, &mut info);
+		Blocklist::default().apply(&page.identity(), &mut titles, &mut info);
+
+		widget_text(List::new(titles), 4)
+	};
+	// Both are "Recommendations", but for different videos
+	let next = |video_id: &str| Page::Next {
+		video_id: video_id.to_owned(),
+		continuation: None,
+		previous: previous(),
+	};
+
+	watched::mark("watchedvid1");
+	assert!(watched::is_watched("watchedvid1"));
+	assert!(directory.join("watched.json").exists());
+
+	// Dimmed, with a marker
+	assert_eq!(render_page(&next("watchedvid1")), "✓ Seen it\n\nNew");
+
+	// Only hidden on the page it was hidden on, not others with the same title
+	assert!(watched::toggle_hidden(next("watchedvid1").identity()));
+	assert_eq!(render_page(&next("watchedvid1")), "New");
+	assert_eq!(render_page(&next("watchedvid2")), "✓ Seen it\n\nNew");
+	assert_eq!(
+		render_page(&Page::Home { continuation: None }),
+		"✓ Seen it\n\nNew"
+	);
+	assert!(!watched::toggle_hidden(next("watchedvid1").identity()));
+	assert_eq!(render_page(&next("watchedvid1")), "✓ Seen it\n\nNew");
+
+	// Search results are dimmed too
+	let mut titles = Vec::new();
+	simd_json::from_slice::<SearchContinuationResponse>(
+		&mut br#"{"onResponseReceivedCommands":[{"appendContinuationItemsAction":{"continuationItems":[{"itemSectionRenderer":{"contents":[{"videoRenderer":{"videoId":"watchedvid1","title":{"runs":[{"text":"Seen it"}]},"ownerText":{"runs":[{"text":"Someone"}]},"shortViewCountText":{"accessibility":{"accessibilityData":{"label":"1 view"}}}}}]}}]}}]}"#.to_vec(),
+	)
+	.expect("Search JSON should be valid")
+	.into_widgets(&mut titles, &mut Vec::new());
+	assert_eq!(widget_text(List::new(titles), 2), "✓ Seen it");
+
+	let _ = remove_file(directory.join("watched.json"));
+}
+
+#[test]
+fn blocklist() {
+	let _globals = lock_globals();
+	let directory = data_directory();
+	let render_page = |blocklist: &Blocklist| {
+		filter::clear();
+
+		let mut titles = Vec::new();
+		let mut info = Vec::new();
+		compact_video("blockvideo1", "Full VOD", "Streamer", "3:00:00")
+			.into_widgets(&mut titles, &mut info);
+		compact_video("blockvideo2", "Clip", "Clipper", "0:30")
+			.into_widgets(&mut titles, &mut info);
+		compact_video("blockvideo3", "Essay", "Essayist", "45:00")
+			.into_widgets(&mut titles, &mut info);
+		blocklist.apply("Recommendations", &mut titles, &mut info);
+
+		widget_text(List::new(titles), 6)
+	};
+
+	// Regular expressions, ignoring case
+	let pattern = title_pattern("^live:|full vod$").expect("Pattern should be valid");
+	assert!(pattern.is_match("LIVE: something"));
+	assert!(pattern.is_match("Yesterday's full VOD"));
+	assert!(!pattern.is_match("Not live: something"));
+	let pattern =
+		title_pattern(r"^(live|premiere):\s+\d{1,2}h\b").expect("Pattern should be valid");
+	assert!(pattern.is_match("Premiere: 10h of rain"));
+	assert!(!pattern.is_match("Live: 100h of rain"));
+	// This would take forever backtracking
+	let pattern = title_pattern(&["a*".repeat(30), "b".to_owned()].concat())
+		.expect("Pattern should be valid");
+	assert!(!pattern.is_match(&"a".repeat(1000)));
+	assert!(pattern.is_match(&["a".repeat(1000), "b".to_owned()].concat()));
+	assert!(title_pattern("*oops").is_err());
+	assert!(title_pattern("[oops").is_err());
+
+	assert_eq!(
+		render_page(&Blocklist::default()),
+		"Full VOD\n\nClip\n\nEssay"
+	);
+	assert_eq!(
+		render_page(&Blocklist::parse(
+			"name streamer # by name\nmin-duration 1:00"
+		)),
+		"Essay"
+	);
+	assert_eq!(
+		render_page(&Blocklist::parse("# Titles\ntitle vod$|^cl\nnonsense")),
+		"Essay"
+	);
+	assert_eq!(
+		render_page(&Blocklist::parse(
+			"max-duration 1:00:00\nchannel UCEssayist"
+		)),
+		"Clip"
+	);
+	assert_eq!(
+		render_page(&Blocklist::parse("max-duration 1:00\nchannel UCClipper")),
+		"Nothing here"
+	);
+
+	// `!` blocks the selected video's channel
+	let (id, name) = filter::channel(&Node::Video("blockvideo3".to_owned()))
+		.expect("The video's channel should be known");
+	assert_eq!((id.as_str(), name.as_str()), ("UCEssayist", "Essayist"));
+	filter::block_channel(&id, &name);
+	assert_eq!(
+		std::fs::read_to_string(directory.join("blocklist")).expect("Blocklist should be written"),
+		"channel UCEssayist # Essayist\n"
+	);
+	assert_eq!(render_page(&Blocklist::load()), "Full VOD\n\nClip");
+
+	let _ = remove_file(directory.join("blocklist"));
+}
+
+#[test]
+fn hashtag() {
+	// As if it was typed in the search box, so the params have to be resolved
+	let mut page = Page::Hashtag {
+		tag: "rust".to_owned(),
+		params: None,
+		continuation: None,
+		previous: previous(),
+	};
+
+	let (first, continued) = browse(&mut page);
+
+	assert!(first.contains(&Node::Video("zF34dRivLOw".to_owned())));
+	assert!(first.contains(&Node::Short(
+		"shortvid001".to_owned(),
+		Some("SHORT_SEQUENCE".to_owned())
+	)));
+	assert_eq!(
+		continued,
+		[Node::Short(
+			"shortvid001".to_owned(),
+			Some("SHORT_SEQUENCE".to_owned())
+		)]
+	);
+	assert_eq!(continuation(&page), Some("HASHTAG_CONTINUATION_2"));
+}
+
+#[test]
+fn next() {
+	let mut page = Page::Next {
+		video_id: "zF34dRivLOw".to_owned(),
+		continuation: None,
+		previous: previous(),
+	};
+
+	let (first, continued) = browse(&mut page);
+
+	assert!(first.contains(&Node::CommentSection("COMMENTS_TOP".to_owned())));
+	assert!(first.contains(&Node::Transcript("TRANSCRIPT_PARAMS".to_owned())));
+	assert_eq!(continued, [Node::Video("compactvid1".to_owned())]);
+	assert_eq!(continuation(&page), Some("NEXT_CONTINUATION_2"));
+}
+
+#[test]
+fn transcript() {
+	let (first, continued) = browse(&mut Page::Transcript {
+		params: "TRANSCRIPT_PARAMS".to_owned(),
+		previous: previous(),
+	});
+
+	assert!(first.contains(&Node::Transcript("TRANSCRIPT_DE".to_owned())));
+	// Transcripts can't be continued
+	assert!(continued.is_empty());
+}
+
+#[test]
+fn comment_section() {
+	let mut page = Page::CommentSection {
+		first_continuation: "COMMENTS_TOP".to_owned(),
+		continuation: None,
+		endpoint: "next",
+		sorts: Vec::new(),
+		sort: 0,
+		filter: None,
+		tree: false,
+		threads: Vec::new(),
+		previous: previous(),
+	};
+
+	let (first, continued) = browse(&mut page);
+
+	assert!(first.contains(&Node::Comment("REPLIES_TOKEN".to_owned())));
+	assert!(!continued.is_empty());
+	assert_eq!(continuation(&page), Some("COMMENTS_CONTINUATION_2"));
+
+	let Page::CommentSection { sorts, sort, .. } = page else {
+		unreachable!()
+	};
+	assert_eq!(
+		sorts,
+		[
+			("Top comments".to_owned(), "COMMENTS_TOP".to_owned()),
+			("Newest first".to_owned(), "COMMENTS_NEWEST".to_owned())
+		]
+	);
+	assert_eq!(sort, 0);
+}
+
+#[test]
+fn comment_filter() {
+	let mut easy = session();
+
+	let mut page = Page::CommentSection {
+		first_continuation: "COMMENTS_TOP".to_owned(),
+		continuation: None,
+		endpoint: "next",
+		sorts: Vec::new(),
+		sort: 0,
+		filter: Some("someone".to_owned()),
+		tree: false,
+		threads: Vec::new(),
+		previous: previous(),
+	};
+
+	// Matches the author of a loaded comment
+	let (titles, _) = page.request(&mut easy);
+	assert_eq!(render(List::new(titles), 2), ["@someone"]);
+
+	// Matches a comment's text, which is only in the next comments
+	if let Page::CommentSection { filter, .. } = &mut page {
+		*filter = Some("love rust".to_owned());
+	}
+	let (titles, _) = page.request(&mut easy);
+	assert_eq!(render(List::new(titles), 2), ["@another"]);
+	assert_eq!(continuation(&page), Some("COMMENTS_CONTINUATION_2"));
+
+	// Stops when there are no more comments
+	if let Page::CommentSection { filter, .. } = &mut page {
+		*filter = Some("nothing like this".to_owned());
+	}
+	let (titles, _) = page.request(&mut easy);
+	assert_eq!(render(List::new(titles), 2), ["No matching comments"]);
+}
+
+#[test]
+fn comment_tree() {
+	let mut easy = session();
+
+	let mut page = Page::CommentSection {
+		first_continuation: "COMMENTS_TOP".to_owned(),
+		continuation: None,
+		endpoint: "next",
+		sorts: Vec::new(),
+		sort: 0,
+		filter: None,
+		tree: true,
+		threads: Vec::new(),
+		previous: previous(),
+	};
+	let (mut titles, mut info) = page.request(&mut easy);
+	let Page::CommentSection { threads, .. } = &mut page else {
+		unreachable!()
+	};
+	let length = info.len();
+	let index = info
+		.iter()
+		.position(|(_, node)| *node == Node::Comment("REPLIES_TOKEN".to_owned()))
+		.expect("A comment should have replies");
+
+	// Expanding adds the replies under the comment, ending with an item for more of them
+	toggle_replies(
+		&mut easy,
+		"next",
+		threads,
+		&None,
+		"REPLIES_TOKEN".to_owned(),
+		index,
+		&mut titles,
+		&mut info,
+	);
+	let added = info.len() - length;
+	let more = index + added;
+	assert_eq!(info[more].1, Node::Comment("MORE_REPLIES".to_owned()));
+	assert!(render(List::new(titles[index + 1..=index + 1].to_vec()), 2)[0].starts_with("    @"));
+
+	// Loading the last of them replaces that item
+	toggle_replies(
+		&mut easy,
+		"next",
+		threads,
+		&None,
+		"MORE_REPLIES".to_owned(),
+		more,
+		&mut titles,
+		&mut info,
+	);
+	assert_eq!(info.len(), length + added);
+	assert_ne!(info[more].1, Node::Comment("MORE_REPLIES".to_owned()));
+
+	// Collapsing removes all of them
+	toggle_replies(
+		&mut easy,
+		"next",
+		threads,
+		&None,
+		"REPLIES_TOKEN".to_owned(),
+		index,
+		&mut titles,
+		&mut info,
+	);
+	assert_eq!(info.len(), length);
+	assert!(threads.is_empty());
+
+	// With the page's filter, replies that don't match it aren't added, and if none of them do the
+	// item for more replies is just removed
+	toggle_replies(
+		&mut easy,
+		"next",
+		threads,
+		&None,
+		"REPLIES_TOKEN".to_owned(),
+		index,
+		&mut titles,
+		&mut info,
+	);
+	toggle_replies(
+		&mut easy,
+		"next",
+		threads,
+		&Some("nothing has this in it".to_owned()),
+		"MORE_REPLIES".to_owned(),
+		more,
+		&mut titles,
+		&mut info,
+	);
+	assert_eq!(info.len(), length + added - 1);
+	assert!(!info
+		.iter()
+		.any(|(_, node)| *node == Node::Comment("MORE_REPLIES".to_owned())));
+	toggle_replies(
+		&mut easy,
+		"next",
+		threads,
+		&None,
+		"REPLIES_TOKEN".to_owned(),
+		index,
+		&mut titles,
+		&mut info,
+	);
+	assert_eq!(info.len(), length);
+}
+
+#[test]
+fn comment_export() {
+	let mut easy = session();
+
+	// Just the first page
+	let mut output = Vec::new();
+	let count = export_comments(
+		&mut easy,
+		"next",
+		"COMMENTS_TOP".to_owned(),
+		false,
+		false,
+		Format::Json,
+		&mut output,
+		&mut |_, _| (),
+	)
+	.expect("Export should succeed");
+	let output = String::from_utf8(output).expect("Export should be UTF-8");
+	assert_eq!(count, 2);
+	assert_eq!(output.lines().count(), 2);
+	assert!(output.starts_with(
+		"{\"id\":\"Ugfireship1\",\"parent\":null,\"author\":\"@fireship\",\"channel_id\":\"\
+		 UCfireship\",\"text\":\"First! \
+		 Pinned\",\"likes\":\"1.2K\",\"replies\":12,\"pinned\":true,\"hearted\":true,\"published\"\
+		 :\"1 day ago\"}\n"
+	));
+
+	// Every page, with the replies after their comments
+	let mut output = Vec::new();
+	let mut pages = Vec::new();
+	let count = export_comments(
+		&mut easy,
+		"next",
+		"COMMENTS_TOP".to_owned(),
+		false,
+		true,
+		Format::Csv,
+		&mut output,
+		&mut |count, token| pages.push((count, token.map(str::to_owned))),
+	)
+	.expect("Export should succeed");
+	let output = String::from_utf8(output).expect("Export should be UTF-8");
+	let ids = output
+		.lines()
+		.map(|line| line.split(',').next().unwrap_or_default())
+		.collect::<Vec<&str>>();
+	assert_eq!(count, ids.len() - 1);
+	assert_eq!(
+		ids[..4],
+		["id", "Ugfireship1", "Ugreplier6", "Uglatecomer7"]
+	);
+	assert_eq!(ids.last(), Some(&"Uglast8"));
+	assert!(output.contains("\nUgreplier6,Ugfireship1,@replier,"));
+	// Progress after each page, with the token to resume from
+	assert_eq!(pages.last(), Some(&(count, None)));
+	assert_eq!(
+		pages[pages.len() - 2].1.as_deref(),
+		Some("COMMENTS_CONTINUATION_2")
+	);
+
+	// Resuming continues from a later page, without the header
+	let mut output = Vec::new();
+	export_comments(
+		&mut easy,
+		"next",
+		"COMMENTS_CONTINUATION_2".to_owned(),
+		true,
+		true,
+		Format::Csv,
+		&mut output,
+		&mut |_, _| (),
+	)
+	.expect("Export should succeed");
+	assert_eq!(
+		String::from_utf8(output).expect("Export should be UTF-8"),
+		"Uglast8,,@last,UClast,The last comment,0,0,false,false,1 day ago\n"
+	);
+
+	// Rate limited once, then tried again
+	let mut output = Vec::new();
+	assert_eq!(
+		export_comments(
+			&mut easy,
+			"next",
+			"COMMENTS_RATE_LIMITED".to_owned(),
+			false,
+			false,
+			Format::Json,
+			&mut output,
+			&mut |_, _| (),
+		),
+		Ok(2)
+	);
+
+	// Errors that won't go away aren't tried again, and a comment section's first page can't be
+	// resumed from
+	assert_eq!(
+		export_comments(
+			&mut easy,
+			"missing",
+			"COMMENTS_TOP".to_owned(),
+			false,
+			false,
+			Format::Json,
+			&mut Vec::new(),
+			&mut |_, _| (),
+		),
+		Err("Youtube responded with status 404".to_owned())
+	);
+	assert_eq!(
+		export_comments(
+			&mut easy,
+			"missing",
+			"COMMENTS_CONTINUATION".to_owned(),
+			true,
+			false,
+			Format::Json,
+			&mut Vec::new(),
+			&mut |_, _| (),
+		),
+		Err("Youtube responded with status 404, resume from COMMENTS_CONTINUATION".to_owned())
+	);
+}
+
+#[test]
+fn comment() {
+	let mut page = Page::Comment {
+		first_continuation: "REPLIES_TOKEN".to_owned(),
+		continuation: None,
+		endpoint: "next",
+		filter: None,
+		previous: previous(),
+	};
+
+	let (first, continued) = browse(&mut page);
+
+	assert!(!first.is_empty());
+	assert_eq!(first, continued);
+}
+
+#[test]
+fn community() {
+	let mut page = Page::Community {
+		browse_id: "UCrust".to_owned(),
+		continuation: None,
+		previous: previous(),
+	};
+
+	let (first, continued) = browse(&mut page);
+
+	assert!(first.contains(&Node::Post("POST_text".to_owned())));
+	assert!(first.contains(&Node::Video("zF34dRivLOw".to_owned())));
+	assert_eq!(continued, [Node::Post("POST_later".to_owned())]);
+}
+
+#[test]
+fn post_comments() {
+	let mut easy = session();
+
+	// The post's page has the token for its comments, which are requested with `browse`
+	let (_, _, first_continuation) = simd_json::from_slice::<CommunityResponse>(&mut request_post(
+		&mut easy,
+		&api_url("browse"),
+		&BrowseRequest {
+			browseId: Some("FEpost_detail".to_owned()),
+			params: Some("POST_text".to_owned()),
+			..BrowseRequest::default()
+		},
+	))
+	.expect("Post response should be valid")
+	.into_widgets();
+
+	let (_, info) = Page::CommentSection {
+		first_continuation: first_continuation.expect("Post should have comments"),
+		continuation: None,
+		endpoint: "browse",
+		sorts: Vec::new(),
+		sort: 0,
+		filter: None,
+		tree: false,
+		threads: Vec::new(),
+		previous: previous(),
+	}
+	.request(&mut easy);
+
+	assert!(nodes(info).contains(&Node::Comment("REPLIES_TOKEN".to_owned())));
+}
+
+#[test]
+fn shorts() {
+	// Shorts from search results don't have the sequence params, so they're requested first
+	let mut page = Page::Shorts {
+		video_id: "shortvideo1".to_owned(),
+		title: Some("A short".to_owned()),
+		sequence_params: None,
+		continuation: None,
+		previous: previous(),
+	};
+
+	// The first short has the title it was opened with
+	let (list, _) = page.request(&mut session());
+	assert_eq!(widget_text(List::new(list), 2), "A short");
+
+	let (first, continued) = browse(&mut page);
+
+	assert_eq!(
+		first,
+		["shortvideo1", "shortvideo2", "shortvideo3"]
+			.map(|video_id| Node::Short(video_id.to_owned(), None))
+	);
+	assert_eq!(continued.len(), 2);
+	assert!(matches!(
+		&page,
+		Page::Shorts { sequence_params: Some(sequence_params), .. } if sequence_params == "SHORTS_SEQUENCE"
+	));
+	assert_eq!(continuation(&page), Some("SHORTS_CONTINUATION"));
+}
+
+#[test]
+fn live_chat() {
+	let mut page = Page::LiveChat {
+		first_continuation: "LIVE_CHAT_RELOAD".to_owned(),
+		continuation: None,
+		timeout: Duration::ZERO,
+		previous: previous(),
+	};
+
+	let mut easy = session();
+	let (mut list, mut info) = page.request(&mut easy);
+
+	assert!(!info.is_empty());
+	assert_eq!(continuation(&page), Some("LIVE_CHAT_CONTINUATION"));
+	assert!(matches!(page, Page::LiveChat { timeout, .. } if timeout == Duration::from_secs(2)));
+
+	let length = list.len();
+	page.r#continue(&mut easy, &mut list, &mut info);
+
+	// The stream ended
+	assert_eq!(info.len(), length);
+	assert_eq!(continuation(&page), None);
+}
+
+#[test]
+fn chat_replay() {
+	let mut page = Page::ChatReplay {
+		video_id: "zF34dRivLOw".to_owned(),
+		first_continuation: "REPLAY_RELOAD".to_owned(),
+		seek_continuation: None,
+		offset: 0,
+		continuation: None,
+		player: None,
+		previous: previous(),
+	};
+
+	let (first, continued) = browse(&mut page);
+
+	assert_eq!(
+		first,
+		[1000, 5000, 3725000].map(Node::ReplayMessage),
+		"Only chat items should be shown"
+	);
+	assert_eq!(continued, [Node::ReplayMessage(3800000)]);
+	assert_eq!(continuation(&page), None);
+
+	// Seeking uses the token from the first response
+	if let Page::ChatReplay { offset, .. } = &mut page {
+		*offset = 3800000;
+	}
+
+	let (_, info) = page.request(&mut session());
+
+	assert_eq!(nodes(info), [Node::ReplayMessage(3800000)]);
+}
+
+#[test]
+fn links() {
+	let links = vec![
+		(
+			"1:23".to_owned(),
+			Node::Timestamp("zF34dRivLOw".to_owned(), 83_000),
+		),
+		("#rust".to_owned(), Node::Hashtag("rust".to_owned(), None)),
+	];
+
+	let (titles, info) = Page::Links {
+		links: links.clone(),
+		previous: previous(),
+	}
+	.request(&mut new_easy());
+
+	assert_eq!(titles.len(), 2);
+	assert_eq!(
+		nodes(info),
+		links
+			.into_iter()
+			.map(|(_, node)| node)
+			.collect::<Vec<Node>>()
+	);
+}
+
+#[test]
+fn playback() {
+	let mut easy = session();
+
+	// The first video is played from the watch page, which links to the player
+	let mut response = unsafe {
+		String::from_utf8_unchecked(request_get(
+			&mut easy,
+			&[base_url(), "/watch/", "zF34dRivLOw"].concat(),
+		))
+	};
+
+	let mut decipherer = Decipherer::from_watch_page(&mut easy, &response);
+
+	assert_eq!(decipherer.player_version, "mockplayer");
+
+	let expected = Playback::Video {
+		// Closest to 720p, with `n` solved
+		video_url: "https://video.example/videoplayback?id=1&n=fedcba&other=1".to_owned(),
+		// Deciphered signature appended
+		audio_url: "https://audio.example/videoplayback?id=3&n=abcdef&sig=xyz".to_owned(),
+		subtitle_url: None,
+	};
+
+	let playback = extract_json::<VideoResponse>(&mut response, "{\"re", "};", 1)
+		.expect("Watch page should have the player response")
+		.playback(&mut decipherer);
+
+	assert!(playback
+		.as_ref()
+		.is_ok_and(|playback| *playback == expected));
+
+	// Later ones use the player endpoint
+	let playback = simd_json::from_slice::<VideoResponse>(&mut request_post(
+		&mut easy,
+		&api_url("player"),
+		&BrowseRequest {
+			videoId: Some("zF34dRivLOw".to_owned()),
+			..BrowseRequest::default()
+		},
+	))
+	.expect("Player response should be valid")
+	.playback(&mut decipherer);
+
+	assert!(playback.is_ok_and(|playback| playback == expected));
+}
+
+#[test]
+fn inspector() {
+	let mut easy = session();
+
+	Page::Search {
+		query: "inspected".to_owned(),
+		params: None,
+		filters: SearchFilters::default(),
+		continuation: None,
+		previous: previous(),
+	}
+	.request(&mut easy);
+
+	let (titles, info) = Page::Inspector {
+		request: inspector::page_request(),
+		previous: previous(),
+	}
+	.request(&mut easy);
+
+	assert_eq!(titles.len(), info.len());
+	let rendered = info
+		.iter()
+		.map(|(paragraph, node)| (render(paragraph.clone(), 40).concat(), node))
+		.collect::<Vec<_>>();
+	// Other tests run at the same time, so the search may not be the last request, but it's the
+	// one the page was made from
+	assert_eq!(rendered[0].0, "Not saved yet");
+	assert!(rendered
+		.iter()
+		.skip(1)
+		.any(|(text, node)| **node == info[0].1 && text.contains("\"query\":\"inspected\"")));
+
+	// Local pages aren't made from a request
+	Page::SearchHistory {
+		previous: previous(),
+	}
+	.request(&mut easy);
+	assert_eq!(inspector::page_request(), None);
+
+	// Huge responses (i.e. the player) aren't kept
+	inspector::record(Record {
+		id: 0,
+		url: "https://example.com/huge".to_owned(),
+		body: None,
+		status: 200,
+		error: None,
+		latency: Duration::ZERO,
+		size: REQUEST_LOG_MAX_RESPONSE + 1,
+		response: vec![b' '; REQUEST_LOG_MAX_RESPONSE + 1],
+		saved_to: None,
+	});
+	let id = inspector::last_id().expect("The request should be recorded");
+	let (_, info) = Page::Inspector {
+		request: Some(id),
+		previous: previous(),
+	}
+	.request(&mut easy);
+	assert!(info.iter().any(|(paragraph, node)| {
+		*node == Node::Request(id)
+			&& render(paragraph.clone(), 40)
+				.concat()
+				.contains("Too big to keep")
+	}));
+
+	// Saved responses go in the data directory, not the shared temp directory
+	inspector::record(Record {
+		id: 0,
+		url: "https://example.com/small".to_owned(),
+		body: None,
+		status: 200,
+		error: None,
+		latency: Duration::ZERO,
+		size: 5,
+		response: b"small".to_vec(),
+		saved_to: None,
+	});
+	let id = inspector::last_id().expect("The request should be recorded");
+	inspector::save(id);
+	let path = data_directory().join(format!("response-{}-{id}.html", std::process::id()));
+	assert_eq!(
+		std::fs::read(&path).expect("The response should be saved"),
+		b"small"
+	);
+	// With its full path shown
+	let (_, info) = Page::Inspector {
+		request: None,
+		previous: previous(),
+	}
+	.request(&mut easy);
+	assert!(info.iter().any(|(paragraph, node)| {
+		*node == Node::Request(id)
+			&& render(paragraph.clone(), 40)
+				.concat()
+				.contains(&path.display().to_string())
+	}));
+	let _ = remove_file(path);
+}
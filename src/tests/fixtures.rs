@@ -0,0 +1,374 @@
+//! Regression tests for the response parsers. Each fixture in `tests/fixtures/<parser>/` is parsed
+//! and rendered as text, which is compared to the `.snap` file next to it. Run with
+//! `UPDATE_SNAPSHOTS=1` to write missing snapshots and overwrite existing ones.
+
+use std::env::var_os;
+use std::fmt::Write;
+use std::fs::{read, read_dir, read_to_string, write};
+use std::path::Path;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::widgets::{List, ListItem, Paragraph, Widget};
+use serde::Deserialize;
+use simd_json::from_slice;
+
+use super::data_directory;
+use crate::structs::*;
+use crate::utils::{debug_log_path, extract_json};
+
+/// Width to render snapshots at
+const WIDTH: u16 = 80;
+/// Maximum height of the info panel in snapshots
+const INFO_HEIGHT: u16 = 40;
+
+/// Renders a widget into lines of text, without trailing whitespace
+pub fn render(widget: impl Widget, height: u16) -> Vec<String> {
+	let area = Rect {
+		x: 0,
+		y: 0,
+		width: WIDTH,
+		height,
+	};
+	let mut buffer = Buffer::empty(area);
+
+	widget.render(area, &mut buffer);
+
+	let mut lines = (0..height)
+		.map(|y| {
+			(0..WIDTH)
+				.map(|x| buffer.get(x, y).symbol.as_str())
+				.collect::<String>()
+				.trim_end()
+				.to_owned()
+		})
+		.collect::<Vec<String>>();
+
+	// Remove empty lines at the end
+	while lines.last().is_some_and(String::is_empty) {
+		lines.pop();
+	}
+
+	lines
+}
+
+/// Renders the output of a parser as text
+fn snapshot(
+	titles: Vec<ListItem>,
+	info: Vec<(Paragraph, Node)>,
+	continuation: Option<String>,
+) -> String {
+	assert_eq!(
+		titles.len(),
+		info.len(),
+		"There should be one info panel per title"
+	);
+
+	let mut snapshot = String::new();
+
+	for (title, (paragraph, node)) in titles.into_iter().zip(info) {
+		let height = title.height() as u16;
+
+		for line in render(List::new(vec![title]), height) {
+			let _ = writeln!(snapshot, "{line}");
+		}
+
+		let _ = writeln!(snapshot, "  -> {node:?}");
+
+		for line in render(paragraph, INFO_HEIGHT) {
+			let _ = writeln!(snapshot, "{}", ["  | ", &line].concat().trim_end());
+		}
+	}
+
+	let _ = writeln!(snapshot, "Continuation: {continuation:?}");
+
+	// The log's path depends on the machine
+	snapshot.replace(&debug_log_path().display().to_string(), "<debug log>")
+}
+
+/// Parses a response that implements `IntoWidgets` and renders it
+fn continued<'a, T: Deserialize<'a> + IntoWidgets>(response: &'a mut [u8]) -> String {
+	let mut titles = Vec::new();
+	let mut info = Vec::new();
+
+	let continuation = from_slice::<T>(response)
+		.expect("Fixture should be valid")
+		.into_widgets(&mut titles, &mut info);
+
+	snapshot(titles, info, continuation)
+}
+
+/// Parses the given response with a parser (named the same as in `captured`), and renders it
+fn parse(parser: &str, mut response: Vec<u8>) -> String {
+	match parser {
+		"home" => {
+			let (titles, info, continuation) = extract_json::<RichGridRenderer>(
+				&mut String::from_utf8(response).expect("Fixture should be UTF-8"),
+				"{\"cont",
+				"1}}",
+				0,
+			)
+			.expect("Fixture should contain the home page JSON")
+			.into_widgets();
+
+			snapshot(titles, info, continuation)
+		}
+		"browse_continuation" => {
+			continued::<ContinuationResponse<RichGridRendererContent>>(&mut response)
+		}
+		"general" => {
+			let (titles, info, continuation) = from_slice::<GeneralResponse>(&mut response)
+				.expect("Fixture should be valid")
+				.into_widgets();
+
+			snapshot(titles, info, continuation)
+		}
+		"search" => {
+			let (titles, info, continuation) = from_slice::<SearchResponse>(&mut response)
+				.expect("Fixture should be valid")
+				.into_widgets();
+
+			snapshot(titles, info, continuation)
+		}
+		"search_continuation" => {
+			let mut titles = Vec::new();
+			let mut info = Vec::new();
+
+			let continuation = from_slice::<SearchContinuationResponse>(&mut response)
+				.expect("Fixture should be valid")
+				.into_widgets(&mut titles, &mut info);
+
+			snapshot(titles, info, continuation)
+		}
+		"hashtag" => {
+			let (titles, info, continuation) = from_slice::<HashtagResponse>(&mut response)
+				.expect("Fixture should be valid")
+				.into_widgets();
+
+			snapshot(titles, info, continuation)
+		}
+		"hashtag_continuation" => continued::<ContinuationResponse<HashtagItem>>(&mut response),
+		"next" => {
+			let (titles, info, continuation) = from_slice::<NextResponse>(&mut response)
+				.expect("Fixture should be valid")
+				.into_widgets();
+
+			snapshot(titles, info, continuation)
+		}
+		"next_continuation" => {
+			continued::<ContinuationResponse<SecondaryResultsResult>>(&mut response)
+		}
+		"transcript" => {
+			let (titles, info) = from_slice::<TranscriptResponse>(&mut response)
+				.expect("Fixture should be valid")
+				.into_widgets();
+
+			snapshot(titles, info, None)
+		}
+		"comments" => continued::<CommentsResponse>(&mut response),
+		"comments_continuation" => {
+			continued::<ContinuationResponse<ContinuationItem>>(&mut response)
+		}
+		"replies" => continued::<ContinuationResponse<Comment>>(&mut response),
+		"reel_item_watch" => format!(
+			"Sequence params: {:?}\n",
+			from_slice::<ReelItemWatchResponse>(&mut response)
+				.expect("Fixture should be valid")
+				.sequence_params()
+		),
+		"reel_watch_sequence" => continued::<ReelWatchSequenceResponse>(&mut response),
+		"community" => {
+			let (titles, info, continuation) = from_slice::<CommunityResponse>(&mut response)
+				.expect("Fixture should be valid")
+				.into_widgets();
+
+			snapshot(titles, info, continuation)
+		}
+		"community_continuation" => continued::<ContinuationResponse<CommunityItem>>(&mut response),
+		"live_chat_replay" => {
+			let mut titles = Vec::new();
+			let mut info = Vec::new();
+
+			let (continuation, seek_continuation) =
+				from_slice::<LiveChatReplayResponse>(&mut response)
+					.expect("Fixture should be valid")
+					.into_widgets(&mut titles, &mut info);
+
+			snapshot(
+				titles,
+				info,
+				continuation
+					.map(|continuation| format!("{continuation} (seek: {seek_continuation:?})")),
+			)
+		}
+		"live_chat" => {
+			let mut titles = Vec::new();
+			let mut info = Vec::new();
+
+			let continuation = from_slice::<LiveChatResponse>(&mut response)
+				.expect("Fixture should be valid")
+				.into_widgets(&mut titles, &mut info)
+				.map(|(continuation, timeout)| format!("{continuation} ({timeout:?})"));
+
+			snapshot(titles, info, continuation)
+		}
+		"resolve_url" => format!(
+			"Params: {:?}\n",
+			from_slice::<ResolveUrlResponse>(&mut response)
+				.expect("Fixture should be valid")
+				.params()
+		),
+		_ => panic!("Unknown parser `{parser}`"),
+	}
+}
+
+/// Checks every fixture for the given parser against its snapshot
+fn check(parser: &str) {
+	let directory = Path::new(env!("CARGO_MANIFEST_DIR"))
+		.join("tests/fixtures")
+		.join(parser);
+
+	let update = var_os("UPDATE_SNAPSHOTS").is_some();
+
+	// Watched videos and resume positions change what's rendered, so the real ones aren't used
+	data_directory();
+
+	let entries = match read_dir(&directory) {
+		Ok(entries) => entries,
+		// Nothing to update
+		Err(_) if update => return,
+		Err(error) => panic!("{} should have fixtures: {error}", directory.display()),
+	};
+
+	for entry in entries {
+		let path = entry.expect("Should be able to read fixtures").path();
+
+		if path
+			.extension()
+			.is_some_and(|extension| extension == "snap")
+		{
+			continue;
+		}
+
+		let rendered = parse(parser, read(&path).expect("Should be able to read fixture"));
+
+		let snapshot_path = path.with_extension("snap");
+
+		if update {
+			write(&snapshot_path, rendered).expect("Should be able to write snapshot");
+		} else {
+			let expected = read_to_string(&snapshot_path).unwrap_or_else(|_| {
+				panic!(
+					"{} has no snapshot, run with `UPDATE_SNAPSHOTS=1` to write it",
+					path.display()
+				)
+			});
+
+			assert!(
+				expected == rendered,
+				"{} doesn't match its snapshot, got:\n{rendered}",
+				path.display()
+			);
+		}
+	}
+}
+
+#[test]
+fn home() {
+	check("home");
+}
+
+#[test]
+fn browse_continuation() {
+	check("browse_continuation");
+}
+
+#[test]
+fn general() {
+	check("general");
+}
+
+#[test]
+fn search() {
+	check("search");
+}
+
+#[test]
+fn search_continuation() {
+	check("search_continuation");
+}
+
+#[test]
+fn hashtag() {
+	check("hashtag");
+}
+
+#[test]
+fn hashtag_continuation() {
+	check("hashtag_continuation");
+}
+
+#[test]
+fn resolve_url() {
+	check("resolve_url");
+}
+
+#[test]
+fn next() {
+	check("next");
+}
+
+#[test]
+fn next_continuation() {
+	check("next_continuation");
+}
+
+#[test]
+fn transcript() {
+	check("transcript");
+}
+
+#[test]
+fn comments() {
+	check("comments");
+}
+
+#[test]
+fn comments_continuation() {
+	check("comments_continuation");
+}
+
+#[test]
+fn replies() {
+	check("replies");
+}
+
+#[test]
+fn reel_item_watch() {
+	check("reel_item_watch");
+}
+
+#[test]
+fn reel_watch_sequence() {
+	check("reel_watch_sequence");
+}
+
+#[test]
+fn live_chat() {
+	check("live_chat");
+}
+
+#[test]
+fn live_chat_replay() {
+	check("live_chat_replay");
+}
+
+#[test]
+fn community() {
+	check("community");
+}
+
+#[test]
+fn community_continuation() {
+	check("community_continuation");
+}
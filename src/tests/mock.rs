@@ -0,0 +1,228 @@
+//! A local mock of the parts of youtube the program uses, serving the fixtures in `tests/fixtures`.
+//! It's started once, on a random port, and `BASE_URL_OVERRIDE` is pointed at it.
+
+use std::fs::read;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::thread;
+
+use crate::structs::COMMUNITY_TAB_PARAMS;
+use crate::utils::BASE_URL_OVERRIDE;
+
+/// Path of the player JS that watch pages link to, the version is `mockplayer`
+const PLAYER_PATH: &str = "/s/player/mockplayer/player_ias.vflset/en_US/base.js";
+
+/// Reads `tests/fixtures/<path>`
+fn fixture(path: &str) -> Vec<u8> {
+	read(
+		Path::new(env!("CARGO_MANIFEST_DIR"))
+			.join("tests/fixtures")
+			.join(path),
+	)
+	.unwrap_or_else(|_| panic!("Fixture `{path}` should exist"))
+}
+
+/// Picks the response for a request, like youtube would
+fn respond(path: &str, body: &str) -> (&'static str, Vec<u8>) {
+	// Requests are told apart by what's in their body, so the tokens in the fixtures matter
+	let json = |path| ("application/json", fixture(path));
+
+	match path.split('?').next().unwrap_or(path) {
+		"/" => ("text/html", fixture("home/basic.html")),
+		PLAYER_PATH => ("text/javascript", fixture("mock/base.js")),
+		watch if watch.starts_with("/watch/") => (
+			"text/html",
+			[
+				&b"<html><head><script src=\""[..],
+				PLAYER_PATH.as_bytes(),
+				b"\"></script></head><body><script>var ytInitialPlayerResponse = ",
+				fixture("mock/player.json").trim_ascii_end(),
+				b";</script></body></html>",
+			]
+			.concat(),
+		),
+		"/youtubei/v1/browse" if body.contains("FEpost_detail") => json("community/post.json"),
+		"/youtubei/v1/browse" if body.contains(COMMUNITY_TAB_PARAMS) => {
+			json("community/basic.json")
+		}
+		"/youtubei/v1/browse" if body.contains("COMMUNITY_CONTINUATION") => {
+			json("community_continuation/basic.json")
+		}
+		"/youtubei/v1/browse" if body.contains("POST_COMMENTS") => json("comments/basic.json"),
+		// Only with the params from resolving the hashtag's URL
+		"/youtubei/v1/browse" if body.contains("HASHTAG_PARAMS") => json("hashtag/basic.json"),
+		"/youtubei/v1/browse" if body.contains("HASHTAG_CONTINUATION") => {
+			json("hashtag_continuation/basic.json")
+		}
+		// Every tab of trending is the same
+		"/youtubei/v1/browse" if body.contains("FEtrending") => json("general/trending.json"),
+		"/youtubei/v1/browse" if body.contains("\"browseId\":\"") => json("general/basic.json"),
+		"/youtubei/v1/browse" => json("browse_continuation/basic.json"),
+		"/youtubei/v1/navigation/resolve_url" if body.contains("/hashtag/rust") => {
+			json("resolve_url/basic.json")
+		}
+		"/youtubei/v1/search" if body.contains("\"query\":\"") => json("search/basic.json"),
+		"/youtubei/v1/search" => json("search_continuation/basic.json"),
+		"/youtubei/v1/next" => json(if body.contains("\"videoId\":\"") {
+			"next/basic.json"
+		} else if body.contains("COMMENTS_TOP")
+			|| body.contains("COMMENTS_NEWEST")
+			|| body.contains("COMMENTS_RATE_LIMITED")
+		{
+			"comments/basic.json"
+		} else if body.contains("REPLIES_TOKEN") {
+			"replies/basic.json"
+		} else if body.contains("MORE_REPLIES") {
+			"replies/end.json"
+		} else if body.contains("COMMENTS_CONTINUATION_2") {
+			"comments_continuation/end.json"
+		} else if body.contains("COMMENTS_CONTINUATION") {
+			"comments_continuation/basic.json"
+		} else {
+			"next_continuation/basic.json"
+		}),
+		"/youtubei/v1/get_transcript" => json("transcript/basic.json"),
+		"/complete/search" => json("mock/suggestions.json"),
+		"/youtubei/v1/player" => json("mock/player.json"),
+		"/youtubei/v1/reel/reel_item_watch" => json("reel_item_watch/basic.json"),
+		"/youtubei/v1/reel/reel_watch_sequence" => json("reel_watch_sequence/basic.json"),
+		// Chat ends after the first poll
+		"/youtubei/v1/live_chat/get_live_chat_replay" if body.contains("REPLAY_RELOAD") => {
+			json("live_chat_replay/basic.json")
+		}
+		// Continuing and seeking both get the end
+		"/youtubei/v1/live_chat/get_live_chat_replay"
+			if body.contains("REPLAY_CONTINUATION") || body.contains("REPLAY_SEEK") =>
+		{
+			json("live_chat_replay/end.json")
+		}
+		"/youtubei/v1/live_chat/get_live_chat" => json(if body.contains("LIVE_CHAT_RELOAD") {
+			"live_chat/basic.json"
+		} else {
+			"live_chat/ended.json"
+		}),
+		_ => ("text/plain", Vec::new()),
+	}
+}
+
+/// Reads a request body sent with `Transfer-Encoding: chunked`
+fn read_chunked(reader: &mut impl BufRead) -> Option<Vec<u8>> {
+	let mut body = Vec::new();
+
+	loop {
+		let mut line = String::new();
+		reader.read_line(&mut line).ok()?;
+
+		let size = usize::from_str_radix(line.trim_end(), 16).ok()?;
+
+		let start = body.len();
+		body.resize(start + size, 0);
+		reader.read_exact(&mut body[start..]).ok()?;
+
+		// Skip the CRLF after the chunk
+		reader.read_line(&mut line).ok()?;
+
+		if size == 0 {
+			return Some(body);
+		}
+	}
+}
+
+/// Answers requests on a connection until it's closed
+fn handle(stream: TcpStream) -> Option<()> {
+	let mut writer = stream.try_clone().ok()?;
+	let mut reader = BufReader::new(stream);
+
+	loop {
+		let mut request_line = String::new();
+		if reader.read_line(&mut request_line).ok()? == 0 {
+			// Closed
+			return Some(());
+		}
+
+		let path = request_line.split(' ').nth(1)?.to_owned();
+
+		let mut content_length = 0;
+		let mut chunked = false;
+
+		// Headers
+		loop {
+			let mut header = String::new();
+			reader.read_line(&mut header).ok()?;
+
+			let header = header.trim_end().to_ascii_lowercase();
+
+			if header.is_empty() {
+				break;
+			} else if let Some(length) = header.strip_prefix("content-length:") {
+				content_length = length.trim().parse().ok()?;
+			} else if header == "transfer-encoding: chunked" {
+				chunked = true;
+			} else if header == "expect: 100-continue" {
+				writer.write_all(b"HTTP/1.1 100 Continue\r\n\r\n").ok()?;
+			}
+		}
+
+		let body = if chunked {
+			read_chunked(&mut reader)?
+		} else {
+			let mut body = vec![0; content_length];
+			reader.read_exact(&mut body).ok()?;
+			body
+		};
+
+		let body = String::from_utf8_lossy(&body);
+		let (content_type, response) = respond(&path, &body);
+
+		// Only the first request for this is rate limited, so it works when it's tried again
+		static RATE_LIMITED: AtomicBool = AtomicBool::new(false);
+		let rate_limited =
+			body.contains("COMMENTS_RATE_LIMITED") && !RATE_LIMITED.swap(true, Ordering::Relaxed);
+
+		writer
+			.write_all(
+				format!(
+					"HTTP/1.1 {}\r\nContent-Type: {content_type}\r\nContent-Length: \
+					 {}\r\nSet-Cookie: __Secure-YEC=mockvisitor; Path=/; Secure\r\n\r\n",
+					if rate_limited {
+						"429 Too Many Requests"
+					} else if response.is_empty() {
+						"404 Not Found"
+					} else {
+						"200 OK"
+					},
+					response.len()
+				)
+				.as_bytes(),
+			)
+			.ok()?;
+		writer.write_all(&response).ok()?;
+	}
+}
+
+/// Starts the mock server if it isn't running yet, and sends all requests to it
+pub fn start() {
+	static STARTED: OnceLock<()> = OnceLock::new();
+
+	STARTED.get_or_init(|| {
+		let listener =
+			TcpListener::bind("127.0.0.1:0").expect("Should be able to start the mock server");
+
+		let _ = BASE_URL_OVERRIDE.set(format!(
+			"http://localhost:{}",
+			listener
+				.local_addr()
+				.expect("Mock server should have an address")
+				.port()
+		));
+
+		thread::spawn(move || {
+			for stream in listener.incoming().flatten() {
+				thread::spawn(move || handle(stream));
+			}
+		});
+	});
+}
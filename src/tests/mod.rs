@@ -0,0 +1,39 @@
+//! Tests, using the responses in `tests/fixtures`. New fixtures can be recorded with `ytui
+//! capture`.
+
+mod e2e;
+mod fixtures;
+mod mock;
+
+use std::env::temp_dir;
+use std::fs::remove_dir_all;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::utils::{DATA_DIRECTORY_OVERRIDE, LOG_FILE};
+
+/// Keeps what the program remembers between runs out of the real data directory, tests remove
+/// their own files. It starts empty, in case an earlier run with the same process ID left some.
+fn data_directory() -> PathBuf {
+	DATA_DIRECTORY_OVERRIDE
+		.get_or_init(|| {
+			let directory = temp_dir().join(format!("ytui-test-{}", std::process::id()));
+			let _ = remove_dir_all(&directory);
+
+			// The log would be in the directory, but its path is shown in the snapshots of
+			// unsupported renderers, which should wrap the same way whatever the process ID is
+			let _ = LOG_FILE.set(temp_dir().join("ytui-test.log"));
+
+			directory
+		})
+		.clone()
+}
+
+/// Held by tests that change what the program keeps globally (the files in the data directory, the
+/// locale), since tests run at the same time
+fn lock_globals() -> MutexGuard<'static, ()> {
+	static GLOBALS: Mutex<()> = Mutex::new(());
+
+	// A test that failed while holding it doesn't matter to the others
+	GLOBALS.lock().unwrap_or_else(|error| error.into_inner())
+}
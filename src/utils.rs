@@ -1,20 +1,227 @@
 //! Useful functions that are used in multiple files in the program
 
-use std::io::Read;
+use std::collections::hash_map::RandomState;
+use std::fs::{create_dir_all, write, OpenOptions};
+use std::hash::BuildHasher;
+use std::io::{stdout, Read, Write};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
-use curl::easy::Easy;
+use crossterm::execute;
+use crossterm::terminal::{
+	disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use curl::easy::{Easy, List};
+use ratatui::buffer::{Buffer, Cell};
+use ratatui::layout::Rect;
+use ratatui::widgets::Widget;
 use serde::{Deserialize, Serialize};
 use simd_json::{from_slice, to_vec};
 
+use crate::config;
+use crate::inspector::{self, Record};
+
+/// Base URL to send requests to, set by `--base-url`. Use `base_url()` to get it.
+pub static BASE_URL_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+/// Moves into the normal terminal, i.e. to run a player
+pub fn leave_tui() {
+	let _ = disable_raw_mode();
+	let _ = execute!(stdout(), LeaveAlternateScreen);
+}
+
+/// Moves back into the TUI after `leave_tui`
+pub fn enter_tui() {
+	let _ = enable_raw_mode();
+	let _ = execute!(stdout(), EnterAlternateScreen);
+}
+
+/// Returns the URL requests should be sent to, `config::BASE_URL` unless it's been overridden
+pub fn base_url() -> &'static str {
+	BASE_URL_OVERRIDE
+		.get()
+		.map_or(config::BASE_URL, String::as_str)
+}
+
+/// Language and region sent with every request
+#[derive(Clone, Debug, PartialEq)]
+pub struct Locale {
+	pub language: Option<String>,
+	pub region: Option<String>,
+	pub time_zone: Option<String>,
+	pub utc_offset_minutes: Option<i16>,
+}
+impl Locale {
+	/// Sets the region and language from the switcher's text, i.e. `JP ja` or just `JP`. Both are
+	/// left to youtube if it's empty.
+	pub fn switch(&mut self, text: &str) {
+		let mut parts = text.split_whitespace();
+
+		self.region = parts.next().map(str::to_uppercase);
+		self.language = parts.next().map(str::to_owned);
+	}
+}
+impl std::fmt::Display for Locale {
+	/// Like the switcher's text, `default` if it's left to youtube
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match (&self.region, &self.language) {
+			(None, None) => write!(f, "default"),
+			(region, language) => write!(
+				f,
+				"{} {}",
+				region.as_deref().unwrap_or("-"),
+				language.as_deref().unwrap_or("-")
+			),
+		}
+	}
+}
+
+/// Starts as the config's `LANGUAGE`, `REGION`, `TIME_ZONE` and `UTC_OFFSET_MINUTES`, use
+/// `locale()` and `set_locale()`
+static LOCALE: Mutex<Option<Locale>> = Mutex::new(None);
+
+/// The language and region requests are sent with
+pub fn locale() -> Locale {
+	LOCALE
+		.lock()
+		.unwrap_or_else(|error| error.into_inner())
+		.get_or_insert_with(|| Locale {
+			language: config::LANGUAGE.map(str::to_owned),
+			region: config::REGION.map(str::to_owned),
+			time_zone: config::TIME_ZONE.map(str::to_owned),
+			utc_offset_minutes: config::UTC_OFFSET_MINUTES,
+		})
+		.clone()
+}
+
+/// Changes the language and region later requests are sent with, i.e. from `--language` or the
+/// switcher
+pub fn set_locale(f: impl FnOnce(&mut Locale)) {
+	let mut new = locale();
+	f(&mut new);
+
+	*LOCALE.lock().unwrap_or_else(|error| error.into_inner()) = Some(new);
+}
+
+/// The home page's URL, for the region and in the language requests are sent with
+pub fn home_url() -> String {
+	let locale = locale();
+	let parameters = [("gl", locale.region), ("hl", locale.language)]
+		.into_iter()
+		.filter_map(|(name, value)| Some([name, "=", &value?].concat()))
+		.collect::<Vec<String>>();
+
+	if parameters.is_empty() {
+		base_url().to_owned()
+	} else {
+		[base_url(), "/?", &parameters.join("&")].concat()
+	}
+}
+
+/// Directory to keep things between runs in, set by `--data-dir`. Use `data_path()` to get files
+/// in it.
+pub static DATA_DIRECTORY_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Returns the path of a file in the data directory, `config::data_directory()` unless it's been
+/// overridden
+pub fn data_path(file: &str) -> PathBuf {
+	DATA_DIRECTORY_OVERRIDE
+		.get()
+		.cloned()
+		.unwrap_or_else(config::data_directory)
+		.join(file)
+}
+
+/// Writes a file in the data directory, creating the directory if it doesn't exist. Failures are
+/// ignored, the program just forgets.
+pub fn write_data(file: &str, contents: &[u8]) {
+	let path = data_path(file);
+
+	if let Some(directory) = path.parent() {
+		let _ = create_dir_all(directory);
+	}
+	let _ = write(path, contents);
+}
+
+/// Returns the URL of the search suggestions for `query`, from `config::SUGGESTIONS_URL` unless
+/// the base URL has been overridden
+pub fn suggestions_url(easy: &mut Easy, query: &str) -> String {
+	[
+		BASE_URL_OVERRIDE
+			.get()
+			.map_or(config::SUGGESTIONS_URL, String::as_str),
+		"/complete/search?client=firefox&ds=yt&q=",
+		&easy.url_encode(query.as_bytes()),
+		&locale()
+			.language
+			.map_or(String::new(), |language| ["&hl=", &language].concat()),
+	]
+	.concat()
+}
+
+/// Returns the URL of the given InnerTube endpoint, i.e. "browse"
+pub fn api_url(endpoint: &str) -> String {
+	[base_url(), "/youtubei/v1/", endpoint].concat()
+}
+
+/// Creates a new `Easy` session, set up how youtube wants it
+pub fn new_easy() -> Easy {
+	let mut easy = Easy::new();
+
+	// Enable cookie engine
+	let _ = easy.cookie_file("");
+
+	set_headers(&mut easy);
+
+	easy
+}
+
+/// Sets the headers every request is sent with, before each one since the language can change
+fn set_headers(easy: &mut Easy) {
+	let mut list = List::new();
+	// Youtube needs the header for post requests
+	let _ = list.append("CONTENT-TYPE:");
+	// For the home page, which is HTML (the API uses `hl` in the request instead)
+	if let Some(language) = locale().language {
+		let _ = list.append(&["Accept-Language: ", &language].concat());
+	}
+	let _ = easy.http_headers(list);
+}
+
+/// Adds a finished request to the request inspector
+fn record(
+	easy: &mut Easy,
+	url: &str,
+	body: Option<String>,
+	start: Instant,
+	result: Result<(), curl::Error>,
+	response: &[u8],
+) {
+	inspector::record(Record {
+		id: 0,
+		url: url.to_owned(),
+		body,
+		status: easy.response_code().unwrap_or(0),
+		error: result.err().map(|error| error.to_string()),
+		latency: start.elapsed(),
+		size: response.len(),
+		response: response.to_vec(),
+		saved_to: None,
+	});
+}
+
 /// Send a GET request and return it as a `Vec<u8>`.
 pub fn request_get(easy: &mut Easy, url: &str) -> Vec<u8> {
 	let mut vec = Vec::new();
+	let start = Instant::now();
 
+	set_headers(easy);
 	let _ = easy.url(url);
 	let _ = easy.get(true);
 
 	// Make sure `transfer` is dropped before we use can `vec` again
-	{
+	let result = {
 		let mut transfer = easy.transfer();
 
 		let _ = transfer.write_function(|slice| {
@@ -23,23 +230,28 @@ pub fn request_get(easy: &mut Easy, url: &str) -> Vec<u8> {
 			Ok(slice.len())
 		});
 
-		let _ = transfer.perform();
-	}
+		transfer.perform()
+	};
+
+	record(easy, url, None, start, result, &vec);
 
 	vec
 }
 
 /// Send a POST request and return it as a `Vec<u8>`.
 pub fn request_post(easy: &mut Easy, url: &str, json: &(impl Serialize + ?Sized)) -> Vec<u8> {
-	let mut data = &*to_vec(json).expect("Should be able to serialize POST data");
+	let body = to_vec(json).expect("Should be able to serialize POST data");
+	let mut data = &*body;
+	let start = Instant::now();
 
+	set_headers(easy);
 	let _ = easy.url(url);
 	let _ = easy.post(true);
 
 	let mut vec = Vec::new();
 
 	// Make sure `transfer` is dropped before we use can `vec` again
-	{
+	let result = {
 		let mut transfer = easy.transfer();
 
 		let _ = transfer.read_function(|slice| Ok(data.read(slice).unwrap_or(0)));
@@ -49,8 +261,17 @@ pub fn request_post(easy: &mut Easy, url: &str, json: &(impl Serialize + ?Sized)
 			Ok(slice.len())
 		});
 
-		let _ = transfer.perform();
-	}
+		transfer.perform()
+	};
+
+	record(
+		easy,
+		url,
+		Some(String::from_utf8_lossy(&body).into_owned()),
+		start,
+		result,
+		&vec,
+	);
 
 	vec
 }
@@ -70,3 +291,155 @@ pub fn extract_json<'a, J: Deserialize<'a>>(
 
 	from_slice(unsafe { string[start..end].as_bytes_mut() }).ok()
 }
+
+/// Directory that responses are saved to, set by `ytui capture`
+pub static CAPTURE_DIRECTORY: OnceLock<PathBuf> = OnceLock::new();
+
+/// If we're capturing, saves a copy of the given response to `<capture directory>/<parser>/`, to
+/// be used as a test fixture. Returns the response, so it can wrap requests.
+pub fn captured(parser: &str, extension: &str, response: Vec<u8>) -> Vec<u8> {
+	if let Some(capture_directory) = CAPTURE_DIRECTORY.get() {
+		let directory = capture_directory.join(parser);
+
+		let _ = create_dir_all(&directory);
+		let _ = write(
+			directory.join(
+				[
+					&SystemTime::now()
+						.duration_since(UNIX_EPOCH)
+						.map_or(0, |duration| duration.as_millis())
+						.to_string(),
+					".",
+					extension,
+				]
+				.concat(),
+			),
+			&response,
+		);
+	}
+
+	response
+}
+
+/// File to log to instead of the default (see `debug_log_path`), set by `--log`. Every request is
+/// logged too if this is set, otherwise only what `debug_log` is given (i.e. unsupported
+/// renderers).
+pub static LOG_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Returns the path of the debug log, `ytui.log` in the data directory unless `--log` is given
+pub fn debug_log_path() -> PathBuf {
+	LOG_FILE
+		.get()
+		.cloned()
+		.unwrap_or_else(|| data_path("ytui.log"))
+}
+
+/// Appends a line to the debug log, i.e. when youtube sends something we don't understand
+pub fn debug_log(message: &str) {
+	let path = debug_log_path();
+
+	if let Some(directory) = path.parent() {
+		let _ = create_dir_all(directory);
+	}
+	if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+		let _ = writeln!(
+			file,
+			"[{}] {message}",
+			SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.map_or(0, |duration| duration.as_secs())
+		);
+	}
+}
+
+/// The text a widget shows when it's rendered `config::BOOKMARK_WIDTH` wide and `height` high,
+/// without its styles, trailing spaces or empty lines at the end
+pub fn widget_text(widget: impl Widget, height: u16) -> String {
+	let area = Rect {
+		x: 0,
+		y: 0,
+		width: config::BOOKMARK_WIDTH,
+		height,
+	};
+	let mut buffer = Buffer::empty(area);
+	widget.render(area, &mut buffer);
+
+	// Every cell differs from this one, so the diff has every symbol that's shown, without the
+	// cells hidden behind wide characters
+	let mut lines = vec![String::new(); height as usize];
+	for (_, y, cell) in Buffer::filled(area, Cell::default().set_symbol("\0")).diff(&buffer) {
+		lines[y as usize].push_str(&cell.symbol);
+	}
+
+	for line in &mut lines {
+		line.truncate(line.trim_end().len());
+	}
+	while lines.last().is_some_and(String::is_empty) {
+		lines.pop();
+	}
+
+	lines.join("\n")
+}
+
+/// Formats milliseconds like youtube does, i.e. `1:02:03`, or `2:03` under an hour
+pub fn format_timestamp(milliseconds: u64) -> String {
+	let seconds = milliseconds / 1000;
+
+	if seconds < 3600 {
+		format!("{}:{:02}", seconds / 60, seconds % 60)
+	} else {
+		format!(
+			"{}:{:02}:{:02}",
+			seconds / 3600,
+			seconds / 60 % 60,
+			seconds % 60
+		)
+	}
+}
+
+/// Parses a timestamp like `1:02:03`, `2:03` or `3` into milliseconds
+pub fn parse_timestamp(timestamp: &str) -> Option<u64> {
+	let parts = timestamp.trim().split(':');
+
+	if parts.clone().count() > 3 {
+		return None;
+	}
+
+	parts
+		.map(|part| part.parse::<u64>().ok())
+		.try_fold(0, |total, part| Some(total * 60 + part?))
+		.map(|seconds| seconds * 1000)
+}
+
+/// Puts `items` in a random order
+pub fn shuffle<T>(items: &mut [T]) {
+	// Randomly seeded for each one, there's no need for anything better
+	let state = RandomState::new();
+
+	for i in (1..items.len()).rev() {
+		items.swap(i, (state.hash_one(i) % (i as u64 + 1)) as usize);
+	}
+}
+
+/// The current time, in seconds since the unix epoch
+pub fn now() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map_or(0, |duration| duration.as_secs())
+}
+
+/// Formats a time (in seconds since the unix epoch) like youtube does, i.e. `3 days ago`
+pub fn time_ago(time: u64) -> String {
+	let seconds = now().saturating_sub(time);
+
+	let (amount, unit) = match seconds {
+		0..=59 => return "Just now".to_owned(),
+		60..=3599 => (seconds / 60, "minute"),
+		3600..=86399 => (seconds / 3600, "hour"),
+		86400..=2_591_999 => (seconds / 86400, "day"),
+		2_592_000..=31_535_999 => (seconds / 2_592_000, "month"),
+		_ => (seconds / 31_536_000, "year"),
+	};
+
+	format!("{amount} {unit}{} ago", if amount == 1 { "" } else { "s" })
+}
@@ -0,0 +1,75 @@
+//! Videos that have been played, kept between runs so they can be dimmed in feeds, or hidden on
+//! pages they're hidden on (`u`)
+
+use std::collections::HashSet;
+use std::fs::read;
+use std::sync::Mutex;
+
+use simd_json::{from_slice, to_vec};
+
+use crate::utils::{data_path, write_data};
+
+/// File in the data directory the watched videos are kept in
+const FILE: &str = "watched.json";
+
+/// IDs of the videos that have been played, loaded from the file the first time they're needed
+static WATCHED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// The pages watched videos are hidden on (their `identity()`), until the program's closed
+static HIDDEN_ON: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// Runs `f` on the watched videos, loading them first if they haven't been. Saves them afterwards
+/// if `f` returns true.
+fn with_watched<T>(f: impl FnOnce(&mut HashSet<String>) -> (T, bool)) -> T {
+	let mut watched = WATCHED.lock().unwrap_or_else(|error| error.into_inner());
+
+	let watched = watched.get_or_insert_with(|| {
+		read(data_path(FILE))
+			.ok()
+			.and_then(|mut file| from_slice(&mut file).ok())
+			.unwrap_or_default()
+	});
+
+	let (result, changed) = f(watched);
+
+	if changed {
+		write_data(
+			FILE,
+			&to_vec(watched).expect("Should be able to serialize watched videos"),
+		);
+	}
+
+	result
+}
+
+/// Remembers that a video has been played
+pub fn mark(id: &str) {
+	with_watched(|watched| ((), watched.insert(id.to_owned())));
+}
+
+pub fn is_watched(id: &str) -> bool {
+	with_watched(|watched| (watched.contains(id), false))
+}
+
+/// Hides watched videos on a page (by its `identity()`), or shows them again. Returns whether
+/// they're hidden now.
+pub fn toggle_hidden(page: String) -> bool {
+	let mut hidden_on = HIDDEN_ON.lock().unwrap_or_else(|error| error.into_inner());
+	let hidden_on = hidden_on.get_or_insert_with(HashSet::new);
+
+	if hidden_on.remove(&page) {
+		false
+	} else {
+		hidden_on.insert(page)
+	}
+}
+
+/// Whether a video should be left out of a page (by its `identity()`)
+pub fn hidden(page: &str, id: &str) -> bool {
+	HIDDEN_ON
+		.lock()
+		.unwrap_or_else(|error| error.into_inner())
+		.as_ref()
+		.is_some_and(|hidden_on| hidden_on.contains(page))
+		&& is_watched(id)
+}
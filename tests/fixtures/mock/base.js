@@ -0,0 +1,3 @@
+var X=function(a){var b=a.split("");b.reverse();return b.join("")};
+gX=1;
+var Y=function(a){a=a.split("");VF.wa(a);return a.join("")};